@@ -0,0 +1,45 @@
+use anchor_spl::token::spl_token;
+use anyhow::Result;
+use clap::Subcommand;
+use client::{airdrop, pda};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::config::Config;
+
+#[derive(Subcommand)]
+pub enum AirdropCommand {
+    /// Create the airdrop and staking vaults of a mint (protocol admin only)
+    InitVaults {
+        /// Mint paid out by airdrops and accepted for staking
+        mint: Pubkey,
+    },
+    /// Transfer tokens from one of the payer's token accounts into the airdrop vault
+    Fund {
+        /// Source token account owned by the payer
+        #[arg(long)]
+        source: Pubkey,
+        amount: u64,
+    },
+}
+
+pub fn run(config: &Config, command: AirdropCommand) -> Result<()> {
+    match command {
+        AirdropCommand::InitVaults { mint } => {
+            let ix = airdrop::initialize_vaults(&config.payer.pubkey(), &mint);
+            config.execute(&[ix], &[])
+        }
+        AirdropCommand::Fund { source, amount } => {
+            let payer = config.payer.pubkey();
+            let ix = spl_token::instruction::transfer(
+                &spl_token::ID,
+                &source,
+                &pda::airdrop_vault().0,
+                &payer,
+                &[],
+                amount,
+            )?;
+            config.execute(&[ix], &[])
+        }
+    }
+}
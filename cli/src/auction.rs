@@ -0,0 +1,120 @@
+use anyhow::Result;
+use clap::Subcommand;
+use client::auction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::config::Config;
+
+#[derive(Subcommand)]
+pub enum AuctionCommand {
+    /// Put an NFT up for auction
+    Create {
+        /// Seller's token account holding the NFT
+        #[arg(long)]
+        nft_token_account: Pubkey,
+        #[arg(long)]
+        nft_mint: Pubkey,
+        /// Mint bids are paid in
+        #[arg(long)]
+        bid_mint: Pubkey,
+        #[arg(long)]
+        start_price: u64,
+        #[arg(long)]
+        reserve_price: u64,
+        #[arg(long)]
+        buy_now_price: Option<u64>,
+        /// Auction length in seconds
+        #[arg(long)]
+        duration: i64,
+        /// Platform fee in percent
+        #[arg(long, default_value_t = 0)]
+        platform_fee: u64,
+    },
+    /// Settle an auction whose end time has passed
+    End {
+        auction: Pubkey,
+        #[arg(long)]
+        seller_token_account: Pubkey,
+        #[arg(long)]
+        highest_bidder_nft_account: Pubkey,
+        #[arg(long)]
+        platform_account: Pubkey,
+    },
+    /// Cancel an auction that has no bids
+    Cancel {
+        auction: Pubkey,
+        #[arg(long)]
+        seller_nft_account: Pubkey,
+    },
+}
+
+pub fn run(config: &Config, command: AuctionCommand) -> Result<()> {
+    let seller = config.payer.pubkey();
+    match command {
+        AuctionCommand::Create {
+            nft_token_account,
+            nft_mint,
+            bid_mint,
+            start_price,
+            reserve_price,
+            buy_now_price,
+            duration,
+            platform_fee,
+        } => {
+            let auction_account = Keypair::new();
+            let address = auction_account.pubkey();
+            println!("Creating auction {address}");
+            let ix = auction::create_auction(
+                &seller,
+                &nft_token_account,
+                &auction::vault_address(&address, &nft_mint),
+                &bid_mint,
+                &auction::vault_address(&address, &bid_mint),
+                &address,
+                start_price,
+                reserve_price,
+                buy_now_price,
+                duration,
+                platform_fee,
+            );
+            let instructions = [
+                auction::create_vault(&seller, &address, &nft_mint),
+                auction::create_vault(&seller, &address, &bid_mint),
+                ix,
+            ];
+            config.execute(&instructions, &[&auction_account])
+        }
+        AuctionCommand::End {
+            auction,
+            seller_token_account,
+            highest_bidder_nft_account,
+            platform_account,
+        } => {
+            let account = auction::fetch_auction(&config.rpc, &auction)?;
+            let ix = auction::end_auction(
+                &seller,
+                &seller_token_account,
+                &highest_bidder_nft_account,
+                &platform_account,
+                &account.auction_vault,
+                &account.bid_vault,
+                &auction,
+            );
+            config.execute(&[ix], &[])
+        }
+        AuctionCommand::Cancel {
+            auction,
+            seller_nft_account,
+        } => {
+            let account = auction::fetch_auction(&config.rpc, &auction)?;
+            let ix = auction::cancel_auction(
+                &seller,
+                &seller_nft_account,
+                &account.auction_vault,
+                &auction,
+            );
+            config.execute(&[ix], &[])
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use anyhow::{anyhow, bail, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::clock::Clock;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+
+pub struct Config {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub dry_run: bool,
+}
+
+impl Config {
+    pub fn new(url: &str, keypair: &str, dry_run: bool) -> Result<Self> {
+        let path = match keypair.strip_prefix("~/") {
+            Some(rest) => format!("{}/{}", std::env::var("HOME")?, rest),
+            None => keypair.to_string(),
+        };
+        let payer = read_keypair_file(&path)
+            .map_err(|err| anyhow!("failed to read keypair {path}: {err}"))?;
+
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed()),
+            payer,
+            dry_run,
+        })
+    }
+
+    /// Signs `instructions` with the payer and `signers`, then sends or simulates them.
+    pub fn execute(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+        let mut all_signers: Vec<&Keypair> = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+
+        if self.dry_run {
+            let result = self.rpc.simulate_transaction(&tx)?.value;
+            for log in result.logs.unwrap_or_default() {
+                println!("  {log}");
+            }
+            if let Some(err) = result.err {
+                bail!("simulation failed: {err}");
+            }
+            println!("Simulation succeeded");
+        } else {
+            let signature = self.rpc.send_and_confirm_transaction(&tx)?;
+            println!("Signature: {signature}");
+        }
+        Ok(())
+    }
+
+    /// Current cluster time, as seen by the programs.
+    pub fn unix_timestamp(&self) -> Result<i64> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+        let clock: Clock = solana_sdk::account::from_account(&account)
+            .ok_or_else(|| anyhow!("failed to decode the clock sysvar"))?;
+        Ok(clock.unix_timestamp)
+    }
+}
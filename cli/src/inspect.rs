@@ -0,0 +1,122 @@
+use anyhow::Result;
+use clap::Subcommand;
+use client::{auction, config as protocol, farm, vesting};
+use protocol_config::Module;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::Config;
+
+#[derive(Subcommand)]
+pub enum InspectCommand {
+    /// Show a `VestingAccount` and what it can claim now
+    Vesting { address: Pubkey },
+    /// Show an `Auction`
+    Auction { address: Pubkey },
+    /// Show a farming `UserInfo`
+    UserInfo { address: Pubkey },
+    /// Show the `ProtocolConfig` and which modules are paused
+    Config,
+}
+
+fn field(name: &str, value: impl std::fmt::Display) {
+    println!("{name:<24}{value}");
+}
+
+pub fn run(config: &Config, command: InspectCommand) -> Result<()> {
+    match command {
+        InspectCommand::Vesting { address } => {
+            let account = vesting::fetch_vesting_account(&config.rpc, &address)?;
+            let now = config.unix_timestamp()?;
+            field("version", account.version);
+            field("beneficiary", account.beneficiary);
+            field("original_beneficiary", account.original_beneficiary);
+            field("mint", account.mint);
+            field("schedule_id", account.schedule_id);
+            field("admin", account.admin);
+            field("total_amount", account.total_amount);
+            field("claimed_amount", account.claimed_amount);
+            field("staked_amount", account.staked_amount);
+            field("start_time", account.start_time);
+            field("cliff_time", account.cliff_time);
+            field("vesting_end_time", account.vesting_end_time);
+            field("kind", format!("{:?}", account.kind));
+            field("revocable", account.revocable);
+            field("revoked", account.revoked);
+            field(
+                "pending_beneficiary",
+                format!("{:?}", account.pending_beneficiary),
+            );
+            field("claim_delegate", format!("{:?}", account.claim_delegate));
+            field("campaign", format!("{:?}", account.campaign));
+            let view = vesting::project_vesting(&account, now)?;
+            field("claimable_now", view.claimable);
+            field(
+                "next_unlock_time",
+                view.next_unlock_time
+                    .map_or("none".to_string(), |time| time.to_string()),
+            );
+            field(
+                "governance_weight",
+                token_vesting::governance_weight(&account, now)?,
+            );
+        }
+        InspectCommand::Auction { address } => {
+            let account = auction::fetch_auction(&config.rpc, &address)?;
+            field("version", account.version);
+            field("seller", account.seller);
+            field("nft_token_account", account.nft_token_account);
+            field("start_price", account.start_price);
+            field("reserve_price", account.reserve_price);
+            field(
+                "buy_now_price",
+                account
+                    .buy_now_price
+                    .map_or("none".to_string(), |price| price.to_string()),
+            );
+            field("highest_bid", account.highest_bid);
+            field("highest_bidder", account.highest_bidder);
+            field("end_time", account.end_time);
+            field("is_active", account.is_active);
+            field("platform_fee", format!("{}%", account.platform_fee));
+            field("auction_vault", account.auction_vault);
+            field("bid_mint", account.bid_mint);
+            field("bid_vault", account.bid_vault);
+        }
+        InspectCommand::UserInfo { address } => {
+            let account = farm::fetch_user_info(&config.rpc, &address)?;
+            field("version", account.version);
+            field("owner", account.owner);
+            field("token_mint", account.token_mint);
+            field("deposited_amount", account.krpza_deposited_amount);
+            field("last_deposit_time", account.last_deposit_time);
+            field("next_claim_time", account.next_claim_time);
+            field("claim_interval", account.claim_interval);
+            field("month_count", account.month_count);
+        }
+        InspectCommand::Config => {
+            let account = protocol::fetch_config(&config.rpc)?;
+            field("version", account.version);
+            field("admin", account.admin);
+            field(
+                "pending_admin",
+                account
+                    .pending_admin
+                    .map_or("none".to_string(), |admin| admin.to_string()),
+            );
+            field("fee_treasury", account.fee_treasury);
+            for module in [
+                Module::Vesting,
+                Module::Farming,
+                Module::Auction,
+                Module::Marketplace,
+                Module::Staking,
+            ] {
+                field(
+                    &format!("{module:?} paused").to_lowercase(),
+                    account.is_paused(module),
+                );
+            }
+        }
+    }
+    Ok(())
+}
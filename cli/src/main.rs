@@ -0,0 +1,74 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod airdrop;
+mod auction;
+mod config;
+mod inspect;
+mod marketplace;
+mod protocol;
+mod vesting;
+
+use config::Config;
+
+#[derive(Parser)]
+#[command(name = "cli", about = "Operate the deployed workspace programs")]
+struct Cli {
+    /// JSON RPC endpoint of the cluster
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+    /// Keypair that pays for and signs transactions
+    #[arg(
+        long,
+        short = 'k',
+        global = true,
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: String,
+    /// Simulate transactions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create and query vesting schedules
+    #[command(subcommand)]
+    Vesting(vesting::VestingCommand),
+    /// Manage the airdrop vault
+    #[command(subcommand)]
+    Airdrop(airdrop::AirdropCommand),
+    /// Create, end and cancel auctions
+    #[command(subcommand)]
+    Auction(auction::AuctionCommand),
+    /// List and delist NFTs on the marketplace
+    #[command(subcommand)]
+    Nft(marketplace::NftCommand),
+    /// Pause modules and manage the protocol admin
+    #[command(subcommand)]
+    Protocol(protocol::ProtocolCommand),
+    /// Print program accounts in human-readable form
+    #[command(subcommand)]
+    Inspect(inspect::InspectCommand),
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = Config::new(&cli.url, &cli.keypair, cli.dry_run)?;
+
+    match cli.command {
+        Command::Vesting(command) => vesting::run(&config, command),
+        Command::Airdrop(command) => airdrop::run(&config, command),
+        Command::Auction(command) => auction::run(&config, command),
+        Command::Nft(command) => marketplace::run(&config, command),
+        Command::Protocol(command) => protocol::run(&config, command),
+        Command::Inspect(command) => inspect::run(&config, command),
+    }
+}
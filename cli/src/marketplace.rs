@@ -0,0 +1,42 @@
+use anyhow::Result;
+use clap::Subcommand;
+use client::marketplace;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+use crate::config::Config;
+
+#[derive(Subcommand)]
+pub enum NftCommand {
+    /// List an NFT for sale at a fixed price in lamports
+    List {
+        mint: Pubkey,
+        price: u64,
+        /// Token account of the payer holding the NFT
+        #[arg(long)]
+        token_account: Pubkey,
+    },
+    /// Remove a listing and reclaim its rent
+    Delist { listing: Pubkey },
+}
+
+pub fn run(config: &Config, command: NftCommand) -> Result<()> {
+    let seller = config.payer.pubkey();
+    match command {
+        NftCommand::List {
+            mint,
+            price,
+            token_account,
+        } => {
+            let listing = Keypair::new();
+            println!("Creating listing {}", listing.pubkey());
+            let ix =
+                marketplace::list_nft(&seller, &mint, &token_account, &listing.pubkey(), price);
+            config.execute(&[ix], &[&listing])
+        }
+        NftCommand::Delist { listing } => {
+            let ix = marketplace::delist_nft(&seller, &listing);
+            config.execute(&[ix], &[])
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use clap::{Subcommand, ValueEnum};
+use client::config as protocol;
+use protocol_config::Module;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+use crate::config::Config;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ModuleArg {
+    Vesting,
+    Farming,
+    Auction,
+    Marketplace,
+    Staking,
+}
+
+impl From<ModuleArg> for Module {
+    fn from(module: ModuleArg) -> Self {
+        match module {
+            ModuleArg::Vesting => Module::Vesting,
+            ModuleArg::Farming => Module::Farming,
+            ModuleArg::Auction => Module::Auction,
+            ModuleArg::Marketplace => Module::Marketplace,
+            ModuleArg::Staking => Module::Staking,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum ProtocolCommand {
+    /// Create the protocol config with the payer as admin
+    Init {
+        #[arg(long)]
+        fee_treasury: Pubkey,
+    },
+    /// Stop all token movements of a module
+    Pause { module: ModuleArg },
+    /// Resume a paused module
+    Unpause { module: ModuleArg },
+    /// Start handing the admin role over to another key
+    ProposeAdmin { new_admin: Pubkey },
+    /// Take over the admin role proposed to the payer
+    AcceptAdmin,
+    /// Change where protocol fees are sent
+    SetTreasury { fee_treasury: Pubkey },
+}
+
+pub fn run(config: &Config, command: ProtocolCommand) -> Result<()> {
+    let admin = config.payer.pubkey();
+    let ix = match command {
+        ProtocolCommand::Init { fee_treasury } => {
+            protocol::initialize_config(&admin, &fee_treasury)
+        }
+        ProtocolCommand::Pause { module } => protocol::set_paused(&admin, module.into(), true),
+        ProtocolCommand::Unpause { module } => protocol::set_paused(&admin, module.into(), false),
+        ProtocolCommand::ProposeAdmin { new_admin } => protocol::propose_admin(&admin, &new_admin),
+        ProtocolCommand::AcceptAdmin => protocol::accept_admin(&admin),
+        ProtocolCommand::SetTreasury { fee_treasury } => {
+            protocol::set_fee_treasury(&admin, &fee_treasury)
+        }
+    };
+    config.execute(&[ix], &[])
+}
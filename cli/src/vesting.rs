@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Subcommand;
+use client::campaign::CampaignTree;
+use client::{fetch_token_program, pda, vesting};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use token_vesting::VestingKind;
+
+use crate::config::Config;
+
+#[derive(Subcommand)]
+pub enum VestingCommand {
+    /// Create one schedule per row of a CSV file with the columns
+    /// `beneficiary,schedule_id,total_amount,cliff_duration,vesting_duration`
+    /// and optional `start_time`, `revocable` and `kind` columns. Schedules without a
+    /// `start_time` start now and `kind` is `linear`, `periodic:<tranches>` or
+    /// `cliff:<basis points>`
+    CreateFromCsv {
+        csv: PathBuf,
+        /// Token account the vested tokens are taken from
+        #[arg(long)]
+        admin_token_account: Pubkey,
+        /// Mint of the vested token
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Fund a campaign for the grants in a CSV file with the columns
+    /// `beneficiary,amount,cliff_time,vesting_end_time`, which beneficiaries then claim
+    CreateCampaign {
+        csv: PathBuf,
+        #[arg(long)]
+        campaign_id: u64,
+        /// Token account the campaign is funded from
+        #[arg(long)]
+        admin_token_account: Pubkey,
+        /// Mint of the vested token
+        #[arg(long)]
+        mint: Pubkey,
+        /// Start of every schedule, defaults to now
+        #[arg(long)]
+        start_time: Option<i64>,
+        #[arg(long)]
+        revocable: bool,
+    },
+    /// Revoke a schedule, sending its unvested tokens to the treasury
+    Revoke {
+        beneficiary: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        schedule_id: u64,
+        /// Token account of the protocol fee treasury
+        #[arg(long)]
+        treasury_token_account: Pubkey,
+    },
+    /// Print the amount a schedule can claim right now
+    Claimable { vesting_account: Pubkey },
+}
+
+#[derive(Deserialize)]
+struct ScheduleRow {
+    beneficiary: String,
+    schedule_id: u64,
+    total_amount: u64,
+    cliff_duration: i64,
+    vesting_duration: i64,
+    #[serde(default)]
+    start_time: Option<i64>,
+    #[serde(default)]
+    revocable: bool,
+    #[serde(default)]
+    kind: Option<String>,
+}
+
+fn parse_kind(kind: &str) -> Result<VestingKind> {
+    let parsed = match kind.split_once(':') {
+        None if kind == "linear" => VestingKind::Linear,
+        Some(("periodic", tranches)) => VestingKind::Periodic {
+            tranches: tranches.parse()?,
+        },
+        Some(("cliff", cliff_bps)) => VestingKind::CliffPercentage {
+            cliff_bps: cliff_bps.parse()?,
+        },
+        _ => bail!("unknown vesting kind {kind:?}"),
+    };
+    Ok(parsed)
+}
+
+pub fn run(config: &Config, command: VestingCommand) -> Result<()> {
+    match command {
+        VestingCommand::CreateFromCsv {
+            csv,
+            admin_token_account,
+            mint,
+        } => {
+            let mut reader = csv::Reader::from_path(&csv)
+                .with_context(|| format!("failed to open {}", csv.display()))?;
+            let now = config.unix_timestamp()?;
+            let token_program = fetch_token_program(&config.rpc, &mint)?;
+            for (line, row) in reader.deserialize::<ScheduleRow>().enumerate() {
+                let row = row.with_context(|| format!("invalid row {}", line + 1))?;
+                let beneficiary: Pubkey = row
+                    .beneficiary
+                    .parse()
+                    .with_context(|| format!("invalid beneficiary on row {}", line + 1))?;
+                let kind = parse_kind(row.kind.as_deref().unwrap_or("linear"))
+                    .with_context(|| format!("invalid kind on row {}", line + 1))?;
+                let (vesting_account, _) = pda::vesting(&mint, &beneficiary, row.schedule_id);
+
+                println!(
+                    "Creating schedule {vesting_account} for {beneficiary} ({} tokens)",
+                    row.total_amount
+                );
+                let ix = vesting::initialize_vesting(
+                    &config.payer.pubkey(),
+                    &admin_token_account,
+                    &mint,
+                    &token_program,
+                    &beneficiary,
+                    row.schedule_id,
+                    row.start_time.unwrap_or(now),
+                    row.cliff_duration,
+                    row.vesting_duration,
+                    row.total_amount,
+                    row.revocable,
+                    kind,
+                );
+                config.execute(&[ix], &[])?;
+            }
+            Ok(())
+        }
+        VestingCommand::CreateCampaign {
+            csv,
+            campaign_id,
+            admin_token_account,
+            mint,
+            start_time,
+            revocable,
+        } => {
+            let file = std::fs::File::open(&csv)
+                .with_context(|| format!("failed to open {}", csv.display()))?;
+            let tree = CampaignTree::from_csv(file)?;
+            let (campaign, _) = pda::vesting_campaign(&mint, campaign_id);
+            println!(
+                "Creating campaign {campaign} for {} grants ({} tokens)",
+                tree.grants().len(),
+                tree.total_amount()
+            );
+            let ix = vesting::create_campaign(
+                &config.payer.pubkey(),
+                &admin_token_account,
+                &mint,
+                &fetch_token_program(&config.rpc, &mint)?,
+                campaign_id,
+                tree.root(),
+                start_time.map_or_else(|| config.unix_timestamp(), Ok)?,
+                tree.total_amount(),
+                revocable,
+            );
+            config.execute(&[ix], &[])
+        }
+        VestingCommand::Revoke {
+            beneficiary,
+            mint,
+            schedule_id,
+            treasury_token_account,
+        } => {
+            let ix = vesting::revoke_vesting(
+                &config.payer.pubkey(),
+                &mint,
+                &fetch_token_program(&config.rpc, &mint)?,
+                &beneficiary,
+                schedule_id,
+                &treasury_token_account,
+            );
+            config.execute(&[ix], &[])
+        }
+        VestingCommand::Claimable { vesting_account } => {
+            let account = vesting::fetch_vesting_account(&config.rpc, &vesting_account)?;
+            let now = config.unix_timestamp()?;
+            println!("{}", vesting::claimable_amount(&account, now));
+            Ok(())
+        }
+    }
+}
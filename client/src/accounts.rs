@@ -0,0 +1,24 @@
+use anchor_lang::AccountDeserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::ClientError;
+
+/// Decodes raw account data, checking the Anchor discriminator of `T`.
+pub fn deserialize_account<T: AccountDeserialize>(data: &[u8]) -> Result<T, ClientError> {
+    Ok(T::try_deserialize(&mut &data[..])?)
+}
+
+/// Fetches `address` and decodes it as `T`.
+pub fn fetch_account<T: AccountDeserialize>(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<T, ClientError> {
+    let data = rpc.get_account_data(address)?;
+    deserialize_account(&data)
+}
+
+/// Token program that owns `mint`, SPL Token or Token-2022.
+pub fn fetch_token_program(rpc: &RpcClient, mint: &Pubkey) -> Result<Pubkey, ClientError> {
+    Ok(rpc.get_account(mint)?.owner)
+}
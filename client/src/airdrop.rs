@@ -0,0 +1,97 @@
+//! Builders for the `staking_airdrop` program.
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{system_program, sysvar};
+use staking_airdrop::{accounts, instruction, UserState};
+
+use crate::{fetch_account, instruction, pda, ClientError};
+
+/// Creates the airdrop and staking vaults of `mint`. `admin` must be the protocol admin.
+pub fn initialize_vaults(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+    instruction(
+        staking_airdrop::ID,
+        accounts::InitializeVaults {
+            admin: *admin,
+            config: pda::protocol_config().0,
+            mint: *mint,
+            airdrop_vault: pda::airdrop_vault().0,
+            airdrop_authority: pda::airdrop_authority().0,
+            staking_vault: pda::staking_vault().0,
+            staking_authority: pda::staking_authority().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::InitializeVaults {},
+    )
+}
+
+pub fn claim_airdrop(user: &Pubkey, user_token_account: &Pubkey) -> Instruction {
+    instruction(
+        staking_airdrop::ID,
+        accounts::ClaimAirdrop {
+            user: *user,
+            airdrop_vault: pda::airdrop_vault().0,
+            user_token_account: *user_token_account,
+            airdrop_authority: pda::airdrop_authority().0,
+            user_state: pda::user_state(user).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::ClaimAirdrop {},
+    )
+}
+
+pub fn stake_tokens(user: &Pubkey, user_token_account: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        staking_airdrop::ID,
+        accounts::StakeTokens {
+            user: *user,
+            user_token_account: *user_token_account,
+            staking_vault: pda::staking_vault().0,
+            staking_authority: pda::staking_authority().0,
+            user_state: pda::user_state(user).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::StakeTokens { amount },
+    )
+}
+
+pub fn unstake_tokens(user: &Pubkey, user_token_account: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        staking_airdrop::ID,
+        accounts::UnstakeTokens {
+            user: *user,
+            staking_vault: pda::staking_vault().0,
+            user_token_account: *user_token_account,
+            staking_authority: pda::staking_authority().0,
+            user_state: pda::user_state(user).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::UnstakeTokens { amount },
+    )
+}
+
+pub fn migrate_user_state(user: &Pubkey) -> Instruction {
+    instruction(
+        staking_airdrop::ID,
+        accounts::MigrateUserState {
+            user: *user,
+            user_state: pda::user_state(user).0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateUserState {},
+    )
+}
+
+pub fn fetch_user_state(rpc: &RpcClient, user: &Pubkey) -> Result<UserState, ClientError> {
+    fetch_account(rpc, &pda::user_state(user).0)
+}
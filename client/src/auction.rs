@@ -0,0 +1,185 @@
+//! Builders for the `advanced_auction` program.
+
+use advanced_auction::{accounts, instruction, Auction};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{system_program, sysvar};
+
+use crate::{fetch_account, instruction, pda, ClientError};
+
+/// Associated token account of the auction's authority for `mint`, usable as its NFT or
+/// bid vault.
+pub fn vault_address(auction: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&pda::auction_authority(auction).0, mint)
+}
+
+/// Creates [`vault_address`] unless it already exists.
+pub fn create_vault(payer: &Pubkey, auction: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: associated_token::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(vault_address(auction, mint), false),
+            AccountMeta::new_readonly(pda::auction_authority(auction).0, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+        ],
+        // `CreateIdempotent`
+        data: vec![1],
+    }
+}
+
+/// `auction` is a fresh keypair that must also sign the transaction. `auction_vault` and
+/// `bid_vault` must be owned by [`pda::auction_authority`] of `auction`; bids are paid in
+/// `bid_mint`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_auction(
+    seller: &Pubkey,
+    nft_token_account: &Pubkey,
+    auction_vault: &Pubkey,
+    bid_mint: &Pubkey,
+    bid_vault: &Pubkey,
+    auction: &Pubkey,
+    start_price: u64,
+    reserve_price: u64,
+    buy_now_price: Option<u64>,
+    duration: i64,
+    platform_fee: u64,
+) -> Instruction {
+    instruction(
+        advanced_auction::ID,
+        accounts::CreateAuction {
+            seller: *seller,
+            nft_token_account: *nft_token_account,
+            auction_vault: *auction_vault,
+            bid_mint: *bid_mint,
+            bid_vault: *bid_vault,
+            auction: *auction,
+            auction_authority: pda::auction_authority(auction).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateAuction {
+            start_price,
+            reserve_price,
+            buy_now_price,
+            duration,
+            platform_fee,
+        },
+    )
+}
+
+/// `highest_bidder_token_account` receives the refund of the bid being outbid.
+pub fn place_bid(
+    bidder: &Pubkey,
+    bidder_token_account: &Pubkey,
+    highest_bidder_token_account: &Pubkey,
+    bid_vault: &Pubkey,
+    auction: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        advanced_auction::ID,
+        accounts::PlaceBid {
+            bidder: *bidder,
+            bidder_token_account: *bidder_token_account,
+            highest_bidder_token_account: *highest_bidder_token_account,
+            bid_vault: *bid_vault,
+            auction: *auction,
+            auction_authority: pda::auction_authority(auction).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::PlaceBid { amount },
+    )
+}
+
+pub fn withdraw_bid(
+    bidder: &Pubkey,
+    bidder_token_account: &Pubkey,
+    bid_vault: &Pubkey,
+    auction: &Pubkey,
+) -> Instruction {
+    instruction(
+        advanced_auction::ID,
+        accounts::WithdrawBid {
+            bidder: *bidder,
+            bidder_token_account: *bidder_token_account,
+            bid_vault: *bid_vault,
+            auction: *auction,
+            auction_authority: pda::auction_authority(auction).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::WithdrawBid {},
+    )
+}
+
+pub fn cancel_auction(
+    seller: &Pubkey,
+    seller_nft_account: &Pubkey,
+    auction_vault: &Pubkey,
+    auction: &Pubkey,
+) -> Instruction {
+    instruction(
+        advanced_auction::ID,
+        accounts::CancelAuction {
+            seller: *seller,
+            seller_nft_account: *seller_nft_account,
+            auction_vault: *auction_vault,
+            auction: *auction,
+            auction_authority: pda::auction_authority(auction).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::CancelAuction {},
+    )
+}
+
+pub fn end_auction(
+    seller: &Pubkey,
+    seller_token_account: &Pubkey,
+    highest_bidder_nft_account: &Pubkey,
+    platform_account: &Pubkey,
+    auction_vault: &Pubkey,
+    bid_vault: &Pubkey,
+    auction: &Pubkey,
+) -> Instruction {
+    instruction(
+        advanced_auction::ID,
+        accounts::EndAuction {
+            seller: *seller,
+            seller_token_account: *seller_token_account,
+            highest_bidder_nft_account: *highest_bidder_nft_account,
+            platform_account: *platform_account,
+            auction_vault: *auction_vault,
+            bid_vault: *bid_vault,
+            auction: *auction,
+            auction_authority: pda::auction_authority(auction).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::EndAuction {},
+    )
+}
+
+pub fn migrate_auction(seller: &Pubkey, auction: &Pubkey) -> Instruction {
+    instruction(
+        advanced_auction::ID,
+        accounts::MigrateAuction {
+            seller: *seller,
+            auction: *auction,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAuction {},
+    )
+}
+
+pub fn fetch_auction(rpc: &RpcClient, address: &Pubkey) -> Result<Auction, ClientError> {
+    fetch_account(rpc, address)
+}
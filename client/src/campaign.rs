@@ -0,0 +1,103 @@
+//! Merkle trees of `token_vesting` campaign grants.
+
+use std::collections::HashSet;
+use std::io::Read;
+
+use common::merkle::MerkleTree;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::ClientError;
+
+/// One beneficiary's grant, hashed into a campaign leaf with `token_vesting::campaign_leaf`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+}
+
+impl Grant {
+    pub fn leaf(&self) -> [u8; 32] {
+        token_vesting::campaign_leaf(
+            &self.beneficiary,
+            self.amount,
+            self.cliff_time,
+            self.vesting_end_time,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct GrantRow {
+    beneficiary: String,
+    amount: u64,
+    cliff_time: i64,
+    vesting_end_time: i64,
+}
+
+/// The grants of a campaign in leaf order, with the tree built over them.
+pub struct CampaignTree {
+    grants: Vec<Grant>,
+    tree: MerkleTree,
+}
+
+impl CampaignTree {
+    /// Builds the tree, rejecting beneficiaries listed twice since each can claim once.
+    pub fn new(grants: Vec<Grant>) -> Result<Self, ClientError> {
+        let mut seen = HashSet::new();
+        if let Some(grant) = grants.iter().find(|grant| !seen.insert(grant.beneficiary)) {
+            return Err(ClientError::InvalidGrant(format!(
+                "{} is listed more than once",
+                grant.beneficiary
+            )));
+        }
+        let tree = MerkleTree::new(grants.iter().map(Grant::leaf).collect());
+        Ok(Self { grants, tree })
+    }
+
+    /// Reads grants from a CSV with the columns
+    /// `beneficiary,amount,cliff_time,vesting_end_time`.
+    pub fn from_csv(reader: impl Read) -> Result<Self, ClientError> {
+        let mut grants = Vec::new();
+        for (line, row) in csv::Reader::from_reader(reader)
+            .deserialize::<GrantRow>()
+            .enumerate()
+        {
+            let row = row?;
+            let beneficiary = row.beneficiary.parse().map_err(|_| {
+                ClientError::InvalidGrant(format!("invalid beneficiary on row {}", line + 1))
+            })?;
+            grants.push(Grant {
+                beneficiary,
+                amount: row.amount,
+                cliff_time: row.cliff_time,
+                vesting_end_time: row.vesting_end_time,
+            });
+        }
+        Self::new(grants)
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.root()
+    }
+
+    pub fn grants(&self) -> &[Grant] {
+        &self.grants
+    }
+
+    /// Amount the campaign vault must be funded with.
+    pub fn total_amount(&self) -> u64 {
+        self.grants.iter().map(|grant| grant.amount).sum()
+    }
+
+    /// The beneficiary's grant and the proof `claim_vesting` needs for it.
+    pub fn proof(&self, beneficiary: &Pubkey) -> Option<(&Grant, Vec<[u8; 32]>)> {
+        let index = self
+            .grants
+            .iter()
+            .position(|grant| grant.beneficiary == *beneficiary)?;
+        Some((&self.grants[index], self.tree.proof(index)))
+    }
+}
@@ -0,0 +1,73 @@
+//! Builders for the `protocol_config` program.
+
+use protocol_config::{accounts, instruction, Module, ProtocolConfig};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::{fetch_account, instruction, pda, ClientError};
+
+pub fn initialize_config(admin: &Pubkey, fee_treasury: &Pubkey) -> Instruction {
+    instruction(
+        protocol_config::ID,
+        accounts::InitializeConfig {
+            config: pda::protocol_config().0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            fee_treasury: *fee_treasury,
+        },
+    )
+}
+
+pub fn set_paused(admin: &Pubkey, module: Module, paused: bool) -> Instruction {
+    instruction(
+        protocol_config::ID,
+        update_accounts(admin),
+        instruction::SetPaused { module, paused },
+    )
+}
+
+pub fn propose_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    instruction(
+        protocol_config::ID,
+        update_accounts(admin),
+        instruction::ProposeAdmin {
+            new_admin: *new_admin,
+        },
+    )
+}
+
+pub fn accept_admin(new_admin: &Pubkey) -> Instruction {
+    instruction(
+        protocol_config::ID,
+        accounts::AcceptAdmin {
+            config: pda::protocol_config().0,
+            new_admin: *new_admin,
+        },
+        instruction::AcceptAdmin {},
+    )
+}
+
+pub fn set_fee_treasury(admin: &Pubkey, fee_treasury: &Pubkey) -> Instruction {
+    instruction(
+        protocol_config::ID,
+        update_accounts(admin),
+        instruction::SetFeeTreasury {
+            fee_treasury: *fee_treasury,
+        },
+    )
+}
+
+pub fn fetch_config(rpc: &RpcClient) -> Result<ProtocolConfig, ClientError> {
+    fetch_account(rpc, &pda::protocol_config().0)
+}
+
+fn update_accounts(admin: &Pubkey) -> accounts::UpdateConfig {
+    accounts::UpdateConfig {
+        config: pda::protocol_config().0,
+        admin: *admin,
+    }
+}
@@ -0,0 +1,131 @@
+//! Builders for the `my_solana_project` counter program.
+
+use my_solana_project::{accounts, instruction, BaseAccount, CounterRegistry};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::{fetch_account, instruction, pda, ClientError};
+
+pub fn initialize_registry(user: &Pubkey) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        accounts::InitializeRegistry {
+            registry: pda::counter_registry().0,
+            user: *user,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeRegistry {},
+    )
+}
+
+pub fn initialize(authority: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        accounts::Initialize {
+            base_account: pda::counter(authority, name).0,
+            registry: pda::counter_registry().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {
+            name: name.to_string(),
+        },
+    )
+}
+
+fn update_counter(authority: &Pubkey, name: &str) -> accounts::UpdateCounter {
+    accounts::UpdateCounter {
+        base_account: pda::counter(authority, name).0,
+        authority: *authority,
+    }
+}
+
+pub fn increment(authority: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        update_counter(authority, name),
+        instruction::Increment {},
+    )
+}
+
+pub fn increment_by(authority: &Pubkey, name: &str, amount: u64) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        update_counter(authority, name),
+        instruction::IncrementBy { amount },
+    )
+}
+
+pub fn next_id(authority: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        update_counter(authority, name),
+        instruction::NextId {},
+    )
+}
+
+pub fn decrement(authority: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        update_counter(authority, name),
+        instruction::Decrement {},
+    )
+}
+
+pub fn reset(authority: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        update_counter(authority, name),
+        instruction::Reset {},
+    )
+}
+
+pub fn close_counter(authority: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        accounts::CloseCounter {
+            base_account: pda::counter(authority, name).0,
+            registry: pda::counter_registry().0,
+            authority: *authority,
+        },
+        instruction::CloseCounter {},
+    )
+}
+
+pub fn migrate_counter(authority: &Pubkey, base_account: &Pubkey) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        accounts::MigrateCounter {
+            base_account: *base_account,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateCounter {},
+    )
+}
+
+pub fn migrate_registry(user: &Pubkey) -> Instruction {
+    instruction(
+        my_solana_project::ID,
+        accounts::MigrateRegistry {
+            registry: pda::counter_registry().0,
+            user: *user,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateRegistry {},
+    )
+}
+
+pub fn fetch_counter(
+    rpc: &RpcClient,
+    authority: &Pubkey,
+    name: &str,
+) -> Result<BaseAccount, ClientError> {
+    fetch_account(rpc, &pda::counter(authority, name).0)
+}
+
+pub fn fetch_registry(rpc: &RpcClient) -> Result<CounterRegistry, ClientError> {
+    fetch_account(rpc, &pda::counter_registry().0)
+}
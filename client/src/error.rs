@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("rpc request failed: {0}")]
+    Rpc(Box<solana_client::client_error::ClientError>),
+    #[error("account could not be deserialized: {0}")]
+    Deserialize(#[from] anchor_lang::error::Error),
+    #[error("campaign csv could not be read: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("invalid campaign grant: {0}")]
+    InvalidGrant(String),
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+}
+
+impl From<solana_client::client_error::ClientError> for ClientError {
+    fn from(err: solana_client::client_error::ClientError) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
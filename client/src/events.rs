@@ -0,0 +1,166 @@
+//! Decoders for the events emitted through `emit!`.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use farming::{Claim, Deposit};
+use my_solana_project::IdIssued;
+use nft_minting_and_marketplace::{NftDelisted, NftListed, NftSold};
+use protocol_config::{AdminChanged, AdminProposed, PauseChanged};
+use solana_sdk::pubkey::Pubkey;
+use token_vesting::{
+    BeneficiaryChanged, TokensClaimed, VestingClosed, VestingCreated, VestingMerged,
+    VestingMigrated, VestingRevoked, VestingSplit, VestingToppedUp,
+};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+pub enum ProgramEvent {
+    IdIssued(IdIssued),
+    Deposit(Deposit),
+    Claim(Claim),
+    NftListed(NftListed),
+    NftSold(NftSold),
+    NftDelisted(NftDelisted),
+    PauseChanged(PauseChanged),
+    AdminProposed(AdminProposed),
+    AdminChanged(AdminChanged),
+    VestingCreated(VestingCreated),
+    TokensClaimed(TokensClaimed),
+    VestingRevoked(VestingRevoked),
+    BeneficiaryChanged(BeneficiaryChanged),
+    VestingClosed(VestingClosed),
+    VestingToppedUp(VestingToppedUp),
+    VestingSplit(VestingSplit),
+    VestingMerged(VestingMerged),
+    VestingMigrated(VestingMigrated),
+}
+
+impl ProgramEvent {
+    /// Decodes an event from its discriminator-prefixed Borsh encoding.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (discriminator, mut payload) = data.split_at(8);
+        let event = match discriminator {
+            d if d == IdIssued::DISCRIMINATOR => {
+                Self::IdIssued(IdIssued::deserialize(&mut payload).ok()?)
+            }
+            d if d == Deposit::DISCRIMINATOR => {
+                Self::Deposit(Deposit::deserialize(&mut payload).ok()?)
+            }
+            d if d == Claim::DISCRIMINATOR => Self::Claim(Claim::deserialize(&mut payload).ok()?),
+            d if d == NftListed::DISCRIMINATOR => {
+                Self::NftListed(NftListed::deserialize(&mut payload).ok()?)
+            }
+            d if d == NftSold::DISCRIMINATOR => {
+                Self::NftSold(NftSold::deserialize(&mut payload).ok()?)
+            }
+            d if d == NftDelisted::DISCRIMINATOR => {
+                Self::NftDelisted(NftDelisted::deserialize(&mut payload).ok()?)
+            }
+            d if d == PauseChanged::DISCRIMINATOR => {
+                Self::PauseChanged(PauseChanged::deserialize(&mut payload).ok()?)
+            }
+            d if d == AdminProposed::DISCRIMINATOR => {
+                Self::AdminProposed(AdminProposed::deserialize(&mut payload).ok()?)
+            }
+            d if d == AdminChanged::DISCRIMINATOR => {
+                Self::AdminChanged(AdminChanged::deserialize(&mut payload).ok()?)
+            }
+            d if d == VestingCreated::DISCRIMINATOR => {
+                Self::VestingCreated(VestingCreated::deserialize(&mut payload).ok()?)
+            }
+            d if d == TokensClaimed::DISCRIMINATOR => {
+                Self::TokensClaimed(TokensClaimed::deserialize(&mut payload).ok()?)
+            }
+            d if d == VestingRevoked::DISCRIMINATOR => {
+                Self::VestingRevoked(VestingRevoked::deserialize(&mut payload).ok()?)
+            }
+            d if d == BeneficiaryChanged::DISCRIMINATOR => {
+                Self::BeneficiaryChanged(BeneficiaryChanged::deserialize(&mut payload).ok()?)
+            }
+            d if d == VestingClosed::DISCRIMINATOR => {
+                Self::VestingClosed(VestingClosed::deserialize(&mut payload).ok()?)
+            }
+            d if d == VestingToppedUp::DISCRIMINATOR => {
+                Self::VestingToppedUp(VestingToppedUp::deserialize(&mut payload).ok()?)
+            }
+            d if d == VestingSplit::DISCRIMINATOR => {
+                Self::VestingSplit(VestingSplit::deserialize(&mut payload).ok()?)
+            }
+            d if d == VestingMerged::DISCRIMINATOR => {
+                Self::VestingMerged(VestingMerged::deserialize(&mut payload).ok()?)
+            }
+            d if d == VestingMigrated::DISCRIMINATOR => {
+                Self::VestingMigrated(VestingMigrated::deserialize(&mut payload).ok()?)
+            }
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    /// Program whose `emit!` produces the event.
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            Self::IdIssued(_) => my_solana_project::ID,
+            Self::Deposit(_) | Self::Claim(_) => farming::ID,
+            Self::NftListed(_) | Self::NftSold(_) | Self::NftDelisted(_) => {
+                nft_minting_and_marketplace::ID
+            }
+            Self::PauseChanged(_) | Self::AdminProposed(_) | Self::AdminChanged(_) => {
+                protocol_config::ID
+            }
+            Self::VestingCreated(_)
+            | Self::TokensClaimed(_)
+            | Self::VestingRevoked(_)
+            | Self::BeneficiaryChanged(_)
+            | Self::VestingClosed(_)
+            | Self::VestingToppedUp(_)
+            | Self::VestingSplit(_)
+            | Self::VestingMerged(_)
+            | Self::VestingMigrated(_) => token_vesting::ID,
+        }
+    }
+
+    /// Decodes a single `Program data: <base64>` log line.
+    pub fn from_log(log: &str) -> Option<Self> {
+        let encoded = log.strip_prefix(PROGRAM_DATA)?;
+        let data = STANDARD.decode(encoded).ok()?;
+        Self::decode(&data)
+    }
+}
+
+/// Extracts every known event from a transaction's log messages, in emission order.
+///
+/// `Program data:` lines carry no program id, so the invoke stack is tracked and an
+/// event only counts when the program defining it is the one executing. Any other
+/// program can log the same bytes.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<ProgramEvent> {
+    let mut stack = Vec::new();
+    let mut events = Vec::new();
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(event) = ProgramEvent::from_log(log) {
+            if stack.last() == Some(&event.program_id()) {
+                events.push(event);
+            }
+            continue;
+        }
+        let Some((program_id, status)) = log
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+        else {
+            continue;
+        };
+        if status.starts_with("invoke [") {
+            if let Ok(program_id) = program_id.parse::<Pubkey>() {
+                stack.push(program_id);
+            }
+        } else if status == "success" || status.starts_with("failed") {
+            stack.pop();
+        }
+    }
+    events
+}
@@ -0,0 +1,139 @@
+//! Builders for the `farming` program.
+
+use farming::{accounts, instruction, Farm, UserInfo};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{system_program, sysvar};
+
+use crate::{fetch_account, instruction, pda, ClientError};
+
+/// `user_info` is a fresh keypair that must also sign the transaction.
+pub fn initialize(owner: &Pubkey, user_info: &Pubkey, token_mint: &Pubkey) -> Instruction {
+    instruction(
+        farming::ID,
+        accounts::Initialize {
+            owner: *owner,
+            user: *user_info,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::Initialize {
+            token_mint: *token_mint,
+        },
+    )
+}
+
+/// `admin` must be the protocol admin.
+pub fn initialize_farm(admin: &Pubkey, mint: &Pubkey, reward_rate_bps: u16) -> Instruction {
+    let farm = pda::farm(mint).0;
+    instruction(
+        farming::ID,
+        accounts::InitializeFarm {
+            admin: *admin,
+            config: pda::protocol_config().0,
+            mint: *mint,
+            farm,
+            farm_vault: pda::farm_vault(&farm).0,
+            reward_vault: pda::reward_vault(&farm).0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::InitializeFarm { reward_rate_bps },
+    )
+}
+
+pub fn fund_rewards(
+    funder: &Pubkey,
+    mint: &Pubkey,
+    from_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let farm = pda::farm(mint).0;
+    instruction(
+        farming::ID,
+        accounts::FundRewards {
+            funder: *funder,
+            farm,
+            from_token_account: *from_token_account,
+            reward_vault: pda::reward_vault(&farm).0,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::FundRewards { amount },
+    )
+}
+
+/// `user` signs both as depositor and as authority of `from_token_account`.
+pub fn deposit_tokens(
+    user: &Pubkey,
+    user_info: &Pubkey,
+    mint: &Pubkey,
+    from_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let farm = pda::farm(mint).0;
+    instruction(
+        farming::ID,
+        accounts::DepositTokens {
+            user: *user,
+            user_info: *user_info,
+            farm,
+            from_token_account: *from_token_account,
+            farm_vault: pda::farm_vault(&farm).0,
+            reward_vault: pda::reward_vault(&farm).0,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+            authority: *user,
+        },
+        instruction::DepositTokens { amount },
+    )
+}
+
+/// Pays `reduce_deposit_amount` out of the farm vault into `to_token_account`.
+pub fn claim_monthly(
+    user: &Pubkey,
+    user_info: &Pubkey,
+    mint: &Pubkey,
+    to_token_account: &Pubkey,
+    months: u64,
+    reduce_deposit_amount: u64,
+) -> Instruction {
+    let farm = pda::farm(mint).0;
+    instruction(
+        farming::ID,
+        accounts::ClaimMonthly {
+            user: *user,
+            user_info: *user_info,
+            farm,
+            farm_vault: pda::farm_vault(&farm).0,
+            to_token_account: *to_token_account,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::ClaimMonthly {
+            months,
+            reduce_deposit_amount,
+        },
+    )
+}
+
+pub fn migrate_user_info(owner: &Pubkey, user_info: &Pubkey) -> Instruction {
+    instruction(
+        farming::ID,
+        accounts::MigrateUserInfo {
+            owner: *owner,
+            user_info: *user_info,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateUserInfo {},
+    )
+}
+
+pub fn fetch_user_info(rpc: &RpcClient, address: &Pubkey) -> Result<UserInfo, ClientError> {
+    fetch_account(rpc, address)
+}
+
+pub fn fetch_farm(rpc: &RpcClient, address: &Pubkey) -> Result<Farm, ClientError> {
+    fetch_account(rpc, address)
+}
@@ -0,0 +1,191 @@
+//! Per-beneficiary ledger replayed from `token_vesting` events.
+
+use std::collections::HashMap;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::events::{parse_logs, ProgramEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerChange {
+    /// A schedule was created for the beneficiary.
+    Granted(u64),
+    /// An existing schedule of the beneficiary was topped up.
+    ToppedUp(u64),
+    /// A legacy schedule was migrated with this much left unclaimed.
+    Migrated(u64),
+    Claimed(u64),
+    /// The unvested remainder went back to the treasury.
+    Revoked(u64),
+    /// Unclaimed tokens moved into the schedule by a beneficiary change, split or merge.
+    TransferredIn(u64),
+    /// Unclaimed tokens moved out of the schedule by a beneficiary change, split or merge.
+    TransferredOut(u64),
+    Closed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub vesting_account: Pubkey,
+    pub timestamp: i64,
+    pub change: LedgerChange,
+}
+
+/// Running totals of one beneficiary across all their schedules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BeneficiaryLedger {
+    pub granted: u64,
+    pub transferred_in: u64,
+    pub transferred_out: u64,
+    pub claimed: u64,
+    pub revoked: u64,
+    pub entries: Vec<LedgerEntry>,
+}
+
+impl BeneficiaryLedger {
+    /// Amount still held in the beneficiary's schedules, vested or not.
+    pub fn outstanding(&self) -> u64 {
+        (self.granted + self.transferred_in)
+            .saturating_sub(self.transferred_out + self.claimed + self.revoked)
+    }
+
+    fn record(&mut self, vesting_account: Pubkey, timestamp: i64, change: LedgerChange) {
+        match change {
+            LedgerChange::Granted(amount)
+            | LedgerChange::ToppedUp(amount)
+            | LedgerChange::Migrated(amount) => self.granted += amount,
+            LedgerChange::Claimed(amount) => self.claimed += amount,
+            LedgerChange::Revoked(amount) => self.revoked += amount,
+            LedgerChange::TransferredIn(amount) => self.transferred_in += amount,
+            LedgerChange::TransferredOut(amount) => self.transferred_out += amount,
+            LedgerChange::Closed => {}
+        }
+        self.entries.push(LedgerEntry {
+            vesting_account,
+            timestamp,
+            change,
+        });
+    }
+}
+
+#[derive(Default)]
+pub struct VestingLedger {
+    beneficiaries: HashMap<Pubkey, BeneficiaryLedger>,
+}
+
+impl VestingLedger {
+    /// Replays the vesting events found in `logs`, in emission order.
+    pub fn replay<S: AsRef<str>>(&mut self, logs: &[S]) {
+        for event in parse_logs(logs) {
+            self.apply(&event);
+        }
+    }
+
+    /// Applies one event; events of other programs are ignored.
+    pub fn apply(&mut self, event: &ProgramEvent) {
+        match event {
+            ProgramEvent::VestingCreated(created) => self.record(
+                created.beneficiary,
+                created.vesting_account,
+                created.timestamp,
+                LedgerChange::Granted(created.total_amount),
+            ),
+            ProgramEvent::TokensClaimed(claimed) => self.record(
+                claimed.beneficiary,
+                claimed.vesting_account,
+                claimed.timestamp,
+                LedgerChange::Claimed(claimed.amount),
+            ),
+            ProgramEvent::VestingRevoked(revoked) => self.record(
+                revoked.beneficiary,
+                revoked.vesting_account,
+                revoked.timestamp,
+                LedgerChange::Revoked(revoked.unvested_amount),
+            ),
+            ProgramEvent::BeneficiaryChanged(changed) => {
+                let remaining = changed.total_amount - changed.claimed_amount;
+                self.record(
+                    changed.previous_beneficiary,
+                    changed.vesting_account,
+                    changed.timestamp,
+                    LedgerChange::TransferredOut(remaining),
+                );
+                self.record(
+                    changed.new_beneficiary,
+                    changed.vesting_account,
+                    changed.timestamp,
+                    LedgerChange::TransferredIn(remaining),
+                );
+            }
+            ProgramEvent::VestingClosed(closed) => self.record(
+                closed.beneficiary,
+                closed.vesting_account,
+                closed.timestamp,
+                LedgerChange::Closed,
+            ),
+            ProgramEvent::VestingToppedUp(topped_up) => self.record(
+                topped_up.beneficiary,
+                topped_up.vesting_account,
+                topped_up.timestamp,
+                LedgerChange::ToppedUp(topped_up.amount),
+            ),
+            ProgramEvent::VestingSplit(split) => {
+                self.record(
+                    split.beneficiary,
+                    split.vesting_account,
+                    split.timestamp,
+                    LedgerChange::TransferredOut(split.moved_amount),
+                );
+                self.record(
+                    split.new_beneficiary,
+                    split.new_vesting_account,
+                    split.timestamp,
+                    LedgerChange::TransferredIn(split.received_amount),
+                );
+            }
+            ProgramEvent::VestingMerged(merged) => {
+                self.record(
+                    merged.beneficiary,
+                    merged.source_vesting_account,
+                    merged.timestamp,
+                    LedgerChange::TransferredOut(merged.moved_amount),
+                );
+                self.record(
+                    merged.beneficiary,
+                    merged.vesting_account,
+                    merged.timestamp,
+                    LedgerChange::TransferredIn(merged.received_amount),
+                );
+            }
+            ProgramEvent::VestingMigrated(migrated) => self.record(
+                migrated.beneficiary,
+                migrated.vesting_account,
+                migrated.timestamp,
+                LedgerChange::Migrated(migrated.total_amount - migrated.claimed_amount),
+            ),
+            _ => {}
+        }
+    }
+
+    pub fn beneficiary(&self, beneficiary: &Pubkey) -> Option<&BeneficiaryLedger> {
+        self.beneficiaries.get(beneficiary)
+    }
+
+    pub fn beneficiaries(&self) -> impl Iterator<Item = (&Pubkey, &BeneficiaryLedger)> {
+        self.beneficiaries.iter()
+    }
+
+    fn record(
+        &mut self,
+        beneficiary: Pubkey,
+        vesting_account: Pubkey,
+        timestamp: i64,
+        change: LedgerChange,
+    ) {
+        self.beneficiaries.entry(beneficiary).or_default().record(
+            vesting_account,
+            timestamp,
+            change,
+        );
+    }
+}
@@ -0,0 +1,35 @@
+//! Instruction builders, account fetchers and event decoders for the workspace programs.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+pub mod accounts;
+pub mod airdrop;
+pub mod auction;
+pub mod campaign;
+pub mod config;
+pub mod counter;
+pub mod error;
+pub mod events;
+pub mod farm;
+pub mod ledger;
+pub mod marketplace;
+pub mod pda;
+pub mod vesting;
+
+pub use accounts::{deserialize_account, fetch_account, fetch_token_program};
+pub use error::ClientError;
+pub use events::{parse_logs, ProgramEvent};
+
+fn instruction(
+    program_id: Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,109 @@
+//! Builders for the `nft_minting_and_marketplace` program.
+
+use nft_minting_and_marketplace::{accounts, instruction, NFTListing};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{system_program, sysvar};
+
+use crate::{fetch_account, instruction, pda, ClientError};
+
+/// Mints one token of `mint` into `token_account` and creates its metadata account.
+#[allow(clippy::too_many_arguments)]
+pub fn nft_format(
+    mint_authority: &Pubkey,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    payer: &Pubkey,
+    creator_key: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::MintNFT {
+            mint_authority: *mint_authority,
+            mint: *mint,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+            metadata: pda::metadata(mint).0,
+            token_account: *token_account,
+            token_metadata_program: anchor_spl::metadata::ID,
+            payer: *payer,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::NftFormat {
+            creator_key: *creator_key,
+            name,
+            symbol,
+            uri,
+        },
+    )
+}
+
+/// `nft_listing` is a fresh keypair that must also sign the transaction. Listing approves
+/// the market authority to deliver the NFT out of `seller_token_account`.
+pub fn list_nft(
+    seller: &Pubkey,
+    mint: &Pubkey,
+    seller_token_account: &Pubkey,
+    nft_listing: &Pubkey,
+    price: u64,
+) -> Instruction {
+    instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::ListNFT {
+            seller: *seller,
+            mint: *mint,
+            seller_token_account: *seller_token_account,
+            market_authority: pda::market_authority().0,
+            nft_listing: *nft_listing,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ListNft { price },
+    )
+}
+
+pub fn purchase_nft(
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    mint: &Pubkey,
+    seller_token_account: &Pubkey,
+    buyer_token_account: &Pubkey,
+    nft_listing: &Pubkey,
+) -> Instruction {
+    instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::PurchaseNFT {
+            buyer: *buyer,
+            seller: *seller,
+            mint: *mint,
+            seller_token_account: *seller_token_account,
+            buyer_token_account: *buyer_token_account,
+            market_authority: pda::market_authority().0,
+            nft_listing: *nft_listing,
+            config: pda::protocol_config().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::PurchaseNft {},
+    )
+}
+
+pub fn delist_nft(seller: &Pubkey, nft_listing: &Pubkey) -> Instruction {
+    instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::DelistNFT {
+            seller: *seller,
+            nft_listing: *nft_listing,
+        },
+        instruction::DelistNft {},
+    )
+}
+
+pub fn fetch_listing(rpc: &RpcClient, address: &Pubkey) -> Result<NFTListing, ClientError> {
+    fetch_account(rpc, address)
+}
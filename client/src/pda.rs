@@ -0,0 +1,124 @@
+//! Program derived addresses used by the workspace programs.
+
+use solana_sdk::pubkey::Pubkey;
+
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault-authority";
+
+pub fn counter_registry() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[my_solana_project::REGISTRY_SEED], &my_solana_project::ID)
+}
+
+pub fn counter(authority: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            my_solana_project::COUNTER_SEED,
+            authority.as_ref(),
+            name.as_bytes(),
+        ],
+        &my_solana_project::ID,
+    )
+}
+
+pub fn vesting(mint: &Pubkey, beneficiary: &Pubkey, schedule_id: u64) -> (Pubkey, u8) {
+    token_vesting::vesting_address(mint, beneficiary, schedule_id)
+}
+
+/// Associated token account of the schedule, which holds its unvested tokens.
+/// `token_program` is the owner of the mint, SPL Token or Token-2022.
+pub fn vesting_vault(vesting_account: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    anchor_spl::associated_token::get_associated_token_address_with_program_id(
+        vesting_account,
+        mint,
+        token_program,
+    )
+}
+
+/// Record of the schedule's governance weight, refreshed by `update_voter_record`.
+pub fn voter_record(vesting_account: &Pubkey) -> (Pubkey, u8) {
+    token_vesting::voter_record_address(vesting_account)
+}
+
+/// Multisig administering the grants created through its proposals.
+pub fn vesting_admin(creator: &Pubkey) -> (Pubkey, u8) {
+    token_vesting::vesting_admin_address(creator)
+}
+
+pub fn admin_proposal(vesting_admin: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    token_vesting::admin_proposal_address(vesting_admin, proposal_id)
+}
+
+pub fn vesting_campaign(mint: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    token_vesting::campaign_address(mint, campaign_id)
+}
+
+pub fn campaign_claim(campaign: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    token_vesting::campaign_claim_address(campaign, beneficiary)
+}
+
+/// Schedule claimed from a campaign, kept apart from direct grants with the same id.
+pub fn campaign_vesting(mint: &Pubkey, beneficiary: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    token_vesting::campaign_vesting_address(mint, beneficiary, campaign_id)
+}
+
+/// Owner of the vaults of schedules created before each schedule got its own.
+pub fn vesting_vault_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &token_vesting::ID)
+}
+
+/// Holds the tokens paid out by `claim_airdrop`.
+pub fn airdrop_vault() -> (Pubkey, u8) {
+    staking_airdrop::airdrop_vault_address()
+}
+
+pub fn airdrop_authority() -> (Pubkey, u8) {
+    staking_airdrop::airdrop_authority_address()
+}
+
+/// Holds the staked tokens, kept apart from the airdrop vault.
+pub fn staking_vault() -> (Pubkey, u8) {
+    staking_airdrop::staking_vault_address()
+}
+
+pub fn staking_authority() -> (Pubkey, u8) {
+    staking_airdrop::staking_authority_address()
+}
+
+pub fn user_state(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[user.as_ref()], &staking_airdrop::ID)
+}
+
+pub fn farm(mint: &Pubkey) -> (Pubkey, u8) {
+    farming::farm_address(mint)
+}
+
+/// Holds the deposits of the farm along with the rewards credited on them.
+pub fn farm_vault(farm: &Pubkey) -> (Pubkey, u8) {
+    farming::farm_vault_address(farm)
+}
+
+/// Holds the rewards of the farm until deposits are credited with them.
+pub fn reward_vault(farm: &Pubkey) -> (Pubkey, u8) {
+    farming::reward_vault_address(farm)
+}
+
+/// Owner of the NFT and bid vaults of `auction`.
+pub fn auction_authority(auction: &Pubkey) -> (Pubkey, u8) {
+    advanced_auction::auction_authority_address(auction)
+}
+
+/// Delegate of every listed NFT, which delivers it to the buyer.
+pub fn market_authority() -> (Pubkey, u8) {
+    nft_minting_and_marketplace::market_authority_address()
+}
+
+pub fn metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    let program_id = anchor_spl::metadata::ID;
+    Pubkey::find_program_address(
+        &[b"metadata", program_id.as_ref(), mint.as_ref()],
+        &program_id,
+    )
+}
+
+pub fn protocol_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[protocol_config::CONFIG_SEED], &protocol_config::ID)
+}
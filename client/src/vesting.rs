@@ -0,0 +1,709 @@
+//! Builders for the `token_vesting` program.
+
+use anchor_lang::AnchorDeserialize;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use solana_sdk::{system_program, sysvar};
+use token_vesting::{
+    accounts, instruction, AdminAction, AdminProposal, VestingAccount, VestingAdmin,
+    VestingCampaign, VestingKind, VestingView, VoterRecord,
+};
+
+use crate::campaign::Grant;
+use crate::{fetch_account, instruction, pda, ClientError};
+
+/// Creates the schedule PDA and its vault and funds it from `admin_token_account`.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_vesting(
+    admin: &Pubkey,
+    admin_token_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    beneficiary: &Pubkey,
+    schedule_id: u64,
+    start_time: i64,
+    cliff_duration: i64,
+    vesting_duration: i64,
+    total_amount: u64,
+    revocable: bool,
+    kind: VestingKind,
+) -> Instruction {
+    let vesting_account = pda::vesting(mint, beneficiary, schedule_id).0;
+    instruction(
+        token_vesting::ID,
+        accounts::InitializeVesting {
+            admin: *admin,
+            admin_token_account: *admin_token_account,
+            mint: *mint,
+            vesting_account,
+            vesting_vault: pda::vesting_vault(&vesting_account, mint, token_program),
+            beneficiary: *beneficiary,
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::InitializeVesting {
+            schedule_id,
+            start_time,
+            cliff_duration,
+            vesting_duration,
+            total_amount,
+            revocable,
+            kind,
+        },
+    )
+}
+
+/// Claims `amount`, or everything claimable when `None`, from the schedule at
+/// `vesting_account`. Signed by its beneficiary, who can pay into any `destination`, or
+/// its claim delegate, who must pay into the beneficiary's associated token account.
+pub fn claim_tokens(
+    claimer: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    destination: &Pubkey,
+    amount: Option<u64>,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ClaimTokens {
+            claimer: *claimer,
+            vesting_account: *vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, token_program),
+            destination: *destination,
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+            staking_program: None,
+            staking_vault: None,
+            staking_vault_authority: None,
+            stake_state: None,
+            system_program: None,
+        },
+        instruction::ClaimTokens { amount },
+    )
+}
+
+/// Like [`claim_tokens`] for an SPL Token schedule with tokens staked in the staking
+/// vault, which are unstaked to cover whatever the vault lacks.
+pub fn claim_staked_tokens(
+    claimer: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    amount: Option<u64>,
+) -> Instruction {
+    let token_program = anchor_spl::token::ID;
+    instruction(
+        token_vesting::ID,
+        accounts::ClaimTokens {
+            claimer: *claimer,
+            vesting_account: *vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, &token_program),
+            destination: *destination,
+            config: pda::protocol_config().0,
+            token_program,
+            staking_program: Some(staking_airdrop::ID),
+            staking_vault: Some(pda::staking_vault().0),
+            staking_vault_authority: Some(pda::staking_authority().0),
+            stake_state: Some(pda::user_state(vesting_account).0),
+            system_program: Some(system_program::ID),
+        },
+        instruction::ClaimTokens { amount },
+    )
+}
+
+/// Reports the schedule's amounts at `at_timestamp`, or the cluster time when `None`,
+/// through return data.
+pub fn view_vesting(vesting_account: &Pubkey, at_timestamp: Option<i64>) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ViewVesting {
+            vesting_account: *vesting_account,
+        },
+        instruction::ViewVesting { at_timestamp },
+    )
+}
+
+/// Refreshes the schedule's `VoterRecord`, creating it at `payer`'s expense if needed.
+pub fn update_voter_record(payer: &Pubkey, vesting_account: &Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::UpdateVoterRecord {
+            payer: *payer,
+            vesting_account: *vesting_account,
+            voter_record: pda::voter_record(vesting_account).0,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateVoterRecord {},
+    )
+}
+
+/// Closes a fully claimed schedule and its vault, sending their rent to `receiver`.
+pub fn close_vesting(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    receiver: &Pubkey,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::CloseVesting {
+            beneficiary: *beneficiary,
+            vesting_account: *vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, token_program),
+            receiver: *receiver,
+            token_program: *token_program,
+        },
+        instruction::CloseVesting {},
+    )
+}
+
+/// Proposes `new_beneficiary` as the owner of the schedule, which it takes over with
+/// `accept_beneficiary`.
+pub fn transfer_beneficiary(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    new_beneficiary: &Pubkey,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::UpdateBeneficiary {
+            beneficiary: *beneficiary,
+            vesting_account: *vesting_account,
+        },
+        instruction::TransferBeneficiary {
+            new_beneficiary: *new_beneficiary,
+        },
+    )
+}
+
+pub fn accept_beneficiary(new_beneficiary: &Pubkey, vesting_account: &Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::AcceptBeneficiary {
+            new_beneficiary: *new_beneficiary,
+            vesting_account: *vesting_account,
+        },
+        instruction::AcceptBeneficiary {},
+    )
+}
+
+pub fn set_claim_delegate(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    claim_delegate: Option<Pubkey>,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::UpdateBeneficiary {
+            beneficiary: *beneficiary,
+            vesting_account: *vesting_account,
+        },
+        instruction::SetClaimDelegate { claim_delegate },
+    )
+}
+
+/// Adds `amount` from `admin_token_account` to the schedule's total. Signed by the
+/// schedule's admin.
+pub fn top_up_vesting(
+    admin: &Pubkey,
+    admin_token_account: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::TopUpVesting {
+            admin: *admin,
+            admin_token_account: *admin_token_account,
+            vesting_account: *vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, token_program),
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+        },
+        instruction::TopUpVesting { amount },
+    )
+}
+
+/// Moves `amount` of the schedule at `vesting_account`, whose id is `schedule_id`, to a
+/// new schedule of `new_beneficiary` with that same id. Signed and paid for by the
+/// beneficiary.
+pub fn split_vesting(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    schedule_id: u64,
+    amount: u64,
+    new_beneficiary: &Pubkey,
+) -> Instruction {
+    let new_vesting_account = pda::vesting(mint, new_beneficiary, schedule_id).0;
+    instruction(
+        token_vesting::ID,
+        accounts::SplitVesting {
+            beneficiary: *beneficiary,
+            vesting_account: *vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, token_program),
+            new_vesting_account,
+            new_vesting_vault: pda::vesting_vault(&new_vesting_account, mint, token_program),
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::SplitVesting {
+            amount,
+            new_beneficiary: *new_beneficiary,
+        },
+    )
+}
+
+/// Folds `source_vesting_account` into `vesting_account`, closing the source and its
+/// vault with their rent going to the beneficiary.
+pub fn merge_vesting(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    source_vesting_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::MergeVesting {
+            beneficiary: *beneficiary,
+            vesting_account: *vesting_account,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, token_program),
+            source_vesting_account: *source_vesting_account,
+            source_vesting_vault: pda::vesting_vault(source_vesting_account, mint, token_program),
+            mint: *mint,
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+        },
+        instruction::MergeVesting {},
+    )
+}
+
+/// Stakes `amount` of an SPL Token schedule's vault into the staking vault.
+pub fn stake_vesting(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        stake_accounts(beneficiary, vesting_account, mint),
+        instruction::StakeVesting { amount },
+    )
+}
+
+/// Returns `amount` of the schedule's stake to its vault.
+pub fn unstake_vesting(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        stake_accounts(beneficiary, vesting_account, mint),
+        instruction::UnstakeVesting { amount },
+    )
+}
+
+fn stake_accounts(
+    beneficiary: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+) -> accounts::StakeVesting {
+    let token_program = anchor_spl::token::ID;
+    accounts::StakeVesting {
+        beneficiary: *beneficiary,
+        vesting_account: *vesting_account,
+        mint: *mint,
+        vesting_vault: pda::vesting_vault(vesting_account, mint, &token_program),
+        staking_vault: pda::staking_vault().0,
+        staking_vault_authority: pda::staking_authority().0,
+        stake_state: pda::user_state(vesting_account).0,
+        config: pda::protocol_config().0,
+        staking_program: staking_airdrop::ID,
+        token_program,
+        system_program: system_program::ID,
+    }
+}
+
+/// Returns the unvested part of a revocable schedule to `treasury_token_account`,
+/// which must be owned by the protocol fee treasury. `beneficiary` is the wallet the
+/// schedule was created for.
+pub fn revoke_vesting(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    beneficiary: &Pubkey,
+    schedule_id: u64,
+    treasury_token_account: &Pubkey,
+) -> Instruction {
+    let vesting_account = pda::vesting(mint, beneficiary, schedule_id).0;
+    instruction(
+        token_vesting::ID,
+        accounts::RevokeVesting {
+            admin: *admin,
+            vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(&vesting_account, mint, token_program),
+            treasury_token_account: *treasury_token_account,
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+        },
+        instruction::RevokeVesting {},
+    )
+}
+
+/// Moves a legacy keypair schedule, funded from `legacy_vault`, to the schedule PDA
+/// for `schedule_id`. Only the protocol admin can sign this.
+pub fn migrate_vesting(
+    admin: &Pubkey,
+    legacy_vesting_account: &Pubkey,
+    legacy_vault: &Pubkey,
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    schedule_id: u64,
+) -> Instruction {
+    let vesting_account = pda::vesting(mint, beneficiary, schedule_id).0;
+    instruction(
+        token_vesting::ID,
+        accounts::MigrateVesting {
+            admin: *admin,
+            config: pda::protocol_config().0,
+            legacy_vesting_account: *legacy_vesting_account,
+            legacy_vault: *legacy_vault,
+            vault_authority: pda::vesting_vault_authority().0,
+            mint: *mint,
+            beneficiary: *beneficiary,
+            vesting_account,
+            vesting_vault: pda::vesting_vault(&vesting_account, mint, &anchor_spl::token::ID),
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateVesting { schedule_id },
+    )
+}
+
+pub fn upgrade_vesting(beneficiary: &Pubkey, vesting_account: &Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::UpgradeVesting {
+            beneficiary: *beneficiary,
+            vesting_account: *vesting_account,
+            system_program: system_program::ID,
+        },
+        instruction::UpgradeVesting {},
+    )
+}
+
+/// Creates a campaign for the grants committed to by `merkle_root`, funded with
+/// `total_amount` from `admin_token_account`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_campaign(
+    admin: &Pubkey,
+    admin_token_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    campaign_id: u64,
+    merkle_root: [u8; 32],
+    start_time: i64,
+    total_amount: u64,
+    revocable: bool,
+) -> Instruction {
+    let campaign = pda::vesting_campaign(mint, campaign_id).0;
+    instruction(
+        token_vesting::ID,
+        accounts::CreateCampaign {
+            admin: *admin,
+            admin_token_account: *admin_token_account,
+            mint: *mint,
+            campaign,
+            campaign_vault: pda::vesting_vault(&campaign, mint, token_program),
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateCampaign {
+            campaign_id,
+            merkle_root,
+            start_time,
+            total_amount,
+            revocable,
+        },
+    )
+}
+
+/// Materializes the grant's schedule, whose schedule id is `campaign_id`.
+pub fn claim_vesting(
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    campaign_id: u64,
+    grant: &Grant,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let campaign = pda::vesting_campaign(mint, campaign_id).0;
+    let vesting_account = pda::campaign_vesting(mint, &grant.beneficiary, campaign_id).0;
+    instruction(
+        token_vesting::ID,
+        accounts::ClaimVesting {
+            beneficiary: grant.beneficiary,
+            campaign,
+            mint: *mint,
+            campaign_vault: pda::vesting_vault(&campaign, mint, token_program),
+            campaign_claim: pda::campaign_claim(&campaign, &grant.beneficiary).0,
+            vesting_account,
+            vesting_vault: pda::vesting_vault(&vesting_account, mint, token_program),
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimVesting {
+            amount: grant.amount,
+            cliff_time: grant.cliff_time,
+            vesting_end_time: grant.vesting_end_time,
+            proof,
+        },
+    )
+}
+
+/// Creates the multisig of `creator`, which needs `threshold` of `signers` to approve
+/// each grant it creates, revokes or tops up.
+pub fn create_vesting_admin(creator: &Pubkey, signers: Vec<Pubkey>, threshold: u8) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::CreateVestingAdmin {
+            creator: *creator,
+            vesting_admin: pda::vesting_admin(creator).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateVestingAdmin { signers, threshold },
+    )
+}
+
+/// Proposes `action` as the admin's `proposal_id`th proposal, which must be its current
+/// `proposal_count`. Signed and paid for by one of its signers.
+pub fn propose_admin_action(
+    proposer: &Pubkey,
+    vesting_admin: &Pubkey,
+    proposal_id: u64,
+    action: AdminAction,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ProposeAdminAction {
+            proposer: *proposer,
+            vesting_admin: *vesting_admin,
+            proposal: pda::admin_proposal(vesting_admin, proposal_id).0,
+            system_program: system_program::ID,
+        },
+        instruction::ProposeAdminAction { action },
+    )
+}
+
+pub fn approve_admin_action(
+    signer: &Pubkey,
+    vesting_admin: &Pubkey,
+    proposal_id: u64,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ApproveAdminAction {
+            signer: *signer,
+            vesting_admin: *vesting_admin,
+            proposal: pda::admin_proposal(vesting_admin, proposal_id).0,
+        },
+        instruction::ApproveAdminAction {},
+    )
+}
+
+/// Executes an approved `CreateVesting` proposal, funded from `admin_token_account`,
+/// which the admin PDA must own. `payer` covers the rent of the new schedule.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_vesting(
+    payer: &Pubkey,
+    vesting_admin: &Pubkey,
+    proposal_id: u64,
+    admin_token_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    beneficiary: &Pubkey,
+    schedule_id: u64,
+) -> Instruction {
+    let vesting_account = pda::vesting(mint, beneficiary, schedule_id).0;
+    instruction(
+        token_vesting::ID,
+        accounts::ExecuteCreateVesting {
+            payer: *payer,
+            vesting_admin: *vesting_admin,
+            proposal: pda::admin_proposal(vesting_admin, proposal_id).0,
+            admin_token_account: *admin_token_account,
+            mint: *mint,
+            vesting_account,
+            vesting_vault: pda::vesting_vault(&vesting_account, mint, token_program),
+            beneficiary: *beneficiary,
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ExecuteCreateVesting {},
+    )
+}
+
+/// Executes an approved `RevokeVesting` proposal, returning the unvested tokens to
+/// `treasury_token_account`, which must be owned by the protocol fee treasury.
+pub fn execute_revoke_vesting(
+    vesting_admin: &Pubkey,
+    proposal_id: u64,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    treasury_token_account: &Pubkey,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ExecuteRevokeVesting {
+            vesting_admin: *vesting_admin,
+            proposal: pda::admin_proposal(vesting_admin, proposal_id).0,
+            vesting_account: *vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, token_program),
+            treasury_token_account: *treasury_token_account,
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+        },
+        instruction::ExecuteRevokeVesting {},
+    )
+}
+
+/// Executes an approved `TopUpVesting` proposal, funded from `admin_token_account`.
+pub fn execute_top_up_vesting(
+    vesting_admin: &Pubkey,
+    proposal_id: u64,
+    admin_token_account: &Pubkey,
+    vesting_account: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ExecuteTopUpVesting {
+            vesting_admin: *vesting_admin,
+            proposal: pda::admin_proposal(vesting_admin, proposal_id).0,
+            admin_token_account: *admin_token_account,
+            vesting_account: *vesting_account,
+            mint: *mint,
+            vesting_vault: pda::vesting_vault(vesting_account, mint, token_program),
+            config: pda::protocol_config().0,
+            token_program: *token_program,
+        },
+        instruction::ExecuteTopUpVesting {},
+    )
+}
+
+pub fn execute_set_signers(vesting_admin: &Pubkey, proposal_id: u64) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ExecuteSetSigners {
+            vesting_admin: *vesting_admin,
+            proposal: pda::admin_proposal(vesting_admin, proposal_id).0,
+        },
+        instruction::ExecuteSetSigners {},
+    )
+}
+
+/// Amount `claim_tokens` would transfer at `now`.
+pub fn claimable_amount(vesting_account: &VestingAccount, now: i64) -> u64 {
+    vesting_account
+        .view(now)
+        .map(|view| view.claimable)
+        .unwrap_or(0)
+}
+
+/// Projects a fetched schedule offline with the same code `view_vesting` runs.
+pub fn project_vesting(
+    vesting_account: &VestingAccount,
+    at_timestamp: i64,
+) -> Result<VestingView, ClientError> {
+    Ok(vesting_account.view(at_timestamp)?)
+}
+
+/// Runs `view_vesting` through `simulateTransaction`, so nothing is signed or paid.
+/// `payer` only has to exist as the simulated fee payer.
+pub fn simulate_view_vesting(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    vesting_account: &Pubkey,
+    at_timestamp: Option<i64>,
+) -> Result<VestingView, ClientError> {
+    let message = Message::new(&[view_vesting(vesting_account, at_timestamp)], Some(payer));
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        ..RpcSimulateTransactionConfig::default()
+    };
+    let result = rpc
+        .simulate_transaction_with_config(&Transaction::new_unsigned(message), config)?
+        .value;
+    if let Some(err) = result.err {
+        return Err(ClientError::Simulation(err.to_string()));
+    }
+    let return_data = result
+        .return_data
+        .ok_or_else(|| ClientError::Simulation("no return data".to_string()))?;
+    let data = STANDARD
+        .decode(&return_data.data.0)
+        .map_err(|err| ClientError::Simulation(err.to_string()))?;
+    VestingView::try_from_slice(&data).map_err(|err| ClientError::Simulation(err.to_string()))
+}
+
+pub fn fetch_vesting_account(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<VestingAccount, ClientError> {
+    fetch_account(rpc, address)
+}
+
+pub fn fetch_campaign(rpc: &RpcClient, address: &Pubkey) -> Result<VestingCampaign, ClientError> {
+    fetch_account(rpc, address)
+}
+
+pub fn fetch_voter_record(rpc: &RpcClient, address: &Pubkey) -> Result<VoterRecord, ClientError> {
+    fetch_account(rpc, address)
+}
+
+pub fn fetch_vesting_admin(rpc: &RpcClient, address: &Pubkey) -> Result<VestingAdmin, ClientError> {
+    fetch_account(rpc, address)
+}
+
+pub fn fetch_admin_proposal(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<AdminProposal, ClientError> {
+    fetch_account(rpc, address)
+}
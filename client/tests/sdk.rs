@@ -0,0 +1,447 @@
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use client::campaign::CampaignTree;
+use client::ledger::{LedgerChange, VestingLedger};
+use client::{auction, parse_logs, pda, vesting, ClientError, ProgramEvent};
+use common::merkle;
+use farming::Deposit;
+use nft_minting_and_marketplace::NftSold;
+use solana_sdk::pubkey::Pubkey;
+use token_vesting::{
+    BeneficiaryChanged, TokensClaimed, VestingClosed, VestingCreated, VestingMerged,
+    VestingMigrated, VestingRevoked, VestingSplit, VestingToppedUp,
+};
+
+fn program_data_log(event: &impl Event) -> String {
+    format!("Program data: {}", STANDARD.encode(event.data()))
+}
+
+/// Wraps `logs` in a top-level invocation of `program_id`.
+fn invocation(program_id: Pubkey, logs: Vec<String>) -> Vec<String> {
+    let mut wrapped = vec![format!("Program {program_id} invoke [1]")];
+    wrapped.extend(logs);
+    wrapped.push(format!("Program {program_id} success"));
+    wrapped
+}
+
+#[test]
+fn parses_events_from_logs() {
+    let user = Pubkey::new_unique();
+    let (seller, buyer, mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let mut logs = invocation(
+        farming::ID,
+        vec![program_data_log(&Deposit { user, amount: 150 })],
+    );
+    logs.extend(invocation(
+        nft_minting_and_marketplace::ID,
+        vec![
+            "Program log: NFT purchased successfully!".to_string(),
+            program_data_log(&NftSold {
+                seller,
+                buyer,
+                mint,
+                price: 42,
+            }),
+            "Program data: not-base64".to_string(),
+        ],
+    ));
+
+    let events = parse_logs(&logs);
+    assert_eq!(events.len(), 2);
+    match &events[0] {
+        ProgramEvent::Deposit(deposit) => {
+            assert_eq!(deposit.user, user);
+            assert_eq!(deposit.amount, 150);
+        }
+        _ => panic!("expected a Deposit event"),
+    }
+    match &events[1] {
+        ProgramEvent::NftSold(sold) => {
+            assert_eq!((sold.seller, sold.buyer, sold.mint), (seller, buyer, mint));
+            assert_eq!(sold.price, 42);
+        }
+        _ => panic!("expected an NftSold event"),
+    }
+}
+
+#[test]
+fn ignores_events_logged_by_other_programs() {
+    let foreign = Pubkey::new_unique();
+    let created = |total_amount| {
+        program_data_log(&VestingCreated {
+            vesting_account: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            total_amount,
+            start_time: 0,
+            cliff_time: 10,
+            vesting_end_time: 100,
+            timestamp: 0,
+        })
+    };
+    let mut logs = invocation(foreign, vec![created(1)]);
+    logs.extend(invocation(
+        token_vesting::ID,
+        vec![
+            format!("Program {foreign} invoke [2]"),
+            created(2),
+            format!("Program {foreign} failed: custom program error: 0x0"),
+            created(3),
+        ],
+    ));
+    logs.push(created(4));
+
+    let events = parse_logs(&logs);
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        ProgramEvent::VestingCreated(created) => assert_eq!(created.total_amount, 3),
+        _ => panic!("expected a VestingCreated event"),
+    }
+}
+
+#[test]
+fn claim_tokens_derives_schedule_pda_and_vault() {
+    let beneficiary = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+
+    let (vesting_account, _) = Pubkey::find_program_address(
+        &[
+            b"vesting",
+            mint.as_ref(),
+            beneficiary.as_ref(),
+            &7u64.to_le_bytes(),
+        ],
+        &token_vesting::ID,
+    );
+    let vault = anchor_spl::associated_token::get_associated_token_address(&vesting_account, &mint);
+
+    assert_eq!(pda::vesting(&mint, &beneficiary, 7).0, vesting_account);
+
+    let ix = vesting::claim_tokens(
+        &beneficiary,
+        &vesting_account,
+        &mint,
+        &anchor_spl::token::ID,
+        &destination,
+        None,
+    );
+    assert_eq!(ix.program_id, token_vesting::ID);
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(ix.accounts[1].pubkey, vesting_account);
+    assert!(!ix.accounts[1].is_signer);
+    assert_eq!(ix.accounts[3].pubkey, vault);
+    assert_eq!(ix.accounts[4].pubkey, destination);
+}
+
+fn campaign_csv(beneficiaries: &[Pubkey]) -> String {
+    let mut csv = String::from("beneficiary,amount,cliff_time,vesting_end_time\n");
+    for (i, beneficiary) in beneficiaries.iter().enumerate() {
+        csv += &format!("{beneficiary},{},100,1000\n", (i as u64 + 1) * 10);
+    }
+    csv
+}
+
+#[test]
+fn token_2022_schedules_use_token_2022_vaults() {
+    let beneficiary = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let token_program = anchor_spl::token_2022::ID;
+    let (vesting_account, _) = pda::vesting(&mint, &beneficiary, 7);
+    let vault = anchor_spl::associated_token::get_associated_token_address_with_program_id(
+        &vesting_account,
+        &mint,
+        &token_program,
+    );
+
+    assert_eq!(
+        pda::vesting_vault(&vesting_account, &mint, &token_program),
+        vault
+    );
+    assert_ne!(
+        pda::vesting_vault(&vesting_account, &mint, &anchor_spl::token::ID),
+        vault
+    );
+
+    let ix = vesting::claim_tokens(
+        &beneficiary,
+        &vesting_account,
+        &mint,
+        &token_program,
+        &beneficiary,
+        None,
+    );
+    assert_eq!(ix.accounts[3].pubkey, vault);
+    assert_eq!(ix.accounts[6].pubkey, token_program);
+}
+
+#[test]
+fn admin_proposals_derive_from_vesting_admin() {
+    let creator = Pubkey::new_unique();
+    let (vesting_admin, _) = pda::vesting_admin(&creator);
+    assert_eq!(
+        vesting_admin,
+        Pubkey::find_program_address(&[b"vesting-admin", creator.as_ref()], &token_vesting::ID).0
+    );
+
+    let (mint, beneficiary, funds) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let ix = vesting::execute_create_vesting(
+        &creator,
+        &vesting_admin,
+        3,
+        &funds,
+        &mint,
+        &anchor_spl::token::ID,
+        &beneficiary,
+        7,
+    );
+    assert_eq!(ix.accounts[1].pubkey, vesting_admin);
+    assert_eq!(
+        ix.accounts[2].pubkey,
+        pda::admin_proposal(&vesting_admin, 3).0
+    );
+    assert_eq!(
+        ix.accounts[5].pubkey,
+        pda::vesting(&mint, &beneficiary, 7).0
+    );
+}
+
+#[test]
+fn auction_vaults_belong_to_their_auction() {
+    let (first, second, mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let (authority, _) = pda::auction_authority(&first);
+    assert_ne!(authority, pda::auction_authority(&second).0);
+    assert_ne!(
+        auction::vault_address(&first, &mint),
+        auction::vault_address(&second, &mint)
+    );
+
+    let create = auction::create_vault(&Pubkey::new_unique(), &first, &mint);
+    assert_eq!(
+        create.accounts[1].pubkey,
+        auction::vault_address(&first, &mint)
+    );
+    assert_eq!(create.accounts[2].pubkey, authority);
+}
+
+#[test]
+fn campaign_tree_proves_every_csv_grant() {
+    let beneficiaries: Vec<_> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    let tree = CampaignTree::from_csv(campaign_csv(&beneficiaries).as_bytes()).unwrap();
+    assert_eq!(tree.total_amount(), 150);
+
+    for beneficiary in &beneficiaries {
+        let (grant, proof) = tree.proof(beneficiary).unwrap();
+        let leaf = token_vesting::campaign_leaf(
+            beneficiary,
+            grant.amount,
+            grant.cliff_time,
+            grant.vesting_end_time,
+        );
+        assert!(merkle::verify(&proof, &tree.root(), leaf));
+        assert!(!merkle::verify(
+            &proof,
+            &tree.root(),
+            merkle::hash_leaf(&[b"other"])
+        ));
+    }
+    assert!(tree.proof(&Pubkey::new_unique()).is_none());
+}
+
+#[test]
+fn campaign_tree_rejects_duplicate_beneficiaries() {
+    let beneficiary = Pubkey::new_unique();
+    let result = CampaignTree::from_csv(campaign_csv(&[beneficiary, beneficiary]).as_bytes());
+    assert!(matches!(result, Err(ClientError::InvalidGrant(_))));
+}
+
+#[test]
+fn ledger_replays_schedule_lifecycle() {
+    let (alice, bob, vesting_account) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let logs = invocation(
+        token_vesting::ID,
+        vec![
+            program_data_log(&VestingCreated {
+                vesting_account,
+                beneficiary: alice,
+                admin: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+                total_amount: 1_000,
+                start_time: 0,
+                cliff_time: 10,
+                vesting_end_time: 100,
+                timestamp: 0,
+            }),
+            program_data_log(&TokensClaimed {
+                vesting_account,
+                beneficiary: alice,
+                destination: Pubkey::new_unique(),
+                amount: 300,
+                claimed_amount: 300,
+                total_amount: 1_000,
+                timestamp: 30,
+            }),
+            program_data_log(&BeneficiaryChanged {
+                vesting_account,
+                previous_beneficiary: alice,
+                new_beneficiary: bob,
+                total_amount: 1_000,
+                claimed_amount: 300,
+                timestamp: 40,
+            }),
+            program_data_log(&VestingRevoked {
+                vesting_account,
+                beneficiary: bob,
+                vested_amount: 500,
+                unvested_amount: 500,
+                timestamp: 50,
+            }),
+            program_data_log(&TokensClaimed {
+                vesting_account,
+                beneficiary: bob,
+                destination: Pubkey::new_unique(),
+                amount: 200,
+                claimed_amount: 500,
+                total_amount: 500,
+                timestamp: 60,
+            }),
+            program_data_log(&VestingClosed {
+                vesting_account,
+                beneficiary: bob,
+                claimed_amount: 500,
+                timestamp: 70,
+            }),
+        ],
+    );
+
+    let mut ledger = VestingLedger::default();
+    ledger.replay(&logs);
+
+    let alice = ledger.beneficiary(&alice).unwrap();
+    assert_eq!((alice.granted, alice.claimed), (1_000, 300));
+    assert_eq!(alice.transferred_out, 700);
+    assert_eq!(alice.outstanding(), 0);
+
+    let bob = ledger.beneficiary(&bob).unwrap();
+    assert_eq!(
+        (bob.transferred_in, bob.revoked, bob.claimed),
+        (700, 500, 200)
+    );
+    assert_eq!(bob.outstanding(), 0);
+    let changes: Vec<_> = bob.entries.iter().map(|entry| entry.change).collect();
+    assert_eq!(
+        changes,
+        [
+            LedgerChange::TransferredIn(700),
+            LedgerChange::Revoked(500),
+            LedgerChange::Claimed(200),
+            LedgerChange::Closed,
+        ]
+    );
+    assert_eq!(bob.entries[3].timestamp, 70);
+}
+
+#[test]
+fn ledger_replays_amended_schedules() {
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (first, second, split_off) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let logs = invocation(
+        token_vesting::ID,
+        vec![
+            program_data_log(&VestingMigrated {
+                vesting_account: first,
+                legacy_vesting_account: Pubkey::new_unique(),
+                beneficiary: alice,
+                total_amount: 1_000,
+                claimed_amount: 400,
+                timestamp: 0,
+            }),
+            program_data_log(&VestingToppedUp {
+                vesting_account: first,
+                beneficiary: alice,
+                amount: 400,
+                total_amount: 1_400,
+                timestamp: 10,
+            }),
+            program_data_log(&VestingSplit {
+                vesting_account: first,
+                new_vesting_account: split_off,
+                beneficiary: alice,
+                new_beneficiary: bob,
+                moved_amount: 300,
+                received_amount: 297,
+                timestamp: 20,
+            }),
+            program_data_log(&VestingCreated {
+                vesting_account: second,
+                beneficiary: alice,
+                admin: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+                total_amount: 500,
+                start_time: 0,
+                cliff_time: 10,
+                vesting_end_time: 100,
+                timestamp: 30,
+            }),
+            program_data_log(&VestingMerged {
+                vesting_account: first,
+                source_vesting_account: second,
+                beneficiary: alice,
+                moved_amount: 500,
+                received_amount: 495,
+                timestamp: 40,
+            }),
+        ],
+    );
+
+    let mut ledger = VestingLedger::default();
+    ledger.replay(&logs);
+
+    // 600 migrated + 400 topped up + 500 granted, less 300 split off and 5 lost to fees
+    let alice = ledger.beneficiary(&alice).unwrap();
+    assert_eq!(alice.granted, 1_500);
+    assert_eq!((alice.transferred_in, alice.transferred_out), (495, 800));
+    assert_eq!(alice.outstanding(), 1_195);
+    let changes: Vec<_> = alice
+        .entries
+        .iter()
+        .map(|entry| (entry.vesting_account, entry.change))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            (first, LedgerChange::Migrated(600)),
+            (first, LedgerChange::ToppedUp(400)),
+            (first, LedgerChange::TransferredOut(300)),
+            (second, LedgerChange::Granted(500)),
+            (second, LedgerChange::TransferredOut(500)),
+            (first, LedgerChange::TransferredIn(495)),
+        ]
+    );
+
+    let bob = ledger.beneficiary(&bob).unwrap();
+    assert_eq!(bob.transferred_in, 297);
+    assert_eq!(bob.entries[0].vesting_account, split_off);
+}
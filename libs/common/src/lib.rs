@@ -0,0 +1,3 @@
+pub mod merkle;
+pub mod token_utils;
+pub mod versioning;
@@ -0,0 +1,76 @@
+//! Keccak Merkle trees whose proofs programs can verify on chain.
+//!
+//! Leaves and inner nodes are hashed with different prefixes so an inner node can
+//! never be passed off as a leaf, and each pair is sorted before hashing so proofs
+//! need no left/right flags.
+
+use anchor_lang::solana_program::keccak::hashv;
+
+pub type Hash = [u8; 32];
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+pub fn hash_leaf(fields: &[&[u8]]) -> Hash {
+    let data = [&[LEAF_PREFIX], fields].concat();
+    hashv(&data).to_bytes()
+}
+
+pub fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Whether `proof` links `leaf` to `root`.
+pub fn verify(proof: &[Hash], root: &Hash, leaf: Hash) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+/// Tree built off chain to publish a root and hand out proofs.
+///
+/// A node without a sibling moves up a level unchanged, so its proof is one hash shorter.
+pub struct MerkleTree {
+    layers: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut layers = vec![leaves];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    /// Root of the tree, all zeroes when it has no leaves.
+    pub fn root(&self) -> Hash {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root.
+    pub fn proof(&self, mut index: usize) -> Vec<Hash> {
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
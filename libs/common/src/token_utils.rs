@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token::{self, MintTo, Transfer};
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TransferChecked};
+use spl_token_2022::extension::transfer_fee::{
+    instruction::harvest_withheld_tokens_to_mint, TransferFeeConfig,
+};
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+
+/// Moves `amount` tokens out of an account whose owner signed the transaction.
+pub fn transfer_from_user<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.clone(),
+        to: to.clone(),
+        authority: authority.clone(),
+    };
+    token::transfer(CpiContext::new(token_program.clone(), cpi_accounts), amount)
+}
+
+/// Moves `amount` tokens out of a vault owned by the PDA derived from `seeds` and `bump`.
+pub fn transfer_from_pda<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let bump = [bump];
+    let seeds = [seeds, &[&bump[..]]].concat();
+    let signer_seeds = [&seeds[..]];
+    let cpi_accounts = Transfer {
+        from: from.clone(),
+        to: to.clone(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, &signer_seeds);
+    token::transfer(cpi_ctx, amount)
+}
+
+/// Moves `amount` tokens of `mint` out of an account whose owner signed the transaction,
+/// through either SPL Token or Token-2022.
+pub fn transfer_checked_from_user<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from: from.clone(),
+        mint: mint.to_account_info(),
+        to: to.clone(),
+        authority: authority.clone(),
+    };
+    token_interface::transfer_checked(
+        CpiContext::new(token_program.clone(), cpi_accounts),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Moves `amount` tokens of `mint` out of a vault owned by the PDA derived from `seeds`
+/// and `bump`, through either SPL Token or Token-2022.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_from_pda<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let bump = [bump];
+    let seeds = [seeds, &[&bump[..]]].concat();
+    let signer_seeds = [&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: from.clone(),
+        mint: mint.to_account_info(),
+        to: to.clone(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, &signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+/// Moves the transfer fees withheld in `account` to its mint, which Token-2022 requires
+/// before the account can be closed. Does nothing for mints without transfer fees.
+pub fn harvest_withheld_fees<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let charges_fees = {
+        let data = mint.try_borrow_data()?;
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?
+            .get_extension::<TransferFeeConfig>()
+            .is_ok()
+    };
+    if !charges_fees {
+        return Ok(());
+    }
+    let ix = harvest_withheld_tokens_to_mint(token_program.key, mint.key, &[account.key])?;
+    invoke(&ix, &[mint.clone(), account.clone()])?;
+    Ok(())
+}
+
+/// Mints `amount` tokens of a mint whose authority is the PDA derived from `seeds` and `bump`.
+pub fn mint_from_pda<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let bump = [bump];
+    let seeds = [seeds, &[&bump[..]]].concat();
+    let signer_seeds = [&seeds[..]];
+    let cpi_accounts = MintTo {
+        mint: mint.clone(),
+        to: to.clone(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, &signer_seeds);
+    token::mint_to(cpi_ctx, amount)
+}
+
+/// Closes an empty token account of either token program owned by the PDA derived from
+/// `seeds` and `bump`, sending its rent to `destination`.
+pub fn close_pda_token_account<'info>(
+    token_program: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    bump: u8,
+) -> Result<()> {
+    let bump = [bump];
+    let seeds = [seeds, &[&bump[..]]].concat();
+    let signer_seeds = [&seeds[..]];
+    let cpi_accounts = CloseAccount {
+        account: account.clone(),
+        destination: destination.clone(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, &signer_seeds);
+    token_interface::close_account(cpi_ctx)
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+
+/// Account types that start with a `version: u8` header right after the
+/// discriminator and are allocated with an exact, known size.
+pub trait Versioned: AccountSerialize + Discriminator + Owner {
+    /// Layout version written by the current program.
+    const VERSION: u8;
+    /// Total allocation size, discriminator included.
+    const LEN: usize;
+}
+
+/// Rewrites an account stored in a legacy layout `L` into the current layout `T`.
+///
+/// The account must be owned by `T`'s program, carry `T`'s discriminator and be
+/// exactly `legacy_len` bytes long, which is how layouts without a version header
+/// are told apart. `upgrade` converts the decoded legacy state and may reject the
+/// migration (e.g. when the caller is not the account's owner). The account is
+/// resized to `T::LEN`, with rent topped up from or refunded to `payer`.
+pub fn migrate_account<'info, L, T>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    legacy_len: usize,
+    upgrade: impl FnOnce(L) -> Result<T>,
+) -> Result<T>
+where
+    L: AnchorDeserialize,
+    T: Versioned,
+{
+    let legacy = read_legacy_account::<L, T>(account, legacy_len)?;
+    let migrated = upgrade(legacy)?;
+
+    resize_account(account, payer, system_program, T::LEN)?;
+    let mut data = account.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])?;
+
+    Ok(migrated)
+}
+
+/// Decodes an account stored in a legacy layout `L` of `T` without modifying it.
+///
+/// Applies the same owner, discriminator and length checks as `migrate_account`,
+/// for migrations that move the state to a new address instead of upgrading in place.
+pub fn read_legacy_account<L, T>(account: &AccountInfo, legacy_len: usize) -> Result<L>
+where
+    L: AnchorDeserialize,
+    T: Versioned,
+{
+    require_keys_eq!(*account.owner, T::owner(), VersionError::InvalidAccount);
+    require!(
+        account.data_len() == legacy_len,
+        VersionError::AlreadyMigrated
+    );
+
+    let data = account.try_borrow_data()?;
+    require!(data[..8] == T::DISCRIMINATOR, VersionError::InvalidAccount);
+    Ok(L::deserialize(&mut &data[8..])?)
+}
+
+/// Closes a program-owned account, sending its lamports to `destination`.
+pub fn close_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = account.lamports();
+    **destination.try_borrow_mut_lamports()? += lamports;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+/// Reallocates a program-owned account to `new_len`, keeping it exactly rent exempt.
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(new_len);
+    let current = account.lamports();
+
+    if required > current {
+        let cpi_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        system_program::transfer(cpi_ctx, required - current)?;
+    } else if current > required {
+        // The program owns the account, so surplus rent can be moved directly
+        **account.try_borrow_mut_lamports()? = required;
+        **payer.try_borrow_mut_lamports()? += current - required;
+    }
+
+    account.realloc(new_len, false)?;
+    Ok(())
+}
+
+#[error_code(offset = 9000)]
+pub enum VersionError {
+    #[msg("Account is not owned by this program or has the wrong type.")]
+    InvalidAccount,
+    #[msg("Account already uses the current layout.")]
+    AlreadyMigrated,
+}
@@ -0,0 +1,512 @@
+//! Helpers for driving the workspace programs through `solana-program-test`.
+
+use std::future::Future;
+use std::sync::{Once, OnceLock};
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, Event, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use common::versioning::Versioned;
+use protocol_config::{Module, ProtocolConfig, CONFIG_SEED};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::account::{Account, AccountSharedData};
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::clock::Clock;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::program_stubs::{set_syscall_stubs, SyscallStubs};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config;
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+
+pub use solana_program_test;
+
+pub mod vesting;
+
+/// Address of the `ProtocolConfig` PDA that token-moving instructions check.
+pub fn protocol_config() -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_SEED], &protocol_config::ID).0
+}
+
+/// Wraps an Anchor `entry` function so `ProgramTest` can run it as a builtin.
+///
+/// Anchor ties the account slice to the accounts' own lifetime, which the
+/// builtin function signature cannot express, so the slice is leaked.
+#[macro_export]
+macro_rules! anchor_processor {
+    ($entry:path) => {
+        $crate::solana_program_test::processor!(
+            |program_id: &::anchor_lang::prelude::Pubkey,
+             accounts: &[::anchor_lang::prelude::AccountInfo],
+             data: &[u8]| {
+                let accounts = Box::leak(Box::new(accounts.to_vec()));
+                $entry(program_id, accounts, data)
+            }
+        )
+    };
+}
+
+/// Prefix of the log lines [`EventLogStubs`] writes for `sol_log_data`.
+const EVENT_LOG_PREFIX: &str = "Program log: data: ";
+
+static PROGRAM_TEST_STUBS: OnceLock<Box<dyn SyscallStubs>> = OnceLock::new();
+
+/// The syscall stubs `ProgramTest` installs for builtin programs, with
+/// `sol_log_data` written to the transaction logs instead of stdout so that
+/// `emit!`ted events can be read back.
+struct EventLogStubs;
+
+impl EventLogStubs {
+    fn inner(&self) -> &'static dyn SyscallStubs {
+        // Only empty while `TestContext::new` is swapping these stubs in
+        loop {
+            if let Some(stubs) = PROGRAM_TEST_STUBS.get() {
+                return stubs.as_ref();
+            }
+            std::thread::yield_now();
+        }
+    }
+}
+
+impl SyscallStubs for EventLogStubs {
+    fn sol_log(&self, message: &str) {
+        self.inner().sol_log(message)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<_> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.inner().sol_log(&format!("data: {}", fields.join(" ")))
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.inner()
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.inner().sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.inner().sol_set_return_data(data)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.inner().sol_get_stack_height()
+    }
+}
+
+pub type TxResult = Result<(), BanksClientError>;
+
+/// Runs an async test body from synchronous harnesses such as `proptest!`.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build a tokio runtime")
+        .block_on(future)
+}
+
+/// Builds an instruction from Anchor's generated account and data structs.
+pub fn instruction(
+    program_id: Pubkey,
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// A rent exempt account owned by `owner` holding `state`, padded to `len` bytes.
+pub fn program_account<T: AccountSerialize>(owner: Pubkey, state: &T, len: usize) -> Account {
+    let mut data = Vec::with_capacity(len);
+    state.try_serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(len),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Asserts that the first instruction failed with the given custom error code.
+pub fn assert_error(result: TxResult, code: impl Into<u32>) {
+    let err = result.expect_err("transaction should have failed").unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(code.into()))
+    );
+}
+
+pub struct TestContext {
+    pub context: ProgramTestContext,
+}
+
+impl TestContext {
+    pub fn new(context: ProgramTestContext) -> Self {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            let previous = set_syscall_stubs(Box::new(EventLogStubs));
+            let _ = PROGRAM_TEST_STUBS.set(previous);
+        });
+        Self { context }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Sends `instructions` paid by the context payer and signed by `signers`.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TxResult {
+        let blockhash = self
+            .context
+            .get_new_latest_blockhash()
+            .await
+            .expect("failed to get a blockhash");
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    /// Sends `instructions` like [`TestContext::process`] and returns the events of type
+    /// `E` they emitted, in order, as logged by `EventLogStubs`.
+    pub async fn process_events<E: Event>(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<E>, BanksClientError> {
+        let blockhash = self
+            .context
+            .get_new_latest_blockhash()
+            .await
+            .expect("failed to get a blockhash");
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let processed = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+        processed.result?;
+        let logs = processed
+            .metadata
+            .map(|metadata| metadata.log_messages)
+            .unwrap_or_default();
+        let events = logs
+            .iter()
+            .filter_map(|log| log.strip_prefix(EVENT_LOG_PREFIX))
+            .filter_map(|encoded| STANDARD.decode(encoded).ok())
+            .filter(|data| data.starts_with(&E::DISCRIMINATOR))
+            .map(|data| E::try_from_slice(&data[8..]).expect("malformed event"))
+            .collect();
+        Ok(events)
+    }
+
+    /// Simulates `instructions` paid by the context payer and returns the data the
+    /// last instruction set with `set_return_data`, if any.
+    pub async fn simulate_return_data(&mut self, instructions: &[Instruction]) -> Option<Vec<u8>> {
+        let blockhash = self
+            .context
+            .get_new_latest_blockhash()
+            .await
+            .expect("failed to get a blockhash");
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer],
+            blockhash,
+        );
+        let simulation = self
+            .context
+            .banks_client
+            .simulate_transaction(tx)
+            .await
+            .expect("failed to simulate the transaction");
+        simulation.result?.ok()?;
+        simulation
+            .simulation_details?
+            .return_data
+            .map(|data| data.data)
+    }
+
+    /// A new keypair funded with 10 SOL.
+    pub async fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        let ix = system_instruction::transfer(&self.payer(), &keypair.pubkey(), 10_000_000_000);
+        self.process(&[ix], &[]).await.unwrap();
+        keypair
+    }
+
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                Rent::default().minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &payer,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// A Token-2022 mint, charging `transfer_fee` as basis points and maximum fee when
+    /// given. The payer is its mint and fee authority.
+    pub async fn create_token_2022_mint(
+        &mut self,
+        decimals: u8,
+        transfer_fee: Option<(u16, u64)>,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let extensions: &[ExtensionType] = match transfer_fee {
+            Some(_) => &[ExtensionType::TransferFeeConfig],
+            None => &[],
+        };
+        let len =
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extensions)
+                .unwrap();
+        let mut instructions = vec![system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            Rent::default().minimum_balance(len),
+            len as u64,
+            &spl_token_2022::ID,
+        )];
+        if let Some((basis_points, maximum_fee)) = transfer_fee {
+            instructions.push(
+                initialize_transfer_fee_config(
+                    &spl_token_2022::ID,
+                    &mint.pubkey(),
+                    Some(&payer),
+                    Some(&payer),
+                    basis_points,
+                    maximum_fee,
+                )
+                .unwrap(),
+            );
+        }
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint(
+                &spl_token_2022::ID,
+                &mint.pubkey(),
+                &payer,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        );
+        self.process(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// The program owning `mint`, SPL Token or Token-2022.
+    pub async fn token_program(&mut self, mint: &Pubkey) -> Pubkey {
+        self.get_account(mint).await.expect("mint not found").owner
+    }
+
+    /// Creates a token account of either token program, sized for the extensions `mint`
+    /// requires of its accounts.
+    pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let mint_account = self.get_account(mint).await.expect("mint not found");
+        let token_program = mint_account.owner;
+        let len = if token_program == spl_token_2022::ID {
+            let state =
+                StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data)
+                    .unwrap();
+            let extensions = ExtensionType::get_required_init_account_extensions(
+                &state.get_extension_types().unwrap(),
+            );
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&extensions)
+                .unwrap()
+        } else {
+            spl_token::state::Account::LEN
+        };
+        let account = Keypair::new();
+        let payer = self.payer();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                Rent::default().minimum_balance(len),
+                len as u64,
+                &token_program,
+            ),
+            spl_token_2022::instruction::initialize_account(
+                &token_program,
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    pub async fn create_associated_token_account(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Pubkey,
+    ) -> Pubkey {
+        let token_program = self.token_program(mint).await;
+        let address = get_associated_token_address_with_program_id(owner, mint, &token_program);
+        let ix = Instruction {
+            program_id: associated_token::ID,
+            accounts: vec![
+                AccountMeta::new(self.payer(), true),
+                AccountMeta::new(address, false),
+                AccountMeta::new_readonly(*owner, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+                AccountMeta::new_readonly(token_program, false),
+            ],
+            data: vec![],
+        };
+        self.process(&[ix], &[]).await.unwrap();
+        address
+    }
+
+    /// Mints `amount` of a mint created by [`TestContext::create_mint`] or
+    /// [`TestContext::create_token_2022_mint`].
+    pub async fn mint_to(&mut self, mint: &Pubkey, account: &Pubkey, amount: u64) {
+        let payer = self.payer();
+        let token_program = self.token_program(mint).await;
+        let ix = spl_token_2022::instruction::mint_to(
+            &token_program,
+            mint,
+            account,
+            &payer,
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.process(&[ix], &[]).await.unwrap();
+    }
+
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        let account = self
+            .get_account(account)
+            .await
+            .expect("token account not found");
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    pub async fn get_account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn fetch<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self.get_account(address).await.expect("account not found");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn set_account(&mut self, address: &Pubkey, account: Account) {
+        self.context
+            .set_account(address, &AccountSharedData::from(account));
+    }
+
+    /// Installs a `ProtocolConfig` administered by the payer with `paused` modules paused.
+    pub fn set_protocol_config(&mut self, paused: &[Module]) {
+        let (address, bump) = Pubkey::find_program_address(&[CONFIG_SEED], &protocol_config::ID);
+        let config = ProtocolConfig {
+            version: ProtocolConfig::VERSION,
+            admin: self.payer(),
+            pending_admin: None,
+            fee_treasury: self.payer(),
+            paused: paused.iter().fold(0, |flags, module| flags | module.flag()),
+            bump,
+        };
+        let account = program_account(protocol_config::ID, &config, ProtocolConfig::LEN);
+        self.set_account(&address, account);
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.clock().await.unix_timestamp
+    }
+
+    /// Moves the cluster clock to `unix_timestamp`.
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock = self.clock().await;
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn warp_forward(&mut self, seconds: i64) {
+        let now = self.now().await;
+        self.warp_to_timestamp(now + seconds).await;
+    }
+}
@@ -0,0 +1,175 @@
+//! A `token_vesting` fixture shared by its test suites.
+
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use solana_program_test::{BanksClientError, ProgramTest};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use token_vesting::{accounts, instruction as ix, vesting_address, VestingKind};
+
+use crate::{instruction, protocol_config, TestContext};
+
+pub const CLIFF: i64 = 100;
+pub const DURATION: i64 = 1_000;
+
+/// A schedule created by [`create_schedule`], with a token account of its beneficiary.
+pub struct Schedule {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub beneficiary: Keypair,
+    pub beneficiary_token_account: Pubkey,
+    pub vesting_account: Pubkey,
+    pub vesting_vault: Pubkey,
+    pub start_time: i64,
+}
+
+impl Schedule {
+    /// Accounts claiming into the beneficiary's token account without unstaking.
+    pub fn claim_accounts(&self, claimer: Pubkey) -> accounts::ClaimTokens {
+        claim_accounts(
+            claimer,
+            self.vesting_account,
+            self.mint,
+            self.token_program,
+            self.beneficiary_token_account,
+        )
+    }
+
+    /// Claims `amount`, or everything claimable, into the beneficiary's token account.
+    pub fn claim_ix(&self, claimer: Pubkey, amount: Option<u64>) -> Instruction {
+        instruction(
+            token_vesting::ID,
+            self.claim_accounts(claimer),
+            ix::ClaimTokens { amount },
+        )
+    }
+}
+
+/// `ProgramTest` running `token_vesting`, for suites that add other programs.
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new(
+        "token_vesting",
+        token_vesting::ID,
+        crate::anchor_processor!(token_vesting::entry),
+    )
+}
+
+/// Starts `program_test` with every protocol module unpaused.
+pub async fn start(program_test: ProgramTest) -> TestContext {
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+    ctx
+}
+
+/// Arguments for an irrevocable linear schedule of `total_amount` with a [`CLIFF`] and
+/// lasting [`DURATION`]; adjust the rest with struct update syntax.
+pub fn terms(schedule_id: u64, start_time: i64, total_amount: u64) -> ix::InitializeVesting {
+    ix::InitializeVesting {
+        schedule_id,
+        start_time,
+        cliff_duration: CLIFF,
+        vesting_duration: DURATION,
+        total_amount,
+        revocable: false,
+        kind: VestingKind::Linear,
+    }
+}
+
+/// The associated token account holding the locked tokens of `vesting_account`.
+pub fn vesting_vault(vesting_account: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(vesting_account, mint, token_program)
+}
+
+/// Funds a token account of the payer with `terms.total_amount` and returns the
+/// instruction granting it to `beneficiary`, along with the schedule it creates.
+pub async fn initialize_ix(
+    ctx: &mut TestContext,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    terms: ix::InitializeVesting,
+) -> (Instruction, Schedule) {
+    let admin = ctx.payer();
+    let token_program = ctx.token_program(&mint).await;
+    let admin_token_account = ctx.create_token_account(&mint, &admin).await;
+    ctx.mint_to(&mint, &admin_token_account, terms.total_amount)
+        .await;
+    let beneficiary_token_account = ctx.create_token_account(&mint, &beneficiary.pubkey()).await;
+    let (vesting_account, _) = vesting_address(&mint, &beneficiary.pubkey(), terms.schedule_id);
+    let vesting_vault = vesting_vault(&vesting_account, &mint, &token_program);
+    let start_time = terms.start_time;
+
+    let init = instruction(
+        token_vesting::ID,
+        accounts::InitializeVesting {
+            admin,
+            admin_token_account,
+            mint,
+            vesting_account,
+            vesting_vault,
+            beneficiary: beneficiary.pubkey(),
+            config: protocol_config(),
+            token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        terms,
+    );
+    let schedule = Schedule {
+        mint,
+        token_program,
+        beneficiary,
+        beneficiary_token_account,
+        vesting_account,
+        vesting_vault,
+        start_time,
+    };
+    (init, schedule)
+}
+
+pub async fn try_create_schedule(
+    ctx: &mut TestContext,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    terms: ix::InitializeVesting,
+) -> Result<Schedule, BanksClientError> {
+    let (init, schedule) = initialize_ix(ctx, mint, beneficiary, terms).await;
+    ctx.process(&[init], &[]).await?;
+    Ok(schedule)
+}
+
+pub async fn create_schedule(
+    ctx: &mut TestContext,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    terms: ix::InitializeVesting,
+) -> Schedule {
+    try_create_schedule(ctx, mint, beneficiary, terms)
+        .await
+        .unwrap()
+}
+
+/// Accounts claiming from `vesting_account` into `destination` without unstaking.
+pub fn claim_accounts(
+    claimer: Pubkey,
+    vesting_account: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    destination: Pubkey,
+) -> accounts::ClaimTokens {
+    accounts::ClaimTokens {
+        claimer,
+        vesting_account,
+        mint,
+        vesting_vault: vesting_vault(&vesting_account, &mint, &token_program),
+        destination,
+        config: protocol_config(),
+        token_program,
+        staking_program: None,
+        staking_vault: None,
+        staking_vault_authority: None,
+        stake_state: None,
+        system_program: None,
+    }
+}
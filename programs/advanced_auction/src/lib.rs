@@ -0,0 +1,546 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::token_utils::{transfer_from_pda, transfer_from_user};
+use common::versioning::{migrate_account, Versioned};
+use protocol_config::{Module, ProtocolConfig, ProtocolError, CONFIG_SEED};
+
+declare_id!("7vnC9afGFUodyHvRNDweWTWvL73KGkM51PohoPR6ZK4H");
+
+pub const AUCTION_AUTHORITY_SEED: &[u8] = b"auction-authority";
+
+#[program]
+pub mod advanced_auction {
+    use super::*;
+
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        start_price: u64,
+        reserve_price: u64,
+        buy_now_price: Option<u64>,
+        duration: i64,
+        platform_fee: u64,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        auction.version = Auction::VERSION;
+        auction.seller = ctx.accounts.seller.key();
+        auction.nft_token_account = ctx.accounts.nft_token_account.key();
+        auction.start_price = start_price;
+        auction.reserve_price = reserve_price;
+        auction.buy_now_price = buy_now_price;
+        auction.highest_bid = 0;
+        auction.highest_bidder = Pubkey::default();
+        auction.end_time = current_time + duration;
+        auction.is_active = true;
+        auction.platform_fee = platform_fee;
+        auction.authority_bump = ctx.bumps.auction_authority;
+        auction.auction_vault = ctx.accounts.auction_vault.key();
+        auction.bid_mint = ctx.accounts.bid_mint.key();
+        auction.bid_vault = ctx.accounts.bid_vault.key();
+
+        // Transfer the NFT to the auction vault
+        transfer_from_user(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.nft_token_account.to_account_info(),
+            &ctx.accounts.auction_vault.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            1,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        let auction_key = ctx.accounts.auction.key();
+        let auction = &mut ctx.accounts.auction;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(auction.is_active, ErrorCode::AuctionNotActive);
+        require!(current_time < auction.end_time, ErrorCode::AuctionEnded);
+        require!(amount > auction.highest_bid, ErrorCode::BidTooLow);
+
+        // Escrow the new bid
+        transfer_from_user(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.bidder_token_account.to_account_info(),
+            &ctx.accounts.bid_vault.to_account_info(),
+            &ctx.accounts.bidder.to_account_info(),
+            amount,
+        )?;
+
+        // Refund the previous highest bidder
+        if auction.highest_bid > 0 {
+            transfer_from_pda(
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.bid_vault.to_account_info(),
+                &ctx.accounts.highest_bidder_token_account.to_account_info(),
+                &ctx.accounts.auction_authority.to_account_info(),
+                &[AUCTION_AUTHORITY_SEED, auction_key.as_ref()],
+                auction.authority_bump,
+                auction.highest_bid,
+            )?;
+        }
+
+        // Accept the new bid
+        auction.highest_bid = amount;
+        auction.highest_bidder = ctx.accounts.bidder.key();
+
+        // Handle Buy Now option
+        if let Some(buy_now_price) = auction.buy_now_price {
+            if amount >= buy_now_price {
+                auction.end_time = current_time; // End the auction immediately
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+        let auction_key = ctx.accounts.auction.key();
+        let auction = &mut ctx.accounts.auction;
+        let bidder_key = ctx.accounts.bidder.key();
+
+        require!(auction.is_active, ErrorCode::AuctionNotActive);
+        require!(auction.highest_bidder == bidder_key, ErrorCode::NotHighestBidder);
+
+        let amount = auction.highest_bid;
+
+        // Transfer back the highest bid to the bidder
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.bid_vault.to_account_info(),
+            &ctx.accounts.bidder_token_account.to_account_info(),
+            &ctx.accounts.auction_authority.to_account_info(),
+            &[AUCTION_AUTHORITY_SEED, auction_key.as_ref()],
+            auction.authority_bump,
+            amount,
+        )?;
+
+        // Reset highest bidder and bid
+        auction.highest_bidder = Pubkey::default();
+        auction.highest_bid = 0;
+
+        Ok(())
+    }
+
+    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+        let auction_key = ctx.accounts.auction.key();
+        let auction = &mut ctx.accounts.auction;
+        require!(auction.highest_bid == 0, ErrorCode::BidsAlreadyPlaced);
+        
+        // Transfer the NFT back to the seller
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.auction_vault.to_account_info(),
+            &ctx.accounts.seller_nft_account.to_account_info(),
+            &ctx.accounts.auction_authority.to_account_info(),
+            &[AUCTION_AUTHORITY_SEED, auction_key.as_ref()],
+            auction.authority_bump,
+            1,
+        )?;
+
+        auction.is_active = false;
+
+        Ok(())
+    }
+
+    pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
+        let auction_key = ctx.accounts.auction.key();
+        let auction = &mut ctx.accounts.auction;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(auction.is_active, ErrorCode::AuctionNotActive);
+        require!(current_time >= auction.end_time, ErrorCode::AuctionNotEnded);
+
+        // Ensure reserve price is met
+        if auction.highest_bid < auction.reserve_price {
+            return Err(error!(ErrorCode::ReservePriceNotMet));
+        }
+
+        auction.is_active = false;
+
+        // Transfer platform fee to platform account
+        let platform_fee = (auction.highest_bid * auction.platform_fee) / 100;
+        let seller_amount = auction.highest_bid - platform_fee;
+
+        // Transfer NFT to highest bidder
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.auction_vault.to_account_info(),
+            &ctx.accounts.highest_bidder_nft_account.to_account_info(),
+            &ctx.accounts.auction_authority.to_account_info(),
+            &[AUCTION_AUTHORITY_SEED, auction_key.as_ref()],
+            auction.authority_bump,
+            1,
+        )?;
+
+        // Transfer funds to seller
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.bid_vault.to_account_info(),
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.auction_authority.to_account_info(),
+            &[AUCTION_AUTHORITY_SEED, auction_key.as_ref()],
+            auction.authority_bump,
+            seller_amount,
+        )?;
+
+        // Transfer platform fee to the platform
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.bid_vault.to_account_info(),
+            &ctx.accounts.platform_account.to_account_info(),
+            &ctx.accounts.auction_authority.to_account_info(),
+            &[AUCTION_AUTHORITY_SEED, auction_key.as_ref()],
+            auction.authority_bump,
+            platform_fee,
+        )?;
+
+        Ok(())
+    }
+
+    /// Rewrites an auction stored in any earlier layout into the current one.
+    pub fn migrate_auction(ctx: Context<MigrateAuction>) -> Result<()> {
+        let info = ctx.accounts.auction.to_account_info();
+        let payer = ctx.accounts.seller.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let seller = ctx.accounts.seller.key();
+        let (_, authority_bump) = auction_authority_address(info.key);
+
+        let migrated = if info.data_len() == AuctionV0::LEN {
+            migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                AuctionV0::LEN,
+                |legacy: AuctionV0| {
+                    require_keys_eq!(legacy.seller, seller, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade(authority_bump))
+                },
+            )?
+        } else {
+            migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                AuctionV1::LEN,
+                |legacy: AuctionV1| {
+                    require_keys_eq!(legacy.seller, seller, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade(authority_bump))
+                },
+            )?
+        };
+
+        msg!("Auction migrated to version {}", migrated.version);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(mut)]
+    pub nft_token_account: Account<'info, TokenAccount>,
+    /// Escrows the NFT until the auction is cancelled or settled
+    #[account(
+        mut,
+        token::mint = nft_token_account.mint,
+        token::authority = auction_authority,
+    )]
+    pub auction_vault: Account<'info, TokenAccount>,
+    /// Mint bids are paid in
+    pub bid_mint: Account<'info, Mint>,
+    /// Escrows the highest bid until it is refunded or paid out
+    #[account(token::mint = bid_mint, token::authority = auction_authority)]
+    pub bid_vault: Account<'info, TokenAccount>,
+    #[account(init, payer = seller, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: PDA signer for the vaults of this auction
+    #[account(seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()], bump)]
+    pub auction_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Auction) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(mut, token::mint = auction.bid_mint)]
+    pub bidder_token_account: Account<'info, TokenAccount>,
+    /// Refunded the bid being outbid; ignored on the first bid
+    #[account(
+        mut,
+        token::mint = auction.bid_mint,
+        constraint = auction.highest_bid == 0
+            || highest_bidder_token_account.owner == auction.highest_bidder
+            @ ErrorCode::NotHighestBidderAccount,
+    )]
+    pub highest_bidder_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = auction.bid_vault)]
+    pub bid_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: PDA signer for the vaults of this auction
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump = auction.authority_bump,
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Auction) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(mut, token::mint = auction.bid_mint)]
+    pub bidder_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = auction.bid_vault)]
+    pub bid_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: PDA signer for the vaults of this auction
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump = auction.authority_bump,
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Auction) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(mut, token::mint = auction_vault.mint)]
+    pub seller_nft_account: Account<'info, TokenAccount>,
+    #[account(mut, address = auction.auction_vault)]
+    pub auction_vault: Account<'info, TokenAccount>,
+    #[account(mut, has_one = seller @ ErrorCode::Unauthorized)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: PDA signer for the vaults of this auction
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump = auction.authority_bump,
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Auction) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct EndAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(mut, token::mint = auction.bid_mint)]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = auction_vault.mint,
+        constraint = highest_bidder_nft_account.owner == auction.highest_bidder
+            @ ErrorCode::NotHighestBidderAccount,
+    )]
+    pub highest_bidder_nft_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = auction.bid_mint,
+        constraint = platform_account.owner == config.fee_treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub platform_account: Account<'info, TokenAccount>,
+    #[account(mut, address = auction.auction_vault)]
+    pub auction_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = auction.bid_vault)]
+    pub bid_vault: Account<'info, TokenAccount>,
+    #[account(mut, has_one = seller @ ErrorCode::Unauthorized)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: PDA signer for the vaults of this auction
+    #[account(
+        seeds = [AUCTION_AUTHORITY_SEED, auction.key().as_ref()],
+        bump = auction.authority_bump,
+    )]
+    pub auction_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Auction) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    /// CHECK: Owner, discriminator and seller are validated against the legacy layout
+    #[account(mut)]
+    pub auction: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Auction {
+    pub version: u8,
+    pub seller: Pubkey,
+    pub nft_token_account: Pubkey,
+    pub start_price: u64,
+    pub reserve_price: u64,
+    pub highest_bid: u64,
+    pub highest_bidder: Pubkey,
+    pub end_time: i64,
+    pub is_active: bool,
+    pub platform_fee: u64,
+    pub buy_now_price: Option<u64>,
+    /// Bump of the auction's own vault authority
+    pub authority_bump: u8,
+    pub auction_vault: Pubkey,
+    pub bid_mint: Pubkey,
+    pub bid_vault: Pubkey,
+}
+
+impl Versioned for Auction {
+    const VERSION: u8 = 2;
+    const LEN: usize = 8 + 1 + 32 + 32 + 8 + 8 + 8 + 32 + 8 + 1 + 8 + (1 + 8) + 1 + 32 + 32 + 32;
+}
+
+/// Layout of `Auction` before the version header.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AuctionV0 {
+    pub seller: Pubkey,
+    pub nft_token_account: Pubkey,
+    pub start_price: u64,
+    pub reserve_price: u64,
+    pub highest_bid: u64,
+    pub highest_bidder: Pubkey,
+    pub end_time: i64,
+    pub is_active: bool,
+    pub platform_fee: u64,
+    pub buy_now_price: Option<u64>,
+}
+
+impl AuctionV0 {
+    pub const LEN: usize = 8 + 200;
+
+    pub fn upgrade(self, authority_bump: u8) -> Auction {
+        Auction {
+            version: Auction::VERSION,
+            seller: self.seller,
+            nft_token_account: self.nft_token_account,
+            start_price: self.start_price,
+            reserve_price: self.reserve_price,
+            highest_bid: self.highest_bid,
+            highest_bidder: self.highest_bidder,
+            end_time: self.end_time,
+            is_active: self.is_active,
+            platform_fee: self.platform_fee,
+            buy_now_price: self.buy_now_price,
+            authority_bump,
+            // Earlier vaults belong to the program-wide authority, which no longer signs
+            auction_vault: Pubkey::default(),
+            bid_mint: Pubkey::default(),
+            bid_vault: Pubkey::default(),
+        }
+    }
+}
+
+/// Version 1 layout of `Auction`, before the auction authority bump was stored.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AuctionV1 {
+    pub version: u8,
+    pub seller: Pubkey,
+    pub nft_token_account: Pubkey,
+    pub start_price: u64,
+    pub reserve_price: u64,
+    pub highest_bid: u64,
+    pub highest_bidder: Pubkey,
+    pub end_time: i64,
+    pub is_active: bool,
+    pub platform_fee: u64,
+    pub buy_now_price: Option<u64>,
+}
+
+impl AuctionV1 {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 + 8 + 8 + 32 + 8 + 1 + 8 + (1 + 8);
+
+    pub fn upgrade(self, authority_bump: u8) -> Auction {
+        Auction {
+            version: Auction::VERSION,
+            seller: self.seller,
+            nft_token_account: self.nft_token_account,
+            start_price: self.start_price,
+            reserve_price: self.reserve_price,
+            highest_bid: self.highest_bid,
+            highest_bidder: self.highest_bidder,
+            end_time: self.end_time,
+            is_active: self.is_active,
+            platform_fee: self.platform_fee,
+            buy_now_price: self.buy_now_price,
+            authority_bump,
+            // Earlier vaults belong to the program-wide authority, which no longer signs
+            auction_vault: Pubkey::default(),
+            bid_mint: Pubkey::default(),
+            bid_vault: Pubkey::default(),
+        }
+    }
+}
+
+/// Owner of the NFT and bid vaults of `auction`.
+pub fn auction_authority_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_AUTHORITY_SEED, auction.as_ref()], &ID)
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Auction is not active")]
+    AuctionNotActive,
+    #[msg("Auction has already ended")]
+    AuctionEnded,
+    #[msg("Bid is too low")]
+    BidTooLow,
+    #[msg("Reserve price was not met")]
+    ReservePriceNotMet,
+    #[msg("Bids have already been placed, cannot cancel")]
+    BidsAlreadyPlaced,
+    #[msg("Only the highest bidder can withdraw their bid")]
+    NotHighestBidder,
+    #[msg("Auction has not ended yet")]
+    AuctionNotEnded,
+    #[msg("Only the seller can perform this action")]
+    Unauthorized,
+    #[msg("Token account is not owned by the highest bidder")]
+    NotHighestBidderAccount,
+    #[msg("Platform account is not owned by the fee treasury")]
+    InvalidTreasury,
+}
\ No newline at end of file
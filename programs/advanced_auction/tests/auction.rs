@@ -0,0 +1,448 @@
+use advanced_auction::{
+    accounts, auction_authority_address, instruction as ix, Auction, ErrorCode,
+};
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use common::versioning::Versioned;
+use protocol_config::{Module, ProtocolError};
+use solana_program_test::ProgramTest;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::{
+    anchor_processor, assert_error, instruction, program_account, protocol_config, TestContext,
+    TxResult,
+};
+
+const DURATION: i64 = 3_600;
+const RESERVE_PRICE: u64 = 500;
+const BUY_NOW_PRICE: u64 = 10_000;
+const BIDDER_FUNDS: u64 = 100_000;
+
+struct Listing {
+    seller: Keypair,
+    auction: Pubkey,
+    auction_authority: Pubkey,
+    nft_mint: Pubkey,
+    seller_nft_account: Pubkey,
+    seller_bid_account: Pubkey,
+    treasury_bid_account: Pubkey,
+    auction_vault: Pubkey,
+    bid_vault: Pubkey,
+    bidder: Keypair,
+    bidder_nft_account: Pubkey,
+    bidder_bid_account: Pubkey,
+}
+
+async fn setup() -> (TestContext, Listing) {
+    let program_test = ProgramTest::new(
+        "advanced_auction",
+        advanced_auction::ID,
+        anchor_processor!(advanced_auction::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+    let listing = create_listing(&mut ctx).await;
+    (ctx, listing)
+}
+
+async fn create_listing(ctx: &mut TestContext) -> Listing {
+    let auction = Keypair::new();
+    let (auction_authority, _) = auction_authority_address(&auction.pubkey());
+
+    let seller = ctx.funded_keypair().await;
+    let nft_mint = ctx.create_mint(0).await;
+    let seller_nft_account = ctx.create_token_account(&nft_mint, &seller.pubkey()).await;
+    let auction_vault = ctx
+        .create_token_account(&nft_mint, &auction_authority)
+        .await;
+    ctx.mint_to(&nft_mint, &seller_nft_account, 1).await;
+
+    let bid_mint = ctx.create_mint(6).await;
+    let seller_bid_account = ctx.create_token_account(&bid_mint, &seller.pubkey()).await;
+    let bid_vault = ctx
+        .create_token_account(&bid_mint, &auction_authority)
+        .await;
+    let treasury = ctx.payer();
+    let treasury_bid_account = ctx.create_token_account(&bid_mint, &treasury).await;
+
+    let bidder = ctx.funded_keypair().await;
+    let bidder_nft_account = ctx.create_token_account(&nft_mint, &bidder.pubkey()).await;
+    let bidder_bid_account = ctx.create_token_account(&bid_mint, &bidder.pubkey()).await;
+    ctx.mint_to(&bid_mint, &bidder_bid_account, BIDDER_FUNDS)
+        .await;
+
+    let create = instruction(
+        advanced_auction::ID,
+        accounts::CreateAuction {
+            seller: seller.pubkey(),
+            nft_token_account: seller_nft_account,
+            auction_vault,
+            bid_mint,
+            bid_vault,
+            auction: auction.pubkey(),
+            auction_authority,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::CreateAuction {
+            start_price: 100,
+            reserve_price: RESERVE_PRICE,
+            buy_now_price: Some(BUY_NOW_PRICE),
+            duration: DURATION,
+            platform_fee: 5,
+        },
+    );
+    ctx.process(&[create], &[&seller, &auction]).await.unwrap();
+
+    Listing {
+        seller,
+        auction: auction.pubkey(),
+        auction_authority,
+        nft_mint,
+        seller_nft_account,
+        seller_bid_account,
+        treasury_bid_account,
+        auction_vault,
+        bid_vault,
+        bidder,
+        bidder_nft_account,
+        bidder_bid_account,
+    }
+}
+
+async fn place_bid(ctx: &mut TestContext, listing: &Listing, amount: u64) -> TxResult {
+    let bid = instruction(
+        advanced_auction::ID,
+        accounts::PlaceBid {
+            bidder: listing.bidder.pubkey(),
+            bidder_token_account: listing.bidder_bid_account,
+            highest_bidder_token_account: listing.bidder_bid_account,
+            bid_vault: listing.bid_vault,
+            auction: listing.auction,
+            auction_authority: listing.auction_authority,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::PlaceBid { amount },
+    );
+    ctx.process(&[bid], &[&listing.bidder]).await
+}
+
+fn withdraw_accounts(listing: &Listing, bidder: &Keypair) -> accounts::WithdrawBid {
+    accounts::WithdrawBid {
+        bidder: bidder.pubkey(),
+        bidder_token_account: listing.bidder_bid_account,
+        bid_vault: listing.bid_vault,
+        auction: listing.auction,
+        auction_authority: listing.auction_authority,
+        config: protocol_config(),
+        token_program: spl_token::ID,
+    }
+}
+
+async fn withdraw_bid(ctx: &mut TestContext, listing: &Listing, bidder: &Keypair) -> TxResult {
+    let withdraw = instruction(
+        advanced_auction::ID,
+        withdraw_accounts(listing, bidder),
+        ix::WithdrawBid {},
+    );
+    ctx.process(&[withdraw], &[bidder]).await
+}
+
+fn cancel_accounts(listing: &Listing, seller: &Keypair) -> accounts::CancelAuction {
+    accounts::CancelAuction {
+        seller: seller.pubkey(),
+        seller_nft_account: listing.seller_nft_account,
+        auction_vault: listing.auction_vault,
+        auction: listing.auction,
+        auction_authority: listing.auction_authority,
+        config: protocol_config(),
+        token_program: spl_token::ID,
+    }
+}
+
+async fn cancel_auction(ctx: &mut TestContext, listing: &Listing) -> TxResult {
+    let cancel = instruction(
+        advanced_auction::ID,
+        cancel_accounts(listing, &listing.seller),
+        ix::CancelAuction {},
+    );
+    ctx.process(&[cancel], &[&listing.seller]).await
+}
+
+fn end_accounts(listing: &Listing) -> accounts::EndAuction {
+    accounts::EndAuction {
+        seller: listing.seller.pubkey(),
+        seller_token_account: listing.seller_bid_account,
+        highest_bidder_nft_account: listing.bidder_nft_account,
+        platform_account: listing.treasury_bid_account,
+        auction_vault: listing.auction_vault,
+        bid_vault: listing.bid_vault,
+        auction: listing.auction,
+        auction_authority: listing.auction_authority,
+        config: protocol_config(),
+        token_program: spl_token::ID,
+    }
+}
+
+async fn end_auction(ctx: &mut TestContext, listing: &Listing) -> TxResult {
+    let end = instruction(
+        advanced_auction::ID,
+        end_accounts(listing),
+        ix::EndAuction {},
+    );
+    ctx.process(&[end], &[&listing.seller]).await
+}
+
+async fn deactivate(ctx: &mut TestContext, listing: &Listing) {
+    let mut auction: Auction = ctx.fetch(&listing.auction).await;
+    auction.is_active = false;
+    let account = program_account(advanced_auction::ID, &auction, Auction::LEN);
+    ctx.set_account(&listing.auction, account);
+}
+
+#[tokio::test]
+async fn create_auction_escrows_nft() {
+    let (mut ctx, listing) = setup().await;
+    let now = ctx.now().await;
+
+    let auction: Auction = ctx.fetch(&listing.auction).await;
+    assert_eq!(auction.seller, listing.seller.pubkey());
+    assert_eq!(auction.nft_token_account, listing.seller_nft_account);
+    assert_eq!(auction.reserve_price, RESERVE_PRICE);
+    assert_eq!(auction.buy_now_price, Some(BUY_NOW_PRICE));
+    assert_eq!(auction.end_time, now + DURATION);
+    assert!(auction.is_active);
+    assert_eq!(ctx.token_balance(&listing.auction_vault).await, 1);
+    assert_eq!(ctx.token_balance(&listing.seller_nft_account).await, 0);
+}
+
+#[tokio::test]
+async fn first_bid_becomes_highest() {
+    let (mut ctx, listing) = setup().await;
+
+    place_bid(&mut ctx, &listing, 200).await.unwrap();
+
+    let auction: Auction = ctx.fetch(&listing.auction).await;
+    assert_eq!(auction.highest_bid, 200);
+    assert_eq!(auction.highest_bidder, listing.bidder.pubkey());
+    assert_eq!(ctx.token_balance(&listing.bid_vault).await, 200);
+    assert_eq!(
+        ctx.token_balance(&listing.bidder_bid_account).await,
+        BIDDER_FUNDS - 200
+    );
+}
+
+#[tokio::test]
+async fn outbid_refunds_previous_bid() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, 200).await.unwrap();
+
+    place_bid(&mut ctx, &listing, 300).await.unwrap();
+
+    assert_eq!(ctx.token_balance(&listing.bid_vault).await, 300);
+    assert_eq!(
+        ctx.token_balance(&listing.bidder_bid_account).await,
+        BIDDER_FUNDS - 300
+    );
+}
+
+#[tokio::test]
+async fn withdraw_refunds_bid() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, 200).await.unwrap();
+
+    let bidder = listing.bidder.insecure_clone();
+    withdraw_bid(&mut ctx, &listing, &bidder).await.unwrap();
+
+    let auction: Auction = ctx.fetch(&listing.auction).await;
+    assert_eq!(auction.highest_bid, 0);
+    assert_eq!(ctx.token_balance(&listing.bid_vault).await, 0);
+    assert_eq!(
+        ctx.token_balance(&listing.bidder_bid_account).await,
+        BIDDER_FUNDS
+    );
+}
+
+#[tokio::test]
+async fn bid_must_exceed_highest_bid() {
+    let (mut ctx, listing) = setup().await;
+    let result = place_bid(&mut ctx, &listing, 0).await;
+    assert_error(result, ErrorCode::BidTooLow);
+}
+
+#[tokio::test]
+async fn bid_after_end_time_fails() {
+    let (mut ctx, listing) = setup().await;
+    ctx.warp_forward(DURATION).await;
+
+    let result = place_bid(&mut ctx, &listing, 200).await;
+    assert_error(result, ErrorCode::AuctionEnded);
+}
+
+#[tokio::test]
+async fn buy_now_bid_ends_auction() {
+    let (mut ctx, listing) = setup().await;
+    let now = ctx.now().await;
+
+    place_bid(&mut ctx, &listing, BUY_NOW_PRICE).await.unwrap();
+
+    let auction: Auction = ctx.fetch(&listing.auction).await;
+    assert_eq!(auction.end_time, now);
+    let result = place_bid(&mut ctx, &listing, BUY_NOW_PRICE + 1).await;
+    assert_error(result, ErrorCode::AuctionEnded);
+}
+
+#[tokio::test]
+async fn bid_on_inactive_auction_fails() {
+    let (mut ctx, listing) = setup().await;
+    deactivate(&mut ctx, &listing).await;
+
+    let result = place_bid(&mut ctx, &listing, 200).await;
+    assert_error(result, ErrorCode::AuctionNotActive);
+}
+
+#[tokio::test]
+async fn withdraw_by_other_bidder_fails() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, 200).await.unwrap();
+
+    let other = ctx.funded_keypair().await;
+    let result = withdraw_bid(&mut ctx, &listing, &other).await;
+    assert_error(result, ErrorCode::NotHighestBidder);
+}
+
+#[tokio::test]
+async fn cancel_after_bid_fails() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, 200).await.unwrap();
+
+    let result = cancel_auction(&mut ctx, &listing).await;
+    assert_error(result, ErrorCode::BidsAlreadyPlaced);
+}
+
+#[tokio::test]
+async fn cancel_returns_nft_to_seller() {
+    let (mut ctx, listing) = setup().await;
+
+    cancel_auction(&mut ctx, &listing).await.unwrap();
+
+    let auction: Auction = ctx.fetch(&listing.auction).await;
+    assert!(!auction.is_active);
+    assert_eq!(ctx.token_balance(&listing.seller_nft_account).await, 1);
+}
+
+#[tokio::test]
+async fn end_pays_seller_and_delivers_nft() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, 1_000).await.unwrap();
+    ctx.warp_forward(DURATION).await;
+
+    end_auction(&mut ctx, &listing).await.unwrap();
+
+    assert_eq!(ctx.token_balance(&listing.seller_bid_account).await, 950);
+    assert_eq!(ctx.token_balance(&listing.treasury_bid_account).await, 50);
+    assert_eq!(ctx.token_balance(&listing.bid_vault).await, 0);
+    assert_eq!(ctx.token_balance(&listing.bidder_nft_account).await, 1);
+    assert_eq!(ctx.token_balance(&listing.auction_vault).await, 0);
+}
+
+#[tokio::test]
+async fn cancel_by_other_signer_fails() {
+    let (mut ctx, listing) = setup().await;
+    let intruder = ctx.funded_keypair().await;
+    let intruder_nft_account = ctx
+        .create_token_account(&listing.nft_mint, &intruder.pubkey())
+        .await;
+
+    let mut accounts = cancel_accounts(&listing, &intruder);
+    accounts.seller_nft_account = intruder_nft_account;
+    let cancel = instruction(advanced_auction::ID, accounts, ix::CancelAuction {});
+    let result = ctx.process(&[cancel], &[&intruder]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn end_to_account_of_other_bidder_fails() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, 1_000).await.unwrap();
+    ctx.warp_forward(DURATION).await;
+
+    let mut accounts = end_accounts(&listing);
+    accounts.highest_bidder_nft_account = listing.seller_nft_account;
+    let end = instruction(advanced_auction::ID, accounts, ix::EndAuction {});
+    let result = ctx.process(&[end], &[&listing.seller]).await;
+    assert_error(result, ErrorCode::NotHighestBidderAccount);
+}
+
+#[tokio::test]
+async fn end_fee_outside_treasury_fails() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, 1_000).await.unwrap();
+    ctx.warp_forward(DURATION).await;
+
+    let mut accounts = end_accounts(&listing);
+    accounts.platform_account = listing.seller_bid_account;
+    let end = instruction(advanced_auction::ID, accounts, ix::EndAuction {});
+    let result = ctx.process(&[end], &[&listing.seller]).await;
+    assert_error(result, ErrorCode::InvalidTreasury);
+}
+
+#[tokio::test]
+async fn withdraw_from_other_auction_vault_fails() {
+    let (mut ctx, listing) = setup().await;
+    let other = create_listing(&mut ctx).await;
+    place_bid(&mut ctx, &other, 200).await.unwrap();
+    place_bid(&mut ctx, &listing, 1).await.unwrap();
+
+    let mut accounts = withdraw_accounts(&listing, &listing.bidder);
+    accounts.bid_vault = other.bid_vault;
+    let withdraw = instruction(advanced_auction::ID, accounts, ix::WithdrawBid {});
+    let result = ctx.process(&[withdraw], &[&listing.bidder]).await;
+    assert_error(result, anchor_lang::error::ErrorCode::ConstraintAddress);
+    assert_eq!(ctx.token_balance(&other.bid_vault).await, 200);
+}
+
+#[tokio::test]
+async fn end_before_end_time_fails() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, RESERVE_PRICE).await.unwrap();
+    ctx.warp_forward(DURATION - 1).await;
+
+    let result = end_auction(&mut ctx, &listing).await;
+    assert_error(result, ErrorCode::AuctionNotEnded);
+}
+
+#[tokio::test]
+async fn end_below_reserve_fails() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, RESERVE_PRICE - 1)
+        .await
+        .unwrap();
+    ctx.warp_forward(DURATION).await;
+
+    let result = end_auction(&mut ctx, &listing).await;
+    assert_error(result, ErrorCode::ReservePriceNotMet);
+}
+
+#[tokio::test]
+async fn end_inactive_auction_fails() {
+    let (mut ctx, listing) = setup().await;
+    place_bid(&mut ctx, &listing, RESERVE_PRICE).await.unwrap();
+    deactivate(&mut ctx, &listing).await;
+    ctx.warp_forward(DURATION).await;
+
+    let result = end_auction(&mut ctx, &listing).await;
+    assert_error(result, ErrorCode::AuctionNotActive);
+}
+
+#[tokio::test]
+async fn bid_while_paused_fails() {
+    let (mut ctx, listing) = setup().await;
+    ctx.set_protocol_config(&[Module::Auction]);
+
+    let result = place_bid(&mut ctx, &listing, 200).await;
+    assert_error(result, ProtocolError::ModulePaused);
+}
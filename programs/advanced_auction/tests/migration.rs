@@ -0,0 +1,167 @@
+use advanced_auction::{auction_authority_address, Auction, AuctionV0, AuctionV1};
+use anchor_lang::{
+    AccountDeserialize, AnchorSerialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use common::versioning::Versioned;
+use solana_program_test::{BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use test_utils::anchor_processor;
+
+fn legacy_auction(seller: Pubkey) -> AuctionV0 {
+    AuctionV0 {
+        seller,
+        nft_token_account: Pubkey::new_unique(),
+        start_price: 10,
+        reserve_price: 50,
+        highest_bid: 75,
+        highest_bidder: Pubkey::new_unique(),
+        end_time: 1_000,
+        is_active: true,
+        platform_fee: 5,
+        buy_now_price: Some(200),
+    }
+}
+
+fn legacy_account<T: AnchorSerialize>(state: &T, len: usize) -> Account {
+    let mut data = Auction::DISCRIMINATOR.to_vec();
+    data.extend(state.try_to_vec().unwrap());
+    data.resize(len, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: advanced_auction::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn migrate<T: AnchorSerialize>(
+    signer: &Keypair,
+    auction: Pubkey,
+    state: &T,
+    len: usize,
+) -> (Result<(), BanksClientError>, Option<Account>) {
+    let mut program_test = ProgramTest::new(
+        "advanced_auction",
+        advanced_auction::ID,
+        anchor_processor!(advanced_auction::entry),
+    );
+    program_test.add_account(auction, legacy_account(state, len));
+    program_test.add_account(
+        signer.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::ID),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let ix = Instruction {
+        program_id: advanced_auction::ID,
+        accounts: advanced_auction::accounts::MigrateAuction {
+            seller: signer.pubkey(),
+            auction,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: advanced_auction::instruction::MigrateAuction {}.data(),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer, signer],
+        recent_blockhash,
+    );
+    let result = banks_client.process_transaction(tx).await;
+    let account = banks_client.get_account(auction).await.unwrap();
+    (result, account)
+}
+
+#[tokio::test]
+async fn migrates_legacy_auction() {
+    let seller = Keypair::new();
+    let auction = Pubkey::new_unique();
+    let legacy = legacy_auction(seller.pubkey());
+
+    let (result, account) = migrate(&seller, auction, &legacy, AuctionV0::LEN).await;
+    result.unwrap();
+
+    let account = account.unwrap();
+    assert_eq!(account.data.len(), Auction::LEN);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(Auction::LEN)
+    );
+
+    let migrated = Auction::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, Auction::VERSION);
+    assert_eq!(migrated.seller, legacy.seller);
+    assert_eq!(migrated.nft_token_account, legacy.nft_token_account);
+    assert_eq!(migrated.start_price, legacy.start_price);
+    assert_eq!(migrated.reserve_price, legacy.reserve_price);
+    assert_eq!(migrated.highest_bid, legacy.highest_bid);
+    assert_eq!(migrated.highest_bidder, legacy.highest_bidder);
+    assert_eq!(migrated.end_time, legacy.end_time);
+    assert_eq!(migrated.is_active, legacy.is_active);
+    assert_eq!(migrated.platform_fee, legacy.platform_fee);
+    assert_eq!(migrated.buy_now_price, legacy.buy_now_price);
+    assert_eq!(
+        migrated.authority_bump,
+        auction_authority_address(&auction).1
+    );
+    assert_eq!(migrated.auction_vault, Pubkey::default());
+}
+
+#[tokio::test]
+async fn migrates_v1_auction() {
+    let seller = Keypair::new();
+    let auction = Pubkey::new_unique();
+    let legacy = AuctionV1 {
+        version: 1,
+        seller: seller.pubkey(),
+        nft_token_account: Pubkey::new_unique(),
+        start_price: 10,
+        reserve_price: 50,
+        highest_bid: 75,
+        highest_bidder: Pubkey::new_unique(),
+        end_time: 1_000,
+        is_active: true,
+        platform_fee: 5,
+        buy_now_price: None,
+    };
+
+    let (result, account) = migrate(&seller, auction, &legacy, AuctionV1::LEN).await;
+    result.unwrap();
+
+    let account = account.unwrap();
+    assert_eq!(account.data.len(), Auction::LEN);
+    let migrated = Auction::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, Auction::VERSION);
+    assert_eq!(migrated.highest_bid, legacy.highest_bid);
+    assert_eq!(migrated.buy_now_price, None);
+    assert_eq!(
+        migrated.authority_bump,
+        auction_authority_address(&auction).1
+    );
+    assert_eq!(migrated.auction_vault, Pubkey::default());
+}
+
+#[tokio::test]
+async fn rejects_migration_by_another_signer() {
+    let intruder = Keypair::new();
+    let auction = Pubkey::new_unique();
+
+    let legacy = legacy_auction(Pubkey::new_unique());
+    let (result, account) = migrate(&intruder, auction, &legacy, AuctionV0::LEN).await;
+    let code = u32::from(advanced_auction::ErrorCode::Unauthorized);
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(code))
+    );
+    assert_eq!(account.unwrap().data.len(), AuctionV0::LEN);
+}
@@ -0,0 +1,324 @@
+//! Random bid/withdraw/cancel/end sequences checked against a reference auction model.
+
+use advanced_auction::{accounts, auction_authority_address, instruction as ix, Auction};
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::{anchor_processor, block_on, instruction, protocol_config, TestContext};
+
+const BIDDERS: usize = 3;
+const DURATION: i64 = 3_600;
+const RESERVE_PRICE: u64 = 500;
+const BUY_NOW_PRICE: u64 = 10_000;
+const BIDDER_FUNDS: u64 = 100_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Bid { bidder: usize, amount: u64 },
+    Withdraw { bidder: usize },
+    Cancel,
+    End,
+    Warp(i64),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => (0..BIDDERS, 0..12_000u64).prop_map(|(bidder, amount)| Op::Bid { bidder, amount }),
+        1 => (0..BIDDERS).prop_map(|bidder| Op::Withdraw { bidder }),
+        1 => Just(Op::Cancel),
+        1 => Just(Op::End),
+        2 => (0..DURATION).prop_map(Op::Warp),
+    ]
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Model {
+    highest_bid: u64,
+    highest_bidder: Option<usize>,
+    end_time: i64,
+    is_active: bool,
+    nft_in_vault: bool,
+    /// Bid tokens the auction owes back to the highest bidder or to the seller.
+    escrowed: u64,
+}
+
+impl Model {
+    fn apply(&mut self, now: i64, op: Op) -> bool {
+        match op {
+            Op::Bid { bidder, amount } => {
+                if !self.is_active || now >= self.end_time || amount <= self.highest_bid {
+                    return false;
+                }
+                self.highest_bid = amount;
+                self.highest_bidder = Some(bidder);
+                self.escrowed = amount;
+                if amount >= BUY_NOW_PRICE {
+                    self.end_time = now;
+                }
+            }
+            Op::Withdraw { bidder } => {
+                if !self.is_active || self.highest_bidder != Some(bidder) {
+                    return false;
+                }
+                self.highest_bid = 0;
+                self.highest_bidder = None;
+                self.escrowed = 0;
+            }
+            Op::Cancel => {
+                if self.highest_bid != 0 || !self.nft_in_vault {
+                    return false;
+                }
+                self.is_active = false;
+                self.nft_in_vault = false;
+            }
+            Op::End => {
+                if !self.is_active || now < self.end_time || self.highest_bid < RESERVE_PRICE {
+                    return false;
+                }
+                self.is_active = false;
+                self.nft_in_vault = false;
+                self.escrowed = 0;
+            }
+            Op::Warp(_) => {}
+        }
+        true
+    }
+}
+
+struct Bidder {
+    keypair: Keypair,
+    nft_account: Pubkey,
+    bid_account: Pubkey,
+}
+
+struct Setup {
+    seller: Keypair,
+    auction: Pubkey,
+    auction_authority: Pubkey,
+    auction_vault: Pubkey,
+    bid_vault: Pubkey,
+    seller_nft_account: Pubkey,
+    seller_bid_account: Pubkey,
+    treasury_bid_account: Pubkey,
+    bidders: Vec<Bidder>,
+}
+
+impl Setup {
+    fn bidder_key(&self, bidder: Option<usize>) -> Pubkey {
+        bidder.map_or(Pubkey::default(), |i| self.bidders[i].keypair.pubkey())
+    }
+}
+
+async fn setup() -> (TestContext, Setup) {
+    let program_test = ProgramTest::new(
+        "advanced_auction",
+        advanced_auction::ID,
+        anchor_processor!(advanced_auction::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+    let auction = Keypair::new();
+    let (auction_authority, _) = auction_authority_address(&auction.pubkey());
+
+    let seller = ctx.funded_keypair().await;
+    let nft_mint = ctx.create_mint(0).await;
+    let seller_nft_account = ctx.create_token_account(&nft_mint, &seller.pubkey()).await;
+    let auction_vault = ctx
+        .create_token_account(&nft_mint, &auction_authority)
+        .await;
+    ctx.mint_to(&nft_mint, &seller_nft_account, 1).await;
+
+    let bid_mint = ctx.create_mint(6).await;
+    let seller_bid_account = ctx.create_token_account(&bid_mint, &seller.pubkey()).await;
+    let bid_vault = ctx
+        .create_token_account(&bid_mint, &auction_authority)
+        .await;
+    let treasury = ctx.payer();
+    let treasury_bid_account = ctx.create_token_account(&bid_mint, &treasury).await;
+
+    let mut bidders = Vec::with_capacity(BIDDERS);
+    for _ in 0..BIDDERS {
+        let keypair = ctx.funded_keypair().await;
+        let nft_account = ctx.create_token_account(&nft_mint, &keypair.pubkey()).await;
+        let bid_account = ctx.create_token_account(&bid_mint, &keypair.pubkey()).await;
+        ctx.mint_to(&bid_mint, &bid_account, BIDDER_FUNDS).await;
+        bidders.push(Bidder {
+            keypair,
+            nft_account,
+            bid_account,
+        });
+    }
+
+    let create = instruction(
+        advanced_auction::ID,
+        accounts::CreateAuction {
+            seller: seller.pubkey(),
+            nft_token_account: seller_nft_account,
+            auction_vault,
+            bid_mint,
+            bid_vault,
+            auction: auction.pubkey(),
+            auction_authority,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::CreateAuction {
+            start_price: 100,
+            reserve_price: RESERVE_PRICE,
+            buy_now_price: Some(BUY_NOW_PRICE),
+            duration: DURATION,
+            platform_fee: 5,
+        },
+    );
+    ctx.process(&[create], &[&seller, &auction]).await.unwrap();
+
+    let setup = Setup {
+        seller,
+        auction: auction.pubkey(),
+        auction_authority,
+        auction_vault,
+        bid_vault,
+        seller_nft_account,
+        seller_bid_account,
+        treasury_bid_account,
+        bidders,
+    };
+    (ctx, setup)
+}
+
+async fn apply(ctx: &mut TestContext, s: &Setup, model: &Model, op: Op) -> bool {
+    let (ix, signer) = match op {
+        Op::Bid { bidder, amount } => {
+            // The previous highest bidder is refunded, or nobody if this is the first bid
+            let refund_to = s.bidders[model.highest_bidder.unwrap_or(bidder)].bid_account;
+            let ix = instruction(
+                advanced_auction::ID,
+                accounts::PlaceBid {
+                    bidder: s.bidders[bidder].keypair.pubkey(),
+                    bidder_token_account: s.bidders[bidder].bid_account,
+                    highest_bidder_token_account: refund_to,
+                    bid_vault: s.bid_vault,
+                    auction: s.auction,
+                    auction_authority: s.auction_authority,
+                    config: protocol_config(),
+                    token_program: spl_token::ID,
+                },
+                ix::PlaceBid { amount },
+            );
+            (ix, &s.bidders[bidder].keypair)
+        }
+        Op::Withdraw { bidder } => {
+            let ix = instruction(
+                advanced_auction::ID,
+                accounts::WithdrawBid {
+                    bidder: s.bidders[bidder].keypair.pubkey(),
+                    bidder_token_account: s.bidders[bidder].bid_account,
+                    bid_vault: s.bid_vault,
+                    auction: s.auction,
+                    auction_authority: s.auction_authority,
+                    config: protocol_config(),
+                    token_program: spl_token::ID,
+                },
+                ix::WithdrawBid {},
+            );
+            (ix, &s.bidders[bidder].keypair)
+        }
+        Op::Cancel => {
+            let ix = instruction(
+                advanced_auction::ID,
+                accounts::CancelAuction {
+                    seller: s.seller.pubkey(),
+                    seller_nft_account: s.seller_nft_account,
+                    auction_vault: s.auction_vault,
+                    auction: s.auction,
+                    auction_authority: s.auction_authority,
+                    config: protocol_config(),
+                    token_program: spl_token::ID,
+                },
+                ix::CancelAuction {},
+            );
+            (ix, &s.seller)
+        }
+        Op::End => {
+            let winner = s.bidders[model.highest_bidder.unwrap_or(0)].nft_account;
+            let ix = instruction(
+                advanced_auction::ID,
+                accounts::EndAuction {
+                    seller: s.seller.pubkey(),
+                    seller_token_account: s.seller_bid_account,
+                    highest_bidder_nft_account: winner,
+                    platform_account: s.treasury_bid_account,
+                    auction_vault: s.auction_vault,
+                    bid_vault: s.bid_vault,
+                    auction: s.auction,
+                    auction_authority: s.auction_authority,
+                    config: protocol_config(),
+                    token_program: spl_token::ID,
+                },
+                ix::EndAuction {},
+            );
+            (ix, &s.seller)
+        }
+        Op::Warp(seconds) => {
+            ctx.warp_forward(seconds).await;
+            return true;
+        }
+    };
+    ctx.process(&[ix], &[signer]).await.is_ok()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    /// The auction follows its rules, the bid vault holds exactly what the auction owes and
+    /// neither the NFT nor bid tokens are duplicated or lost.
+    #[test]
+    fn auction_follows_reference_model(ops in vec(op(), 1..12)) {
+        block_on(async {
+            let (mut ctx, s) = setup().await;
+            let auction: Auction = ctx.fetch(&s.auction).await;
+            let mut model = Model {
+                end_time: auction.end_time,
+                is_active: true,
+                nft_in_vault: true,
+                ..Model::default()
+            };
+
+            for op in ops {
+                let now = ctx.now().await;
+                let accepted = apply(&mut ctx, &s, &model, op).await;
+                prop_assert_eq!(accepted, model.apply(now, op), "{:?}", op);
+
+                let auction: Auction = ctx.fetch(&s.auction).await;
+                prop_assert_eq!(auction.highest_bid, model.highest_bid);
+                prop_assert_eq!(auction.highest_bidder, s.bidder_key(model.highest_bidder));
+                prop_assert_eq!(auction.end_time, model.end_time);
+                prop_assert_eq!(auction.is_active, model.is_active);
+
+                let mut nfts = ctx.token_balance(&s.auction_vault).await;
+                nfts += ctx.token_balance(&s.seller_nft_account).await;
+                for bidder in &s.bidders {
+                    nfts += ctx.token_balance(&bidder.nft_account).await;
+                }
+                prop_assert_eq!(nfts, 1);
+                prop_assert_eq!(ctx.token_balance(&s.auction_vault).await == 1, model.nft_in_vault);
+
+                let escrowed = ctx.token_balance(&s.bid_vault).await;
+                prop_assert_eq!(escrowed, model.escrowed, "{:?}", op);
+                let mut bid_tokens = escrowed + ctx.token_balance(&s.seller_bid_account).await;
+                bid_tokens += ctx.token_balance(&s.treasury_bid_account).await;
+                for bidder in &s.bidders {
+                    bid_tokens += ctx.token_balance(&bidder.bid_account).await;
+                }
+                prop_assert_eq!(bid_tokens, BIDDERS as u64 * BIDDER_FUNDS);
+            }
+            Ok(())
+        })?;
+    }
+}
@@ -0,0 +1,404 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_lang::solana_program::clock::Clock;
+use common::token_utils::{transfer_from_pda, transfer_from_user};
+use common::versioning::{migrate_account, Versioned};
+use protocol_config::{Module, ProtocolConfig, ProtocolError, CONFIG_SEED};
+
+declare_id!("MG9fm5JQWJshEXAsAqssCCFRbsBgJEPUZWpUTqPDawf");
+
+pub const FARM_SEED: &[u8] = b"farm";
+pub const FARM_VAULT_SEED: &[u8] = b"farm-vault";
+pub const REWARD_VAULT_SEED: &[u8] = b"reward-vault";
+pub const BASIS_POINTS: u64 = 10_000;
+/// Rewards are capped at half of each deposit.
+pub const MAX_REWARD_RATE_BPS: u16 = 5_000;
+
+#[program]
+pub mod farming {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>, token_mint: Pubkey) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        user.version = UserInfo::VERSION;
+        user.owner = ctx.accounts.owner.key();
+        user.claim_interval = 30 * 24 * 60 * 60; // 30 days in seconds
+        user.token_mint = token_mint;
+        Ok(())
+    }
+
+    /// Creates the farm of `mint` with its principal and reward vaults. Each deposit earns
+    /// `reward_rate_bps` of its amount, paid out of the reward vault. Only the protocol
+    /// admin can create farms.
+    pub fn initialize_farm(ctx: Context<InitializeFarm>, reward_rate_bps: u16) -> Result<()> {
+        require!(reward_rate_bps <= MAX_REWARD_RATE_BPS, CustomError::InvalidRewardAmount);
+        ctx.accounts.farm.set_inner(Farm {
+            version: Farm::VERSION,
+            mint: ctx.accounts.mint.key(),
+            reward_rate_bps,
+            bump: ctx.bumps.farm,
+            farm_vault_bump: ctx.bumps.farm_vault,
+            reward_vault_bump: ctx.bumps.reward_vault,
+        });
+        Ok(())
+    }
+
+    /// Adds `amount` to the farm's reward vault. Anyone can fund a farm.
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, CustomError::InvalidAmount);
+        transfer_from_user(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.from_token_account.to_account_info(),
+            &ctx.accounts.reward_vault.to_account_info(),
+            &ctx.accounts.funder.to_account_info(),
+            amount,
+        )
+    }
+
+    /// Credits `amount` plus the farm's reward on it. The reward moves from the reward
+    /// vault into the farm vault, so the farm vault holds everything it owes. Every deposit
+    /// restarts the claim interval, so no reward can be claimed before a full interval.
+    pub fn deposit_tokens(ctx: Context<DepositTokens>, amount: u64) -> Result<()> {
+        let user_info = &mut ctx.accounts.user_info;
+        let farm = &ctx.accounts.farm;
+        let clock = Clock::get()?;
+        require!(amount > 0, CustomError::InvalidAmount);
+        let reward_amount = farm.reward_for(amount);
+        require!(ctx.accounts.reward_vault.amount >= reward_amount, CustomError::InsufficientRewards);
+
+        let total_amount = amount + reward_amount;
+        user_info.krpza_deposited_amount += total_amount;
+        
+        user_info.last_deposit_time = clock.unix_timestamp;
+        user_info.next_claim_time = clock.unix_timestamp + user_info.claim_interval as i64;
+
+        transfer_from_user(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.from_token_account.to_account_info(),
+            &ctx.accounts.farm_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            amount,
+        )?;
+        if reward_amount > 0 {
+            transfer_from_pda(
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.reward_vault.to_account_info(),
+                &ctx.accounts.farm_vault.to_account_info(),
+                &farm.to_account_info(),
+                &[FARM_SEED, farm.mint.as_ref()],
+                farm.bump,
+                reward_amount,
+            )?;
+        }
+
+        emit!(Deposit {
+            user: *ctx.accounts.user.key,
+            amount: total_amount,
+        });
+        Ok(())
+    }
+
+    /// Pays `reduce_deposit_amount` of the credited principal and reward out of the farm
+    /// vault into `to_token_account`.
+    pub fn claim_monthly(ctx: Context<ClaimMonthly>, months: u64, reduce_deposit_amount: u64) -> Result<()> {
+        let user_info = &mut ctx.accounts.user_info;
+        let clock = Clock::get()?;
+        require!(months > 0, CustomError::InvalidMonthCount);
+        require!(user_info.krpza_deposited_amount >= reduce_deposit_amount, CustomError::InsufficientBalance);
+        require!(clock.unix_timestamp >= user_info.next_claim_time, CustomError::ClaimIntervalNotPassed);
+        
+        // Check if correct number of months have passed
+        let claim_time = user_info.next_claim_time + ((months - 1) as i64 * user_info.claim_interval as i64);
+        require!(clock.unix_timestamp >= claim_time, CustomError::InvalidClaimTime);
+
+        user_info.krpza_deposited_amount -= reduce_deposit_amount;
+        user_info.next_claim_time = clock.unix_timestamp + (user_info.claim_interval as i64 * months as i64);
+        user_info.month_count += months;
+
+        if reduce_deposit_amount > 0 {
+            let farm = &ctx.accounts.farm;
+            transfer_from_pda(
+                &ctx.accounts.token_program.to_account_info(),
+                &ctx.accounts.farm_vault.to_account_info(),
+                &ctx.accounts.to_token_account.to_account_info(),
+                &farm.to_account_info(),
+                &[FARM_SEED, farm.mint.as_ref()],
+                farm.bump,
+                reduce_deposit_amount,
+            )?;
+        }
+        emit!(Claim {
+            user: *ctx.accounts.user.key,
+            amount: reduce_deposit_amount,
+        });
+        Ok(())
+    }
+
+    pub fn migrate_user_info(ctx: Context<MigrateUserInfo>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let migrated = migrate_account(
+            &ctx.accounts.user_info.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            UserInfoV0::LEN,
+            |legacy: UserInfoV0| {
+                require_keys_eq!(legacy.owner, owner, CustomError::Unauthorized);
+                Ok(legacy.upgrade())
+            },
+        )?;
+
+        msg!("User info migrated to version {}", migrated.version);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = UserInfo::LEN,
+    )]
+    pub user: Account<'info, UserInfo>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFarm<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = config.admin == admin.key() @ CustomError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = Farm::LEN,
+        seeds = [FARM_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub farm: Account<'info, Farm>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = farm,
+        seeds = [FARM_VAULT_SEED, farm.key().as_ref()],
+        bump,
+    )]
+    pub farm_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = farm,
+        seeds = [REWARD_VAULT_SEED, farm.key().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    pub funder: Signer<'info>,
+    #[account(seeds = [FARM_SEED, farm.mint.as_ref()], bump = farm.bump)]
+    pub farm: Account<'info, Farm>,
+    #[account(mut)]
+    pub from_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [REWARD_VAULT_SEED, farm.key().as_ref()], bump = farm.reward_vault_bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        constraint = user_info.owner == user.key() @ CustomError::Unauthorized,
+        constraint = user_info.token_mint == farm.mint @ CustomError::InvalidMint,
+    )]
+    pub user_info: Account<'info, UserInfo>,
+    #[account(seeds = [FARM_SEED, farm.mint.as_ref()], bump = farm.bump)]
+    pub farm: Account<'info, Farm>,
+    #[account(mut)]
+    pub from_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [FARM_VAULT_SEED, farm.key().as_ref()], bump = farm.farm_vault_bump)]
+    pub farm_vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [REWARD_VAULT_SEED, farm.key().as_ref()], bump = farm.reward_vault_bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Farming) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMonthly<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        constraint = user_info.owner == user.key() @ CustomError::Unauthorized,
+        constraint = user_info.token_mint == farm.mint @ CustomError::InvalidMint,
+    )]
+    pub user_info: Account<'info, UserInfo>,
+    #[account(seeds = [FARM_SEED, farm.mint.as_ref()], bump = farm.bump)]
+    pub farm: Account<'info, Farm>,
+    #[account(mut, seeds = [FARM_VAULT_SEED, farm.key().as_ref()], bump = farm.farm_vault_bump)]
+    pub farm_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = farm.mint)]
+    pub to_token_account: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Farming) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserInfo<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: Owner, discriminator and user are validated against the legacy layout
+    #[account(mut)]
+    pub user_info: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct UserInfo {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub krpza_deposited_amount: u64,
+    pub last_deposit_time: i64,
+    pub next_claim_time: i64,
+    pub claim_interval: u64,
+    pub month_count: u64,
+    pub token_mint: Pubkey,
+}
+
+impl Versioned for UserInfo {
+    const VERSION: u8 = 1;
+    const LEN: usize = 8 + 1 + 32 + 8 + 8 + 8 + 8 + 8 + 32;
+}
+
+/// A farm of one mint. `farm_vault` holds the credited deposits and `reward_vault` the
+/// rewards not yet credited to anyone.
+#[account]
+pub struct Farm {
+    pub version: u8,
+    pub mint: Pubkey,
+    /// Reward credited on each deposit, in basis points of its amount.
+    pub reward_rate_bps: u16,
+    pub bump: u8,
+    pub farm_vault_bump: u8,
+    pub reward_vault_bump: u8,
+}
+
+impl Versioned for Farm {
+    const VERSION: u8 = 1;
+    const LEN: usize = 8 + 1 + 32 + 2 + 1 + 1 + 1;
+}
+
+impl Farm {
+    pub fn reward_for(&self, amount: u64) -> u64 {
+        (u128::from(amount) * u128::from(self.reward_rate_bps) / u128::from(BASIS_POINTS)) as u64
+    }
+}
+
+pub fn farm_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FARM_SEED, mint.as_ref()], &ID)
+}
+
+pub fn farm_vault_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FARM_VAULT_SEED, farm.as_ref()], &ID)
+}
+
+pub fn reward_vault_address(farm: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REWARD_VAULT_SEED, farm.as_ref()], &ID)
+}
+
+/// Layout of `UserInfo` before the version header.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserInfoV0 {
+    pub owner: Pubkey,
+    pub krpza_deposited_amount: u64,
+    pub last_deposit_time: i64,
+    pub next_claim_time: i64,
+    pub claim_interval: u64,
+    pub month_count: u64,
+    pub token_mint: Pubkey,
+}
+
+impl UserInfoV0 {
+    // Allocated as `8 + size_of::<UserInfo>()`, which had no padding
+    pub const LEN: usize = 8 + 104;
+
+    pub fn upgrade(self) -> UserInfo {
+        UserInfo {
+            version: UserInfo::VERSION,
+            owner: self.owner,
+            krpza_deposited_amount: self.krpza_deposited_amount,
+            last_deposit_time: self.last_deposit_time,
+            next_claim_time: self.next_claim_time,
+            claim_interval: self.claim_interval,
+            month_count: self.month_count,
+            token_mint: self.token_mint,
+        }
+    }
+}
+
+#[error_code]
+pub enum CustomError {
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Reward rate must be at most half of the deposit amount")]
+    InvalidRewardAmount,
+    #[msg("Claim interval has not passed yet")]
+    ClaimIntervalNotPassed,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Month count must be greater than 0")]
+    InvalidMonthCount,
+    #[msg("Invalid claim time")]
+    InvalidClaimTime,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Reward vault cannot cover the reward")]
+    InsufficientRewards,
+    #[msg("User info belongs to another farm")]
+    InvalidMint,
+}
+
+#[event]
+pub struct Deposit {
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct Claim {
+    pub user: Pubkey,
+    pub amount: u64,
+}
\ No newline at end of file
@@ -0,0 +1,343 @@
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use farming::{
+    accounts, farm_address, farm_vault_address, instruction as ix, reward_vault_address,
+    CustomError, UserInfo,
+};
+use protocol_config::{Module, ProtocolError};
+use solana_program_test::ProgramTest;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::{
+    anchor_processor, assert_error, instruction, protocol_config, TestContext, TxResult,
+};
+
+const MONTH: i64 = 30 * 24 * 60 * 60;
+/// 20% of each deposit.
+const REWARD_RATE_BPS: u16 = 2_000;
+const REWARD_FUNDS: u64 = 100_000;
+
+struct Farm {
+    owner: Keypair,
+    user_info: Pubkey,
+    mint: Pubkey,
+    owner_token_account: Pubkey,
+    farm: Pubkey,
+    farm_vault: Pubkey,
+    reward_vault: Pubkey,
+}
+
+fn initialize_farm_ix(admin: Pubkey, mint: Pubkey, reward_rate_bps: u16) -> Instruction {
+    let farm = farm_address(&mint).0;
+    instruction(
+        farming::ID,
+        accounts::InitializeFarm {
+            admin,
+            config: protocol_config(),
+            mint,
+            farm,
+            farm_vault: farm_vault_address(&farm).0,
+            reward_vault: reward_vault_address(&farm).0,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::InitializeFarm { reward_rate_bps },
+    )
+}
+
+async fn setup() -> (TestContext, Farm) {
+    let program_test = ProgramTest::new("farming", farming::ID, anchor_processor!(farming::entry));
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+
+    let owner = ctx.funded_keypair().await;
+    let mint = ctx.create_mint(6).await;
+    let owner_token_account = ctx.create_token_account(&mint, &owner.pubkey()).await;
+    ctx.mint_to(&mint, &owner_token_account, 1_000_000).await;
+
+    let admin = ctx.payer();
+    let funder_token_account = ctx.create_token_account(&mint, &admin).await;
+    ctx.mint_to(&mint, &funder_token_account, REWARD_FUNDS)
+        .await;
+    let farm = farm_address(&mint).0;
+    let reward_vault = reward_vault_address(&farm).0;
+    let fund = instruction(
+        farming::ID,
+        accounts::FundRewards {
+            funder: admin,
+            farm,
+            from_token_account: funder_token_account,
+            reward_vault,
+            token_program: spl_token::ID,
+        },
+        ix::FundRewards {
+            amount: REWARD_FUNDS,
+        },
+    );
+    ctx.process(
+        &[initialize_farm_ix(admin, mint, REWARD_RATE_BPS), fund],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let user_info = Keypair::new();
+    let init = instruction(
+        farming::ID,
+        accounts::Initialize {
+            owner: owner.pubkey(),
+            user: user_info.pubkey(),
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::Initialize { token_mint: mint },
+    );
+    ctx.process(&[init], &[&owner, &user_info]).await.unwrap();
+
+    let farm = Farm {
+        owner,
+        user_info: user_info.pubkey(),
+        mint,
+        owner_token_account,
+        farm,
+        farm_vault: farm_vault_address(&farm).0,
+        reward_vault,
+    };
+    (ctx, farm)
+}
+
+async fn deposit(ctx: &mut TestContext, farm: &Farm, amount: u64) -> TxResult {
+    let deposit = instruction(
+        farming::ID,
+        accounts::DepositTokens {
+            user: farm.owner.pubkey(),
+            user_info: farm.user_info,
+            farm: farm.farm,
+            from_token_account: farm.owner_token_account,
+            farm_vault: farm.farm_vault,
+            reward_vault: farm.reward_vault,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            authority: farm.owner.pubkey(),
+        },
+        ix::DepositTokens { amount },
+    );
+    ctx.process(&[deposit], &[&farm.owner]).await
+}
+
+fn claim_ix(farm: &Farm, user: &Keypair, months: u64, reduce_deposit_amount: u64) -> Instruction {
+    instruction(
+        farming::ID,
+        accounts::ClaimMonthly {
+            user: user.pubkey(),
+            user_info: farm.user_info,
+            farm: farm.farm,
+            farm_vault: farm.farm_vault,
+            to_token_account: farm.owner_token_account,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::ClaimMonthly {
+            months,
+            reduce_deposit_amount,
+        },
+    )
+}
+
+async fn claim(
+    ctx: &mut TestContext,
+    farm: &Farm,
+    months: u64,
+    reduce_deposit_amount: u64,
+) -> TxResult {
+    let claim = claim_ix(farm, &farm.owner, months, reduce_deposit_amount);
+    ctx.process(&[claim], &[&farm.owner]).await
+}
+
+#[tokio::test]
+async fn initialize_sets_interval_and_mint() {
+    let (mut ctx, farm) = setup().await;
+
+    let user_info: UserInfo = ctx.fetch(&farm.user_info).await;
+    assert_eq!(user_info.owner, farm.owner.pubkey());
+    assert_eq!(user_info.claim_interval, MONTH as u64);
+    assert_eq!(user_info.token_mint, farm.mint);
+    assert_eq!(user_info.last_deposit_time, 0);
+}
+
+#[tokio::test]
+async fn initialize_farm_creates_vaults() {
+    let (mut ctx, farm) = setup().await;
+
+    let account: farming::Farm = ctx.fetch(&farm.farm).await;
+    assert_eq!(account.mint, farm.mint);
+    assert_eq!(account.reward_rate_bps, REWARD_RATE_BPS);
+    assert_eq!(ctx.token_balance(&farm.farm_vault).await, 0);
+    assert_eq!(ctx.token_balance(&farm.reward_vault).await, REWARD_FUNDS);
+}
+
+#[tokio::test]
+async fn initialize_farm_rejects_excessive_rate() {
+    let (mut ctx, _) = setup().await;
+    let admin = ctx.payer();
+    let mint = ctx.create_mint(6).await;
+
+    let result = ctx
+        .process(&[initialize_farm_ix(admin, mint, 5_001)], &[])
+        .await;
+    assert_error(result, CustomError::InvalidRewardAmount);
+}
+
+#[tokio::test]
+async fn initialize_farm_requires_protocol_admin() {
+    let (mut ctx, farm) = setup().await;
+    let mint = ctx.create_mint(6).await;
+
+    let init = initialize_farm_ix(farm.owner.pubkey(), mint, REWARD_RATE_BPS);
+    let result = ctx.process(&[init], &[&farm.owner]).await;
+    assert_error(result, CustomError::Unauthorized);
+}
+
+#[tokio::test]
+async fn deposit_records_amount_and_schedule() {
+    let (mut ctx, farm) = setup().await;
+    let now = ctx.now().await;
+
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+
+    let user_info: UserInfo = ctx.fetch(&farm.user_info).await;
+    assert_eq!(user_info.krpza_deposited_amount, 1_200);
+    assert_eq!(user_info.last_deposit_time, now);
+    assert_eq!(user_info.next_claim_time, now + MONTH);
+    assert_eq!(ctx.token_balance(&farm.farm_vault).await, 1_200);
+    assert_eq!(
+        ctx.token_balance(&farm.reward_vault).await,
+        REWARD_FUNDS - 200
+    );
+    assert_eq!(ctx.token_balance(&farm.owner_token_account).await, 999_000);
+}
+
+#[tokio::test]
+async fn deposit_rejects_zero_amount() {
+    let (mut ctx, farm) = setup().await;
+    let result = deposit(&mut ctx, &farm, 0).await;
+    assert_error(result, CustomError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn deposit_beyond_reward_funds_fails() {
+    let (mut ctx, farm) = setup().await;
+    // Earns 120_000 in rewards
+    let result = deposit(&mut ctx, &farm, 600_000).await;
+    assert_error(result, CustomError::InsufficientRewards);
+}
+
+#[tokio::test]
+async fn claim_rejects_zero_months() {
+    let (mut ctx, farm) = setup().await;
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    ctx.warp_forward(MONTH).await;
+
+    let result = claim(&mut ctx, &farm, 0, 0).await;
+    assert_error(result, CustomError::InvalidMonthCount);
+}
+
+#[tokio::test]
+async fn claim_rejects_reduction_above_deposit() {
+    let (mut ctx, farm) = setup().await;
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    ctx.warp_forward(MONTH).await;
+
+    let result = claim(&mut ctx, &farm, 1, 1_201).await;
+    assert_error(result, CustomError::InsufficientBalance);
+}
+
+#[tokio::test]
+async fn claim_before_interval_fails() {
+    let (mut ctx, farm) = setup().await;
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    ctx.warp_forward(MONTH - 1).await;
+
+    let result = claim(&mut ctx, &farm, 1, 0).await;
+    assert_error(result, CustomError::ClaimIntervalNotPassed);
+}
+
+#[tokio::test]
+async fn deposit_then_immediate_claim_fails() {
+    let (mut ctx, farm) = setup().await;
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    ctx.warp_forward(MONTH).await;
+    let now = ctx.now().await;
+
+    // The first deposit's interval has passed, but the new one restarts it
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+
+    let user_info: UserInfo = ctx.fetch(&farm.user_info).await;
+    assert_eq!(user_info.next_claim_time, now + MONTH);
+    let result = claim(&mut ctx, &farm, 1, 2_400).await;
+    assert_error(result, CustomError::ClaimIntervalNotPassed);
+    assert_eq!(ctx.token_balance(&farm.farm_vault).await, 2_400);
+}
+
+#[tokio::test]
+async fn claim_for_unelapsed_months_fails() {
+    let (mut ctx, farm) = setup().await;
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    ctx.warp_forward(MONTH).await;
+
+    let result = claim(&mut ctx, &farm, 2, 0).await;
+    assert_error(result, CustomError::InvalidClaimTime);
+}
+
+#[tokio::test]
+async fn claim_after_months_pays_out_reduction() {
+    let (mut ctx, farm) = setup().await;
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    ctx.warp_forward(2 * MONTH).await;
+    let now = ctx.now().await;
+
+    claim(&mut ctx, &farm, 2, 300).await.unwrap();
+
+    let user_info: UserInfo = ctx.fetch(&farm.user_info).await;
+    assert_eq!(user_info.krpza_deposited_amount, 900);
+    assert_eq!(user_info.month_count, 2);
+    assert_eq!(user_info.next_claim_time, now + 2 * MONTH);
+    assert_eq!(ctx.token_balance(&farm.farm_vault).await, 900);
+    assert_eq!(ctx.token_balance(&farm.owner_token_account).await, 999_300);
+
+    let result = claim(&mut ctx, &farm, 1, 0).await;
+    assert_error(result, CustomError::ClaimIntervalNotPassed);
+}
+
+#[tokio::test]
+async fn claim_by_other_user_fails() {
+    let (mut ctx, farm) = setup().await;
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    ctx.warp_forward(MONTH).await;
+
+    let other = ctx.funded_keypair().await;
+    let result = ctx
+        .process(&[claim_ix(&farm, &other, 1, 1_200)], &[&other])
+        .await;
+    assert_error(result, CustomError::Unauthorized);
+}
+
+#[tokio::test]
+async fn deposit_while_paused_fails() {
+    let (mut ctx, farm) = setup().await;
+    ctx.set_protocol_config(&[Module::Farming]);
+
+    let result = deposit(&mut ctx, &farm, 1_000).await;
+    assert_error(result, ProtocolError::ModulePaused);
+}
+
+#[tokio::test]
+async fn other_paused_modules_do_not_block_deposits() {
+    let (mut ctx, farm) = setup().await;
+    ctx.set_protocol_config(&[Module::Vesting, Module::Auction, Module::Staking]);
+
+    deposit(&mut ctx, &farm, 1_000).await.unwrap();
+    assert_eq!(ctx.token_balance(&farm.farm_vault).await, 1_200);
+}
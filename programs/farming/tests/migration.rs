@@ -0,0 +1,125 @@
+use anchor_lang::{
+    AccountDeserialize, AnchorSerialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use common::versioning::Versioned;
+use farming::{UserInfo, UserInfoV0};
+use solana_program_test::{BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use test_utils::anchor_processor;
+
+fn legacy_user_info(owner: Pubkey) -> UserInfoV0 {
+    UserInfoV0 {
+        owner,
+        krpza_deposited_amount: 1_500,
+        last_deposit_time: 100,
+        next_claim_time: 2_592_100,
+        claim_interval: 2_592_000,
+        month_count: 3,
+        token_mint: Pubkey::new_unique(),
+    }
+}
+
+fn legacy_account(state: &UserInfoV0) -> Account {
+    let mut data = UserInfo::DISCRIMINATOR.to_vec();
+    data.extend(state.try_to_vec().unwrap());
+    data.resize(UserInfoV0::LEN, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: farming::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn migrate(
+    signer: &Keypair,
+    user_info: Pubkey,
+    state: &UserInfoV0,
+) -> (Result<(), BanksClientError>, Option<Account>) {
+    let mut program_test =
+        ProgramTest::new("farming", farming::ID, anchor_processor!(farming::entry));
+    program_test.add_account(user_info, legacy_account(state));
+    program_test.add_account(
+        signer.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::ID),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let ix = Instruction {
+        program_id: farming::ID,
+        accounts: farming::accounts::MigrateUserInfo {
+            owner: signer.pubkey(),
+            user_info,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: farming::instruction::MigrateUserInfo {}.data(),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer, signer],
+        recent_blockhash,
+    );
+    let result = banks_client.process_transaction(tx).await;
+    let account = banks_client.get_account(user_info).await.unwrap();
+    (result, account)
+}
+
+#[tokio::test]
+async fn migrates_legacy_user_info() {
+    let owner = Keypair::new();
+    let user_info = Pubkey::new_unique();
+    let legacy = legacy_user_info(owner.pubkey());
+
+    let (result, account) = migrate(&owner, user_info, &legacy).await;
+    result.unwrap();
+
+    let account = account.unwrap();
+    assert_eq!(account.data.len(), UserInfo::LEN);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(UserInfo::LEN)
+    );
+
+    let migrated = UserInfo::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, UserInfo::VERSION);
+    assert_eq!(migrated.owner, legacy.owner);
+    assert_eq!(
+        migrated.krpza_deposited_amount,
+        legacy.krpza_deposited_amount
+    );
+    assert_eq!(migrated.last_deposit_time, legacy.last_deposit_time);
+    assert_eq!(migrated.next_claim_time, legacy.next_claim_time);
+    assert_eq!(migrated.claim_interval, legacy.claim_interval);
+    assert_eq!(migrated.month_count, legacy.month_count);
+    assert_eq!(migrated.token_mint, legacy.token_mint);
+}
+
+#[tokio::test]
+async fn rejects_migration_by_another_signer() {
+    let intruder = Keypair::new();
+    let user_info = Pubkey::new_unique();
+
+    let (result, account) = migrate(
+        &intruder,
+        user_info,
+        &legacy_user_info(Pubkey::new_unique()),
+    )
+    .await;
+    let code = u32::from(farming::CustomError::Unauthorized);
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(code))
+    );
+    assert_eq!(account.unwrap().data.len(), UserInfoV0::LEN);
+}
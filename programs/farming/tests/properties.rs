@@ -0,0 +1,299 @@
+//! Random deposit/claim sequences checked against a reference model of the farm.
+
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use farming::{
+    accounts, farm_address, farm_vault_address, instruction as ix, reward_vault_address, UserInfo,
+};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::{anchor_processor, block_on, instruction, protocol_config, TestContext};
+
+const INTERVAL: i64 = 30 * 24 * 60 * 60;
+const INITIAL_BALANCE: u64 = 1_000_000;
+const REWARD_RATE_BPS: u64 = 2_000;
+/// Runs out within a few deposits, so underfunded rewards get exercised too.
+const REWARD_FUNDS: u64 = 5_000;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Deposit {
+        amount: u64,
+    },
+    Claim {
+        months: u64,
+        reduce_deposit_amount: u64,
+    },
+    Warp {
+        seconds: i64,
+    },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..5_000u64).prop_map(|amount| Op::Deposit { amount }),
+        (0..4u64, 0..8_000u64).prop_map(|(months, reduce_deposit_amount)| Op::Claim {
+            months,
+            reduce_deposit_amount
+        }),
+        (0..3 * INTERVAL).prop_map(|seconds| Op::Warp { seconds }),
+    ]
+}
+
+/// What `UserInfo` should hold, plus the balances of the farm's vaults.
+#[derive(Debug, PartialEq)]
+struct Model {
+    deposited: u64,
+    last_deposit_time: i64,
+    next_claim_time: i64,
+    month_count: u64,
+    vault_balance: u64,
+    reward_vault_balance: u64,
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Self {
+            deposited: 0,
+            last_deposit_time: 0,
+            next_claim_time: 0,
+            month_count: 0,
+            vault_balance: 0,
+            reward_vault_balance: REWARD_FUNDS,
+        }
+    }
+}
+
+impl Model {
+    fn owner_balance(&self) -> u64 {
+        INITIAL_BALANCE + REWARD_FUNDS - self.vault_balance - self.reward_vault_balance
+    }
+
+    fn deposit(&mut self, now: i64, amount: u64) -> bool {
+        let reward_amount = amount * REWARD_RATE_BPS / 10_000;
+        if amount == 0 || reward_amount > self.reward_vault_balance || amount > self.owner_balance()
+        {
+            return false;
+        }
+        self.deposited += amount + reward_amount;
+        self.vault_balance += amount + reward_amount;
+        self.reward_vault_balance -= reward_amount;
+        self.last_deposit_time = now;
+        self.next_claim_time = now + INTERVAL;
+        true
+    }
+
+    fn claim(&mut self, now: i64, months: u64, reduce_deposit_amount: u64) -> bool {
+        if months == 0
+            || self.deposited < reduce_deposit_amount
+            || now < self.next_claim_time + (months as i64 - 1) * INTERVAL
+        {
+            return false;
+        }
+        self.deposited -= reduce_deposit_amount;
+        self.vault_balance -= reduce_deposit_amount;
+        self.next_claim_time = now + INTERVAL * months as i64;
+        self.month_count += months;
+        true
+    }
+
+    fn observe(user_info: &UserInfo, vault_balance: u64, reward_vault_balance: u64) -> Self {
+        Self {
+            deposited: user_info.krpza_deposited_amount,
+            last_deposit_time: user_info.last_deposit_time,
+            next_claim_time: user_info.next_claim_time,
+            month_count: user_info.month_count,
+            vault_balance,
+            reward_vault_balance,
+        }
+    }
+}
+
+struct Farm {
+    owner: Keypair,
+    user_info: Pubkey,
+    owner_token_account: Pubkey,
+    farm: Pubkey,
+    farm_vault: Pubkey,
+    reward_vault: Pubkey,
+}
+
+async fn setup() -> (TestContext, Farm) {
+    let program_test = ProgramTest::new("farming", farming::ID, anchor_processor!(farming::entry));
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+
+    let owner = ctx.funded_keypair().await;
+    let mint = ctx.create_mint(6).await;
+    let owner_token_account = ctx.create_token_account(&mint, &owner.pubkey()).await;
+    ctx.mint_to(&mint, &owner_token_account, INITIAL_BALANCE)
+        .await;
+
+    let admin = ctx.payer();
+    let funder_token_account = ctx.create_token_account(&mint, &admin).await;
+    ctx.mint_to(&mint, &funder_token_account, REWARD_FUNDS)
+        .await;
+    let farm = farm_address(&mint).0;
+    let farm_vault = farm_vault_address(&farm).0;
+    let reward_vault = reward_vault_address(&farm).0;
+    let initialize_farm = instruction(
+        farming::ID,
+        accounts::InitializeFarm {
+            admin,
+            config: protocol_config(),
+            mint,
+            farm,
+            farm_vault,
+            reward_vault,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::InitializeFarm {
+            reward_rate_bps: REWARD_RATE_BPS as u16,
+        },
+    );
+    let fund = instruction(
+        farming::ID,
+        accounts::FundRewards {
+            funder: admin,
+            farm,
+            from_token_account: funder_token_account,
+            reward_vault,
+            token_program: spl_token::ID,
+        },
+        ix::FundRewards {
+            amount: REWARD_FUNDS,
+        },
+    );
+    ctx.process(&[initialize_farm, fund], &[]).await.unwrap();
+
+    let user_info = Keypair::new();
+    let init = instruction(
+        farming::ID,
+        accounts::Initialize {
+            owner: owner.pubkey(),
+            user: user_info.pubkey(),
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::Initialize { token_mint: mint },
+    );
+    ctx.process(&[init], &[&owner, &user_info]).await.unwrap();
+
+    let farm = Farm {
+        owner,
+        user_info: user_info.pubkey(),
+        owner_token_account,
+        farm,
+        farm_vault,
+        reward_vault,
+    };
+    (ctx, farm)
+}
+
+async fn apply(ctx: &mut TestContext, farm: &Farm, op: &Op) -> bool {
+    let ix = match *op {
+        Op::Deposit { amount } => instruction(
+            farming::ID,
+            accounts::DepositTokens {
+                user: farm.owner.pubkey(),
+                user_info: farm.user_info,
+                farm: farm.farm,
+                from_token_account: farm.owner_token_account,
+                farm_vault: farm.farm_vault,
+                reward_vault: farm.reward_vault,
+                config: protocol_config(),
+                token_program: spl_token::ID,
+                authority: farm.owner.pubkey(),
+            },
+            ix::DepositTokens { amount },
+        ),
+        Op::Claim {
+            months,
+            reduce_deposit_amount,
+        } => instruction(
+            farming::ID,
+            accounts::ClaimMonthly {
+                user: farm.owner.pubkey(),
+                user_info: farm.user_info,
+                farm: farm.farm,
+                farm_vault: farm.farm_vault,
+                to_token_account: farm.owner_token_account,
+                config: protocol_config(),
+                token_program: spl_token::ID,
+            },
+            ix::ClaimMonthly {
+                months,
+                reduce_deposit_amount,
+            },
+        ),
+        Op::Warp { seconds } => {
+            ctx.warp_forward(seconds).await;
+            return true;
+        }
+    };
+    ctx.process(&[ix], &[&farm.owner]).await.is_ok()
+}
+
+async fn observe(ctx: &mut TestContext, farm: &Farm) -> Model {
+    let user_info: UserInfo = ctx.fetch(&farm.user_info).await;
+    let vault_balance = ctx.token_balance(&farm.farm_vault).await;
+    let reward_vault_balance = ctx.token_balance(&farm.reward_vault).await;
+    Model::observe(&user_info, vault_balance, reward_vault_balance)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn farm_follows_reference_model(ops in vec(op(), 1..12)) {
+        block_on(async {
+            let (mut ctx, farm) = setup().await;
+            let mut model = Model::default();
+
+            for op in &ops {
+                let now = ctx.now().await;
+                let expected = match *op {
+                    Op::Deposit { amount } => model.deposit(now, amount),
+                    Op::Claim { months, reduce_deposit_amount } => {
+                        model.claim(now, months, reduce_deposit_amount)
+                    }
+                    Op::Warp { .. } => true,
+                };
+                let accepted = apply(&mut ctx, &farm, op).await;
+                prop_assert_eq!(accepted, expected, "{:?}", op);
+                prop_assert_eq!(&observe(&mut ctx, &farm).await, &model, "{:?}", op);
+
+                let owner_balance = ctx.token_balance(&farm.owner_token_account).await;
+                prop_assert_eq!(owner_balance, model.owner_balance());
+            }
+            Ok(())
+        })?;
+    }
+
+    /// Every token the farm owes its depositor is held by the farm, and claims pay out.
+    #[test]
+    fn farm_vault_covers_liabilities(ops in vec(op(), 1..12)) {
+        block_on(async {
+            let (mut ctx, farm) = setup().await;
+
+            for op in &ops {
+                let owner_before = ctx.token_balance(&farm.owner_token_account).await;
+                let accepted = apply(&mut ctx, &farm, op).await;
+                let owner_after = ctx.token_balance(&farm.owner_token_account).await;
+                let user_info: UserInfo = ctx.fetch(&farm.user_info).await;
+                let vault_balance = ctx.token_balance(&farm.farm_vault).await;
+
+                prop_assert!(vault_balance >= user_info.krpza_deposited_amount, "{:?}", op);
+                if let (true, Op::Claim { reduce_deposit_amount, .. }) = (accepted, op) {
+                    prop_assert_eq!(owner_after, owner_before + reduce_deposit_amount);
+                }
+            }
+            Ok(())
+        })?;
+    }
+}
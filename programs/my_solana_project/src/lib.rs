@@ -1,43 +1,347 @@
 use anchor_lang::prelude::*;
+use common::versioning::{migrate_account, Versioned};
 
 // Define a placeholder program ID for initial testing
 declare_id!("9YQK5crT1uqpddaNKGBgmey4NQnkcSYFmHNe8E1zL1V2");
 
+pub const REGISTRY_SEED: &[u8] = b"registry";
+pub const COUNTER_SEED: &[u8] = b"counter";
+pub const MAX_NAME_LEN: usize = 32;
+
 #[program]
 pub mod my_solana_project {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    pub fn initialize_registry(ctx: Context<InitializeRegistry>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.version = CounterRegistry::VERSION;
+        registry.counter_count = 0;
+        registry.bump = ctx.bumps.registry;
+        msg!("Counter registry initialized!");
+        Ok(())
+    }
+
+    pub fn initialize(ctx: Context<Initialize>, name: String) -> Result<()> {
+        require!(name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);
+
         let base_account = &mut ctx.accounts.base_account;
+        base_account.version = BaseAccount::VERSION;
+        base_account.authority = ctx.accounts.authority.key();
+        base_account.name = name;
         base_account.counter = 0;
-        msg!("Initialized counter to 0!");
+        base_account.bump = ctx.bumps.base_account;
+
+        let registry = &mut ctx.accounts.registry;
+        registry.counter_count = registry
+            .counter_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        msg!("Initialized counter {} to 0!", base_account.name);
+        Ok(())
+    }
+
+    pub fn increment(ctx: Context<UpdateCounter>) -> Result<()> {
+        let base_account = &mut ctx.accounts.base_account;
+        base_account.counter = base_account
+            .counter
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        msg!("Counter increased to {}!", base_account.counter);
         Ok(())
     }
 
-    pub fn increment(ctx: Context<Increment>) -> Result<()> {
+    pub fn increment_by(ctx: Context<UpdateCounter>, amount: u64) -> Result<()> {
         let base_account = &mut ctx.accounts.base_account;
-        base_account.counter += 1;
+        base_account.counter = base_account
+            .counter
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
         msg!("Counter increased to {}!", base_account.counter);
         Ok(())
     }
+
+    /// Issues the next id of the sequence and returns it through return data.
+    /// Other programs call this via `my_solana_project::cpi::next_id` with a
+    /// PDA they control as the counter authority.
+    pub fn next_id(ctx: Context<UpdateCounter>) -> Result<u64> {
+        let base_account = &mut ctx.accounts.base_account;
+        base_account.counter = base_account
+            .counter
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(IdIssued {
+            counter: base_account.key(),
+            authority: base_account.authority,
+            id: base_account.counter,
+        });
+        Ok(base_account.counter)
+    }
+
+    pub fn decrement(ctx: Context<UpdateCounter>) -> Result<()> {
+        let base_account = &mut ctx.accounts.base_account;
+        base_account.counter = base_account
+            .counter
+            .checked_sub(1)
+            .ok_or(ErrorCode::Underflow)?;
+        msg!("Counter decreased to {}!", base_account.counter);
+        Ok(())
+    }
+
+    pub fn reset(ctx: Context<UpdateCounter>) -> Result<()> {
+        let base_account = &mut ctx.accounts.base_account;
+        base_account.counter = 0;
+        msg!("Counter reset to 0!");
+        Ok(())
+    }
+
+    pub fn close_counter(ctx: Context<CloseCounter>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.counter_count = registry
+            .counter_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::Underflow)?;
+        msg!("Counter {} closed!", ctx.accounts.base_account.name);
+        Ok(())
+    }
+
+    /// Rewrites a counter stored in either header-less layout into the current one.
+    pub fn migrate_counter(ctx: Context<MigrateCounter>) -> Result<()> {
+        let info = ctx.accounts.base_account.to_account_info();
+        let payer = ctx.accounts.authority.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let authority = ctx.accounts.authority.key();
+
+        let migrated = if info.data_len() == BaseAccountV0::LEN {
+            migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                BaseAccountV0::LEN,
+                |legacy: BaseAccountV0| {
+                    require_keys_eq!(legacy.authority, authority, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade())
+                },
+            )?
+        } else {
+            migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                BaseAccountV1::LEN,
+                |legacy: BaseAccountV1| {
+                    require_keys_eq!(legacy.authority, authority, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade())
+                },
+            )?
+        };
+
+        msg!(
+            "Counter {} migrated to version {}!",
+            migrated.name,
+            migrated.version
+        );
+        Ok(())
+    }
+
+    pub fn migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+        let migrated = migrate_account(
+            &ctx.accounts.registry.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            CounterRegistryV0::LEN,
+            |legacy: CounterRegistryV0| Ok(legacy.upgrade()),
+        )?;
+
+        msg!("Counter registry migrated to version {}!", migrated.version);
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = user, space = 8 + 4)] // 8 bytes for discriminator + 4 bytes for the counter
-    pub base_account: Account<'info, BaseAccount>,
+pub struct InitializeRegistry<'info> {
+    #[account(init, payer = user, space = CounterRegistry::LEN, seeds = [REGISTRY_SEED], bump)]
+    pub registry: Account<'info, CounterRegistry>,
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Increment<'info> {
+#[instruction(name: String)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = BaseAccount::LEN,
+        seeds = [COUNTER_SEED, authority.key().as_ref(), name.as_bytes()],
+        bump,
+    )]
+    pub base_account: Account<'info, BaseAccount>,
+    #[account(mut, seeds = [REGISTRY_SEED], bump = registry.bump)]
+    pub registry: Account<'info, CounterRegistry>,
     #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCounter<'info> {
+    #[account(
+        mut,
+        has_one = authority @ ErrorCode::Unauthorized,
+        seeds = [COUNTER_SEED, authority.key().as_ref(), base_account.name.as_bytes()],
+        bump = base_account.bump,
+    )]
     pub base_account: Account<'info, BaseAccount>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCounter<'info> {
+    #[account(
+        mut,
+        close = authority,
+        has_one = authority @ ErrorCode::Unauthorized,
+        seeds = [COUNTER_SEED, authority.key().as_ref(), base_account.name.as_bytes()],
+        bump = base_account.bump,
+    )]
+    pub base_account: Account<'info, BaseAccount>,
+    #[account(mut, seeds = [REGISTRY_SEED], bump = registry.bump)]
+    pub registry: Account<'info, CounterRegistry>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateCounter<'info> {
+    /// CHECK: Owner, discriminator and authority are validated against the legacy layout
+    #[account(mut)]
+    pub base_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRegistry<'info> {
+    /// CHECK: Owner and discriminator are validated against the legacy layout
+    #[account(mut, seeds = [REGISTRY_SEED], bump)]
+    pub registry: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct CounterRegistry {
+    pub version: u8,
+    pub counter_count: u64,
+    pub bump: u8,
+}
+
+impl Versioned for CounterRegistry {
+    const VERSION: u8 = 1;
+    // discriminator + version + counter_count + bump
+    const LEN: usize = 8 + 1 + 8 + 1;
+}
+
+/// Layout of `CounterRegistry` before the version header.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CounterRegistryV0 {
+    pub counter_count: u64,
+    pub bump: u8,
+}
+
+impl CounterRegistryV0 {
+    pub const LEN: usize = 8 + 8 + 1;
+
+    pub fn upgrade(self) -> CounterRegistry {
+        CounterRegistry {
+            version: CounterRegistry::VERSION,
+            counter_count: self.counter_count,
+            bump: self.bump,
+        }
+    }
 }
 
 #[account]
 pub struct BaseAccount {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub name: String,
+    pub counter: u64,
+    pub bump: u8,
+}
+
+impl Versioned for BaseAccount {
+    const VERSION: u8 = 2;
+    // discriminator + version + authority + name (length prefix + bytes) + counter + bump
+    const LEN: usize = 8 + 1 + 32 + 4 + MAX_NAME_LEN + 8 + 1;
+}
+
+/// Layout of `BaseAccount` with a `u32` counter and no version header.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BaseAccountV0 {
+    pub authority: Pubkey,
+    pub name: String,
     pub counter: u32,
+    pub bump: u8,
+}
+
+impl BaseAccountV0 {
+    pub const LEN: usize = 8 + 32 + 4 + MAX_NAME_LEN + 4 + 1;
+
+    pub fn upgrade(self) -> BaseAccount {
+        BaseAccount {
+            version: BaseAccount::VERSION,
+            authority: self.authority,
+            name: self.name,
+            counter: self.counter as u64,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Layout of `BaseAccount` with a `u64` counter and no version header.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct BaseAccountV1 {
+    pub authority: Pubkey,
+    pub name: String,
+    pub counter: u64,
+    pub bump: u8,
+}
+
+impl BaseAccountV1 {
+    pub const LEN: usize = 8 + 32 + 4 + MAX_NAME_LEN + 8 + 1;
+
+    pub fn upgrade(self) -> BaseAccount {
+        BaseAccount {
+            version: BaseAccount::VERSION,
+            authority: self.authority,
+            name: self.name,
+            counter: self.counter,
+            bump: self.bump,
+        }
+    }
+}
+
+#[event]
+pub struct IdIssued {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub id: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Only the counter authority can perform this action.")]
+    Unauthorized,
+    #[msg("Counter name is too long.")]
+    NameTooLong,
+    #[msg("Counter overflow.")]
+    Overflow,
+    #[msg("Counter underflow.")]
+    Underflow,
 }
@@ -0,0 +1,198 @@
+use anchor_lang::{
+    AccountDeserialize, AnchorSerialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use common::versioning::{VersionError, Versioned};
+use my_solana_project::{
+    BaseAccount, BaseAccountV0, BaseAccountV1, CounterRegistry, CounterRegistryV0, REGISTRY_SEED,
+};
+use solana_program_test::{BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use test_utils::anchor_processor;
+
+fn legacy_account<T: AnchorSerialize>(discriminator: [u8; 8], state: &T, len: usize) -> Account {
+    let mut data = discriminator.to_vec();
+    data.extend(state.try_to_vec().unwrap());
+    data.resize(len, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(len),
+        data,
+        owner: my_solana_project::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn process(
+    accounts: Vec<(Pubkey, Account)>,
+    ix: Instruction,
+    signer: &Keypair,
+    address: Pubkey,
+) -> (Result<(), BanksClientError>, Account) {
+    let mut program_test = ProgramTest::new(
+        "my_solana_project",
+        my_solana_project::ID,
+        anchor_processor!(my_solana_project::entry),
+    );
+    for (address, account) in accounts {
+        program_test.add_account(address, account);
+    }
+    program_test.add_account(
+        signer.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::ID),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer, signer],
+        recent_blockhash,
+    );
+    let result = banks_client.process_transaction(tx).await;
+    let account = banks_client.get_account(address).await.unwrap().unwrap();
+    (result, account)
+}
+
+fn migrate_counter_ix(base_account: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: my_solana_project::ID,
+        accounts: my_solana_project::accounts::MigrateCounter {
+            base_account,
+            authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: my_solana_project::instruction::MigrateCounter {}.data(),
+    }
+}
+
+fn assert_migrated_counter(account: &Account, authority: Pubkey, counter: u64) {
+    assert_eq!(account.data.len(), BaseAccount::LEN);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(BaseAccount::LEN)
+    );
+
+    let migrated = BaseAccount::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, BaseAccount::VERSION);
+    assert_eq!(migrated.authority, authority);
+    assert_eq!(migrated.name, "orders");
+    assert_eq!(migrated.counter, counter);
+    assert_eq!(migrated.bump, 254);
+}
+
+#[tokio::test]
+async fn migrates_u32_counter() {
+    let authority = Keypair::new();
+    let base_account = Pubkey::new_unique();
+    let legacy = BaseAccountV0 {
+        authority: authority.pubkey(),
+        name: "orders".to_string(),
+        counter: u32::MAX,
+        bump: 254,
+    };
+    let account = legacy_account(BaseAccount::DISCRIMINATOR, &legacy, BaseAccountV0::LEN);
+
+    let (result, account) = process(
+        vec![(base_account, account)],
+        migrate_counter_ix(base_account, authority.pubkey()),
+        &authority,
+        base_account,
+    )
+    .await;
+    result.unwrap();
+    assert_migrated_counter(&account, authority.pubkey(), u32::MAX as u64);
+}
+
+#[tokio::test]
+async fn migrates_u64_counter_without_header() {
+    let authority = Keypair::new();
+    let base_account = Pubkey::new_unique();
+    let legacy = BaseAccountV1 {
+        authority: authority.pubkey(),
+        name: "orders".to_string(),
+        counter: u64::MAX - 1,
+        bump: 254,
+    };
+    let account = legacy_account(BaseAccount::DISCRIMINATOR, &legacy, BaseAccountV1::LEN);
+
+    let (result, account) = process(
+        vec![(base_account, account)],
+        migrate_counter_ix(base_account, authority.pubkey()),
+        &authority,
+        base_account,
+    )
+    .await;
+    result.unwrap();
+    assert_migrated_counter(&account, authority.pubkey(), u64::MAX - 1);
+}
+
+#[tokio::test]
+async fn rejects_already_migrated_counter() {
+    let authority = Keypair::new();
+    let base_account = Pubkey::new_unique();
+    let current = BaseAccount {
+        version: BaseAccount::VERSION,
+        authority: authority.pubkey(),
+        name: "orders".to_string(),
+        counter: 7,
+        bump: 254,
+    };
+    let account = legacy_account(BaseAccount::DISCRIMINATOR, &current, BaseAccount::LEN);
+
+    let (result, account) = process(
+        vec![(base_account, account)],
+        migrate_counter_ix(base_account, authority.pubkey()),
+        &authority,
+        base_account,
+    )
+    .await;
+    let code = u32::from(VersionError::AlreadyMigrated);
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(code))
+    );
+    assert_eq!(account.data.len(), BaseAccount::LEN);
+}
+
+#[tokio::test]
+async fn migrates_registry() {
+    let user = Keypair::new();
+    let (registry, bump) = Pubkey::find_program_address(&[REGISTRY_SEED], &my_solana_project::ID);
+    let legacy = CounterRegistryV0 {
+        counter_count: 42,
+        bump,
+    };
+    let account = legacy_account(
+        CounterRegistry::DISCRIMINATOR,
+        &legacy,
+        CounterRegistryV0::LEN,
+    );
+    let ix = Instruction {
+        program_id: my_solana_project::ID,
+        accounts: my_solana_project::accounts::MigrateRegistry {
+            registry,
+            user: user.pubkey(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: my_solana_project::instruction::MigrateRegistry {}.data(),
+    };
+
+    let (result, account) = process(vec![(registry, account)], ix, &user, registry).await;
+    result.unwrap();
+
+    assert_eq!(account.data.len(), CounterRegistry::LEN);
+    let migrated = CounterRegistry::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, CounterRegistry::VERSION);
+    assert_eq!(migrated.counter_count, 42);
+    assert_eq!(migrated.bump, bump);
+}
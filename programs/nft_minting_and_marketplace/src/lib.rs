@@ -1,21 +1,18 @@
-[7:34 pm, 5/10/2024] Rakesh Kumar Barik: pub struct Deposit {
-    pub user: Pubkey,
-    pub amount: u64,
-}
-
-#[event]
-pub struct Claim {
-    pub user: Pubkey,
-    pub amount: u64,
-}
-[7:45 pm, 5/10/2024] Rakesh Kumar Barik: use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke;
-use anchor_spl::token::mint_to;
-use anchor_spl::token::{MintTo, Token, Transfer, TransferFrom, TokenAccount};
-use mpl_token_metadata::instruction::create_metadata_accounts_v2;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::metadata::mpl_token_metadata::types::{Creator, DataV2};
+use anchor_spl::metadata::{create_metadata_accounts_v3, CreateMetadataAccountsV3, Metadata};
+use anchor_spl::token::{approve, mint_to};
+use anchor_spl::token::{Approve, MintTo, Token, TokenAccount};
+use common::token_utils::transfer_from_pda;
+use common::versioning::Versioned;
+use protocol_config::{Module, ProtocolConfig, ProtocolError, CONFIG_SEED};
 
 declare_id!("9FKLho9AUYScrrKgJbG1mExt5nSgEfk1CNEbR8qBwKTZ");
 
+/// Delegate of listed NFTs, which hands them over to buyers.
+pub const MARKET_AUTHORITY_SEED: &[u8] = b"market-authority";
+
 #[program]
 pub mod nft_minting_and_marketplace {
     use super::*;
@@ -36,62 +33,68 @@ pub mod nft_minting_and_marketplace {
         };
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         let result = mint_to(cpi_ctx, 1);
-        if let Err(_) = result {
+        if result.is_err() {
             return Err(error!(ErrorCode::MintFailed));
         }
         msg!("NFT has been minted!");
         msg!("Metadata account is being created:");
-        let accounts = vec![
-            ctx.accounts.token_metadata_program.to_account_info(),
-            ctx.accounts.metadata.to_account_info(),
-            ctx.accounts.mint.to_account_info(),
-            ctx.accounts.mint_authority.to_account_info(),
-            ctx.accounts.payer.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.rent.to_account_info(),
-        ];
+        let cpi_program = ctx.accounts.token_metadata_program.to_account_info();
+        let cpi_accounts = CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.metadata.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            update_authority: ctx.accounts.payer.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
         let creators = vec![
-            mpl_token_metadata::state::Creator {
+            Creator {
                 address: creator_key,
                 verified: false,
                 share: 100,
             },
-            mpl_token_metadata::state::Creator {
+            Creator {
                 address: ctx.accounts.mint_authority.key(),
                 verified: false,
                 share: 0,
             },
         ];
-        let result = invoke(
-            &create_metadata_accounts_v2(
-                ctx.accounts.token_metadata_program.key(),
-                ctx.accounts.metadata.key(),
-                ctx.accounts.mint.key(),
-                ctx.accounts.mint_authority.key(),
-                ctx.accounts.payer.key(),
-                ctx.accounts.payer.key(),
-                name,
-                symbol,
-                uri,
-                Some(creators),
-                1,
-                true,
-                false,
-                None,
-                None,
-            ),
-            &accounts,
-        );
-        if let Err(_) = result {
+        let data = DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 1,
+            creators: Some(creators),
+            collection: None,
+            uses: None,
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        let result = create_metadata_accounts_v3(cpi_ctx, data, false, true, None);
+        if result.is_err() {
             return Err(error!(ErrorCode::MetadataCreateFailed));
         }
         msg!("Metadata account has been created");
         Ok(())
     }
 
+    /// Lists the NFT held in `seller_token_account` and approves the market authority
+    /// to deliver it, so buyers can purchase it without the seller signing.
     pub fn list_nft(ctx: Context<ListNFT>, price: u64) -> Result<()> {
+        approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Approve {
+                    to: ctx.accounts.seller_token_account.to_account_info(),
+                    delegate: ctx.accounts.market_authority.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
         let nft_listing = &mut ctx.accounts.nft_listing;
+        nft_listing.version = NFTListing::VERSION;
         nft_listing.seller = *ctx.accounts.seller.key;
         nft_listing.mint = *ctx.accounts.mint.key;
         nft_listing.price = price;
@@ -115,18 +118,27 @@ pub mod nft_minting_and_marketplace {
         require!(ctx.accounts.buyer.to_account_info().lamports() >= price, ErrorCode::InsufficientFunds);
 
         // Transfer funds from buyer to seller
-        **ctx.accounts.buyer.to_account_info().try_borrow_mut_lamports()? -= price;
-        **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += price;
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            price,
+        )?;
 
         // Transfer the NFT to the buyer
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.seller_token_account.to_account_info(),
-            to: ctx.accounts.buyer_token_account.to_account_info(),
-            authority: ctx.accounts.seller.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        anchor_spl::token::transfer(cpi_ctx, 1)?;
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.seller_token_account.to_account_info(),
+            &ctx.accounts.buyer_token_account.to_account_info(),
+            &ctx.accounts.market_authority.to_account_info(),
+            &[MARKET_AUTHORITY_SEED],
+            ctx.bumps.market_authority,
+            1,
+        )?;
 
         // Mark the NFT as sold
         nft_listing.is_listed = false;
@@ -151,7 +163,7 @@ pub mod nft_minting_and_marketplace {
 
         emit!(NftDelisted {
             seller: *ctx.accounts.seller.key,
-            mint: *ctx.accounts.mint.key,
+            mint: nft_listing.mint,
         });
 
         Ok(())
@@ -165,16 +177,26 @@ pub struct MintNFT<'info> {
     /// CHECK: Not dangerous as we don't read/write
     #[account(mut)]
     pub mint: UncheckedAccount<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Marketplace) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
     pub token_program: Program<'info, Token>,
+    /// CHECK: Created and validated by the token metadata program
     #[account(mut)]
     pub metadata: UncheckedAccount<'info>,
+    /// CHECK: Validated by the token program during mint_to
     #[account(mut)]
     pub token_account: UncheckedAccount<'info>,
-    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    /// CHECK: Pays for the metadata account and acts as update authority
     #[account(mut)]
     pub payer: AccountInfo<'info>,
     pub system_program: Program<'info, System>,
-    pub rent: AccountInfo<'info>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
@@ -183,8 +205,14 @@ pub struct ListNFT<'info> {
     pub seller: Signer<'info>,
     /// CHECK: This is the mint account
     pub mint: UncheckedAccount<'info>,
-    #[account(init, payer = seller, space = 8 + 64)]
+    #[account(mut, token::mint = mint, token::authority = seller)]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA approved as delegate of the listed NFT
+    #[account(seeds = [MARKET_AUTHORITY_SEED], bump)]
+    pub market_authority: UncheckedAccount<'info>,
+    #[account(init, payer = seller, space = NFTListing::LEN)]
     pub nft_listing: Account<'info, NFTListing>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -192,17 +220,33 @@ pub struct ListNFT<'info> {
 pub struct PurchaseNFT<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
+    /// CHECK: Receives the sale lamports
     #[account(mut)]
     pub seller: UncheckedAccount<'info>,
     /// CHECK: Mint account
     pub mint: UncheckedAccount<'info>,
-    #[account(mut)]
+    #[account(mut, token::mint = mint, token::authority = seller)]
     pub seller_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    #[account(mut, token::mint = mint)]
     pub buyer_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
+    /// CHECK: PDA the seller approved as delegate when listing
+    #[account(seeds = [MARKET_AUTHORITY_SEED], bump)]
+    pub market_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = seller @ ErrorCode::ListingMismatch,
+        has_one = mint @ ErrorCode::ListingMismatch,
+    )]
     pub nft_listing: Account<'info, NFTListing>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Marketplace) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -215,12 +259,20 @@ pub struct DelistNFT<'info> {
 
 #[account]
 pub struct NFTListing {
+    pub version: u8,
     pub seller: Pubkey,
     pub mint: Pubkey,
     pub price: u64,
     pub is_listed: bool,
 }
 
+// The previous `8 + 64` allocation was too small to ever serialize a listing,
+// so no legacy listings exist and there is nothing to migrate.
+impl Versioned for NFTListing {
+    const VERSION: u8 = 1;
+    const LEN: usize = 8 + 1 + 32 + 32 + 8 + 1;
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("NFT mint failed!")]
@@ -231,6 +283,12 @@ pub enum ErrorCode {
     InsufficientFunds,
     #[msg("NFT is not listed for sale")]
     NFTNotListed,
+    #[msg("Seller or mint does not match the listing")]
+    ListingMismatch,
+}
+
+pub fn market_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MARKET_AUTHORITY_SEED], &ID)
 }
 
 #[event]
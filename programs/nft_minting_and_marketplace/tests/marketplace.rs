@@ -0,0 +1,366 @@
+use anchor_lang::prelude::{AccountInfo, ProgramError};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::system_program;
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token::spl_token;
+use common::versioning::Versioned;
+use nft_minting_and_marketplace::{
+    accounts, instruction as ix, market_authority_address, ErrorCode, NFTListing,
+};
+use protocol_config::{Module, ProtocolError};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::{
+    anchor_processor, assert_error, instruction, program_account, protocol_config, TestContext,
+    TxResult,
+};
+
+const PRICE: u64 = 1_000_000;
+
+// Stands in for the token metadata program, which is not shipped with program-test
+fn metadata_ok(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Ok(())
+}
+
+fn metadata_err(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Err(ProgramError::InvalidInstructionData)
+}
+
+async fn start(metadata_succeeds: bool) -> TestContext {
+    let mut program_test = ProgramTest::new(
+        "nft_minting_and_marketplace",
+        nft_minting_and_marketplace::ID,
+        anchor_processor!(nft_minting_and_marketplace::entry),
+    );
+    let (name, id) = ("mpl_token_metadata", mpl_token_metadata::ID);
+    if metadata_succeeds {
+        program_test.add_program(name, id, processor!(metadata_ok));
+    } else {
+        program_test.add_program(name, id, processor!(metadata_err));
+    }
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+    ctx
+}
+
+struct Nft {
+    mint: Pubkey,
+    token_account: Pubkey,
+}
+
+async fn mint_nft(ctx: &mut TestContext, mint: Pubkey, owner: &Pubkey) -> (Nft, TxResult) {
+    let token_account = ctx.create_token_account(&mint, owner).await;
+    let payer = ctx.payer();
+    let mint_authority = ctx.funded_keypair().await;
+    let (metadata, _) = Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    );
+
+    let mint_ix = instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::MintNFT {
+            mint_authority: mint_authority.pubkey(),
+            mint,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            metadata,
+            token_account,
+            token_metadata_program: mpl_token_metadata::ID,
+            payer,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::NftFormat {
+            creator_key: payer,
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            uri: "https://example.com/nft.json".to_string(),
+        },
+    );
+    let result = ctx.process(&[mint_ix], &[&mint_authority]).await;
+    (
+        Nft {
+            mint,
+            token_account,
+        },
+        result,
+    )
+}
+
+/// An empty token account of `seller`, enough to list without minting first.
+async fn unminted_nft(ctx: &mut TestContext, seller: &Keypair) -> Nft {
+    let mint = ctx.create_mint(0).await;
+    let token_account = ctx.create_token_account(&mint, &seller.pubkey()).await;
+    Nft {
+        mint,
+        token_account,
+    }
+}
+
+async fn list(ctx: &mut TestContext, seller: &Keypair, nft: &Nft) -> Pubkey {
+    let listing = Keypair::new();
+    let list = instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::ListNFT {
+            seller: seller.pubkey(),
+            mint: nft.mint,
+            seller_token_account: nft.token_account,
+            market_authority: market_authority_address().0,
+            nft_listing: listing.pubkey(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::ListNft { price: PRICE },
+    );
+    ctx.process(&[list], &[seller, &listing]).await.unwrap();
+    listing.pubkey()
+}
+
+async fn purchase(
+    ctx: &mut TestContext,
+    buyer: &Keypair,
+    seller: Pubkey,
+    nft: &Nft,
+    buyer_token_account: Pubkey,
+    nft_listing: Pubkey,
+) -> TxResult {
+    let purchase = instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::PurchaseNFT {
+            buyer: buyer.pubkey(),
+            seller,
+            mint: nft.mint,
+            seller_token_account: nft.token_account,
+            buyer_token_account,
+            market_authority: market_authority_address().0,
+            nft_listing,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::PurchaseNft {},
+    );
+    ctx.process(&[purchase], &[buyer]).await
+}
+
+async fn delist(ctx: &mut TestContext, seller: &Keypair, nft_listing: Pubkey) -> TxResult {
+    let delist = instruction(
+        nft_minting_and_marketplace::ID,
+        accounts::DelistNFT {
+            seller: seller.pubkey(),
+            nft_listing,
+        },
+        ix::DelistNft {},
+    );
+    ctx.process(&[delist], &[seller]).await
+}
+
+async fn mark_sold(ctx: &mut TestContext, nft_listing: Pubkey) {
+    let mut listing: NFTListing = ctx.fetch(&nft_listing).await;
+    listing.is_listed = false;
+    let account = program_account(nft_minting_and_marketplace::ID, &listing, NFTListing::LEN);
+    ctx.set_account(&nft_listing, account);
+}
+
+#[tokio::test]
+async fn nft_format_mints_one_token() {
+    let mut ctx = start(true).await;
+    let mint = ctx.create_mint(0).await;
+    let owner = Pubkey::new_unique();
+
+    let (nft, result) = mint_nft(&mut ctx, mint, &owner).await;
+    result.unwrap();
+    assert_eq!(ctx.token_balance(&nft.token_account).await, 1);
+}
+
+#[tokio::test]
+async fn nft_format_without_mint_authority_fails() {
+    let mut ctx = start(true).await;
+    let mint = ctx.create_mint(0).await;
+    let owner = Pubkey::new_unique();
+    // Hand the mint authority to someone other than the payer
+    let payer = ctx.payer();
+    let set_authority = spl_token::instruction::set_authority(
+        &spl_token::ID,
+        &mint,
+        Some(&owner),
+        spl_token::instruction::AuthorityType::MintTokens,
+        &payer,
+        &[],
+    )
+    .unwrap();
+    ctx.process(&[set_authority], &[]).await.unwrap();
+
+    let (_, result) = mint_nft(&mut ctx, mint, &owner).await;
+    assert_error(result, ErrorCode::MintFailed);
+}
+
+#[tokio::test]
+async fn nft_format_reports_metadata_failure() {
+    let mut ctx = start(false).await;
+    let mint = ctx.create_mint(0).await;
+    let owner = Pubkey::new_unique();
+
+    let (_, result) = mint_nft(&mut ctx, mint, &owner).await;
+    assert_error(result, ErrorCode::MetadataCreateFailed);
+}
+
+#[tokio::test]
+async fn list_nft_records_listing() {
+    let mut ctx = start(true).await;
+    let seller = ctx.funded_keypair().await;
+    let nft = unminted_nft(&mut ctx, &seller).await;
+
+    let nft_listing = list(&mut ctx, &seller, &nft).await;
+
+    let listing: NFTListing = ctx.fetch(&nft_listing).await;
+    assert_eq!(listing.version, NFTListing::VERSION);
+    assert_eq!(listing.seller, seller.pubkey());
+    assert_eq!(listing.mint, nft.mint);
+    assert_eq!(listing.price, PRICE);
+    assert!(listing.is_listed);
+}
+
+#[tokio::test]
+async fn delist_closes_listing() {
+    let mut ctx = start(true).await;
+    let seller = ctx.funded_keypair().await;
+    let nft = unminted_nft(&mut ctx, &seller).await;
+    let nft_listing = list(&mut ctx, &seller, &nft).await;
+
+    delist(&mut ctx, &seller, nft_listing).await.unwrap();
+    assert!(ctx.get_account(&nft_listing).await.is_none());
+}
+
+#[tokio::test]
+async fn delist_unlisted_nft_fails() {
+    let mut ctx = start(true).await;
+    let seller = ctx.funded_keypair().await;
+    let nft = unminted_nft(&mut ctx, &seller).await;
+    let nft_listing = list(&mut ctx, &seller, &nft).await;
+    mark_sold(&mut ctx, nft_listing).await;
+
+    let result = delist(&mut ctx, &seller, nft_listing).await;
+    assert_error(result, ErrorCode::NFTNotListed);
+}
+
+#[tokio::test]
+async fn purchase_unlisted_nft_fails() {
+    let mut ctx = start(true).await;
+    let seller = ctx.funded_keypair().await;
+    let buyer = ctx.funded_keypair().await;
+    let mint = ctx.create_mint(0).await;
+    let (nft, result) = mint_nft(&mut ctx, mint, &seller.pubkey()).await;
+    result.unwrap();
+    let buyer_token_account = ctx.create_token_account(&mint, &buyer.pubkey()).await;
+    let nft_listing = list(&mut ctx, &seller, &nft).await;
+    mark_sold(&mut ctx, nft_listing).await;
+
+    let result = purchase(
+        &mut ctx,
+        &buyer,
+        seller.pubkey(),
+        &nft,
+        buyer_token_account,
+        nft_listing,
+    )
+    .await;
+    assert_error(result, ErrorCode::NFTNotListed);
+}
+
+#[tokio::test]
+async fn purchase_without_funds_fails() {
+    let mut ctx = start(true).await;
+    let seller = ctx.funded_keypair().await;
+    let buyer = Keypair::new();
+    let mint = ctx.create_mint(0).await;
+    let (nft, result) = mint_nft(&mut ctx, mint, &seller.pubkey()).await;
+    result.unwrap();
+    let buyer_token_account = ctx.create_token_account(&mint, &buyer.pubkey()).await;
+    let nft_listing = list(&mut ctx, &seller, &nft).await;
+    // Enough to pay fees through the context payer, but not the listing price
+    let fund = solana_sdk::system_instruction::transfer(&ctx.payer(), &buyer.pubkey(), PRICE - 1);
+    ctx.process(&[fund], &[]).await.unwrap();
+
+    let result = purchase(
+        &mut ctx,
+        &buyer,
+        seller.pubkey(),
+        &nft,
+        buyer_token_account,
+        nft_listing,
+    )
+    .await;
+    assert_error(result, ErrorCode::InsufficientFunds);
+}
+
+#[tokio::test]
+async fn purchase_transfers_nft_and_price() {
+    let mut ctx = start(true).await;
+    let seller = ctx.funded_keypair().await;
+    let buyer = ctx.funded_keypair().await;
+    let mint = ctx.create_mint(0).await;
+    let (nft, result) = mint_nft(&mut ctx, mint, &seller.pubkey()).await;
+    result.unwrap();
+    let buyer_token_account = ctx.create_token_account(&mint, &buyer.pubkey()).await;
+    let nft_listing = list(&mut ctx, &seller, &nft).await;
+    let seller_lamports = ctx.get_account(&seller.pubkey()).await.unwrap().lamports;
+
+    purchase(
+        &mut ctx,
+        &buyer,
+        seller.pubkey(),
+        &nft,
+        buyer_token_account,
+        nft_listing,
+    )
+    .await
+    .unwrap();
+
+    let listing: NFTListing = ctx.fetch(&nft_listing).await;
+    assert!(!listing.is_listed);
+    assert_eq!(ctx.token_balance(&buyer_token_account).await, 1);
+    let seller_account = ctx.get_account(&seller.pubkey()).await.unwrap();
+    assert_eq!(seller_account.lamports, seller_lamports + PRICE);
+}
+
+#[tokio::test]
+async fn purchase_paying_other_seller_fails() {
+    let mut ctx = start(true).await;
+    let seller = ctx.funded_keypair().await;
+    let buyer = ctx.funded_keypair().await;
+    let mint = ctx.create_mint(0).await;
+    let (nft, result) = mint_nft(&mut ctx, mint, &seller.pubkey()).await;
+    result.unwrap();
+    let buyer_token_account = ctx.create_token_account(&mint, &buyer.pubkey()).await;
+    let nft_listing = list(&mut ctx, &seller, &nft).await;
+    let impostor = ctx.funded_keypair().await;
+    let impostor_nft = Nft {
+        mint,
+        token_account: ctx.create_token_account(&mint, &impostor.pubkey()).await,
+    };
+
+    let result = purchase(
+        &mut ctx,
+        &buyer,
+        impostor.pubkey(),
+        &impostor_nft,
+        buyer_token_account,
+        nft_listing,
+    )
+    .await;
+    assert_error(result, ErrorCode::ListingMismatch);
+}
+
+#[tokio::test]
+async fn nft_format_while_paused_fails() {
+    let mut ctx = start(true).await;
+    ctx.set_protocol_config(&[Module::Marketplace]);
+    let mint = ctx.create_mint(0).await;
+    let owner = Pubkey::new_unique();
+
+    let (_, result) = mint_nft(&mut ctx, mint, &owner).await;
+    assert_error(result, ProtocolError::ModulePaused);
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use common::versioning::Versioned;
+
+declare_id!("FM9JfrBekShEafYZuyT2PNF9dtRJ4fNL199LrUWEfpWM");
+
+pub const CONFIG_SEED: &[u8] = b"protocol-config";
+
+#[program]
+pub mod protocol_config {
+    use super::*;
+
+    /// Creates the protocol config with the signer as admin. Run once right after deployment.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, fee_treasury: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.version = ProtocolConfig::VERSION;
+        config.admin = ctx.accounts.admin.key();
+        config.pending_admin = None;
+        config.fee_treasury = fee_treasury;
+        config.paused = 0;
+        config.bump = ctx.bumps.config;
+        msg!("Protocol config initialized!");
+        Ok(())
+    }
+
+    pub fn set_paused(ctx: Context<UpdateConfig>, module: Module, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        if paused {
+            config.paused |= module.flag();
+        } else {
+            config.paused &= !module.flag();
+        }
+
+        emit!(PauseChanged { module, paused });
+        Ok(())
+    }
+
+    /// First step of an admin handover; the new admin must call `accept_admin`.
+    pub fn propose_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.pending_admin = Some(new_admin);
+
+        emit!(AdminProposed {
+            admin: config.admin,
+            pending_admin: new_admin,
+        });
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let previous_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+        config.pending_admin = None;
+
+        emit!(AdminChanged {
+            previous_admin,
+            admin: config.admin,
+        });
+        Ok(())
+    }
+
+    pub fn set_fee_treasury(ctx: Context<UpdateConfig>, fee_treasury: Pubkey) -> Result<()> {
+        ctx.accounts.config.fee_treasury = fee_treasury;
+        msg!("Fee treasury set to {}", fee_treasury);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(init, payer = admin, space = ProtocolConfig::LEN, seeds = [CONFIG_SEED], bump)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ProtocolError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(new_admin.key()) @ ProtocolError::NotPendingAdmin,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub new_admin: Signer<'info>,
+}
+
+/// Programs that can be paused independently.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Module {
+    Vesting,
+    Farming,
+    Auction,
+    Marketplace,
+    Staking,
+}
+
+impl Module {
+    pub fn flag(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+#[account]
+pub struct ProtocolConfig {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub fee_treasury: Pubkey,
+    /// One bit per `Module`, set while that module is paused.
+    pub paused: u8,
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    pub fn is_paused(&self, module: Module) -> bool {
+        self.paused & module.flag() != 0
+    }
+}
+
+impl Versioned for ProtocolConfig {
+    const VERSION: u8 = 1;
+    // discriminator + version + admin + pending_admin + fee_treasury + paused + bump
+    const LEN: usize = 8 + 1 + 32 + (1 + 32) + 32 + 1 + 1;
+}
+
+#[event]
+pub struct PauseChanged {
+    pub module: Module,
+    pub paused: bool,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminChanged {
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+// Offset keeps these codes distinct from the errors of the programs that check the pause flags
+#[error_code(offset = 8000)]
+pub enum ProtocolError {
+    #[msg("Only the protocol admin can perform this action.")]
+    Unauthorized,
+    #[msg("Signer is not the pending admin.")]
+    NotPendingAdmin,
+    #[msg("This module is paused.")]
+    ModulePaused,
+}
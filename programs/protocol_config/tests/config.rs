@@ -0,0 +1,173 @@
+use anchor_lang::system_program;
+use protocol_config::{accounts, instruction as ix, Module, ProtocolConfig, ProtocolError};
+use solana_program_test::ProgramTest;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::{anchor_processor, assert_error, instruction, TestContext, TxResult};
+
+fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[protocol_config::CONFIG_SEED], &protocol_config::ID).0
+}
+
+async fn setup() -> (TestContext, Pubkey) {
+    let program_test = ProgramTest::new(
+        "protocol_config",
+        protocol_config::ID,
+        anchor_processor!(protocol_config::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+
+    let fee_treasury = Pubkey::new_unique();
+    let init = instruction(
+        protocol_config::ID,
+        accounts::InitializeConfig {
+            config: config_address(),
+            admin: ctx.payer(),
+            system_program: system_program::ID,
+        },
+        ix::InitializeConfig { fee_treasury },
+    );
+    ctx.process(&[init], &[]).await.unwrap();
+    (ctx, fee_treasury)
+}
+
+async fn set_paused(
+    ctx: &mut TestContext,
+    admin: &Keypair,
+    module: Module,
+    paused: bool,
+) -> TxResult {
+    let set_paused = instruction(
+        protocol_config::ID,
+        accounts::UpdateConfig {
+            config: config_address(),
+            admin: admin.pubkey(),
+        },
+        ix::SetPaused { module, paused },
+    );
+    ctx.process(&[set_paused], &[admin]).await
+}
+
+async fn propose_admin(ctx: &mut TestContext, admin: &Keypair, new_admin: Pubkey) -> TxResult {
+    let propose = instruction(
+        protocol_config::ID,
+        accounts::UpdateConfig {
+            config: config_address(),
+            admin: admin.pubkey(),
+        },
+        ix::ProposeAdmin { new_admin },
+    );
+    ctx.process(&[propose], &[admin]).await
+}
+
+async fn accept_admin(ctx: &mut TestContext, new_admin: &Keypair) -> TxResult {
+    let accept = instruction(
+        protocol_config::ID,
+        accounts::AcceptAdmin {
+            config: config_address(),
+            new_admin: new_admin.pubkey(),
+        },
+        ix::AcceptAdmin {},
+    );
+    ctx.process(&[accept], &[new_admin]).await
+}
+
+/// The payer, which initialized the config and is therefore its admin.
+fn payer_keypair(ctx: &TestContext) -> Keypair {
+    ctx.context.payer.insecure_clone()
+}
+
+#[tokio::test]
+async fn initialize_makes_payer_admin() {
+    let (mut ctx, fee_treasury) = setup().await;
+
+    let config: ProtocolConfig = ctx.fetch(&config_address()).await;
+    assert_eq!(config.admin, ctx.payer());
+    assert_eq!(config.pending_admin, None);
+    assert_eq!(config.fee_treasury, fee_treasury);
+    assert_eq!(config.paused, 0);
+}
+
+#[tokio::test]
+async fn admin_pauses_and_unpauses_modules() {
+    let (mut ctx, _) = setup().await;
+    let admin = payer_keypair(&ctx);
+
+    set_paused(&mut ctx, &admin, Module::Auction, true)
+        .await
+        .unwrap();
+    set_paused(&mut ctx, &admin, Module::Staking, true)
+        .await
+        .unwrap();
+    let config: ProtocolConfig = ctx.fetch(&config_address()).await;
+    assert!(config.is_paused(Module::Auction));
+    assert!(config.is_paused(Module::Staking));
+    assert!(!config.is_paused(Module::Vesting));
+
+    set_paused(&mut ctx, &admin, Module::Auction, false)
+        .await
+        .unwrap();
+    let config: ProtocolConfig = ctx.fetch(&config_address()).await;
+    assert!(!config.is_paused(Module::Auction));
+    assert!(config.is_paused(Module::Staking));
+}
+
+#[tokio::test]
+async fn pause_by_other_signer_fails() {
+    let (mut ctx, _) = setup().await;
+    let intruder = ctx.funded_keypair().await;
+
+    let result = set_paused(&mut ctx, &intruder, Module::Vesting, true).await;
+    assert_error(result, ProtocolError::Unauthorized);
+}
+
+#[tokio::test]
+async fn proposed_admin_takes_over() {
+    let (mut ctx, _) = setup().await;
+    let admin = payer_keypair(&ctx);
+    let new_admin = ctx.funded_keypair().await;
+
+    propose_admin(&mut ctx, &admin, new_admin.pubkey())
+        .await
+        .unwrap();
+    let config: ProtocolConfig = ctx.fetch(&config_address()).await;
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!(config.pending_admin, Some(new_admin.pubkey()));
+
+    accept_admin(&mut ctx, &new_admin).await.unwrap();
+    let config: ProtocolConfig = ctx.fetch(&config_address()).await;
+    assert_eq!(config.admin, new_admin.pubkey());
+    assert_eq!(config.pending_admin, None);
+
+    let result = set_paused(&mut ctx, &admin, Module::Farming, true).await;
+    assert_error(result, ProtocolError::Unauthorized);
+    set_paused(&mut ctx, &new_admin, Module::Farming, true)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn accept_by_unproposed_signer_fails() {
+    let (mut ctx, _) = setup().await;
+    let admin = payer_keypair(&ctx);
+    let new_admin = ctx.funded_keypair().await;
+    let intruder = ctx.funded_keypair().await;
+
+    let result = accept_admin(&mut ctx, &new_admin).await;
+    assert_error(result, ProtocolError::NotPendingAdmin);
+
+    propose_admin(&mut ctx, &admin, new_admin.pubkey())
+        .await
+        .unwrap();
+    let result = accept_admin(&mut ctx, &intruder).await;
+    assert_error(result, ProtocolError::NotPendingAdmin);
+}
+
+#[tokio::test]
+async fn propose_by_other_signer_fails() {
+    let (mut ctx, _) = setup().await;
+    let intruder = ctx.funded_keypair().await;
+
+    let result = propose_admin(&mut ctx, &intruder, intruder.pubkey()).await;
+    assert_error(result, ProtocolError::Unauthorized);
+}
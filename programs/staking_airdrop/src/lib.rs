@@ -0,0 +1,359 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use common::token_utils::{transfer_from_pda, transfer_from_user};
+use common::versioning::{migrate_account, Versioned};
+use protocol_config::{Module, ProtocolConfig, ProtocolError, CONFIG_SEED};
+
+declare_id!("Fg6PaFpoGXkYsidMpWxqSWdixgW8Hd4CPj1Ntb5uZ6bX");
+
+pub const AIRDROP_VAULT_SEED: &[u8] = b"airdrop-vault";
+pub const AIRDROP_AUTHORITY_SEED: &[u8] = b"airdrop-authority";
+pub const STAKING_VAULT_SEED: &[u8] = b"staking-vault";
+pub const STAKING_AUTHORITY_SEED: &[u8] = b"staking-authority";
+
+const UNLOCK_PERIOD: i64 = 21 * 24 * 60 * 60; // 21 days in seconds
+const AIRDROP_AMOUNT: u64 = 1000 * 1_000_000; // Example: 1000 tokens (assuming 6 decimal places)
+
+#[program]
+pub mod staking_airdrop {
+    use super::*;
+
+    /// Creates the airdrop and staking vaults of `mint`, each held by its own authority so
+    /// stakes can never pay out airdrops and the reverse. Only the protocol admin can call it.
+    pub fn initialize_vaults(_ctx: Context<InitializeVaults>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn claim_airdrop(ctx: Context<ClaimAirdrop>) -> Result<()> {
+        let user_state = &mut ctx.accounts.user_state;
+        require!(!user_state.has_claimed, ErrorCode::AirdropAlreadyClaimed);
+        user_state.version = UserState::VERSION;
+        user_state.bump = ctx.bumps.user_state;
+        user_state.staking_authority_bump = staking_authority_address().1;
+
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.airdrop_vault.to_account_info(),
+            &ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.airdrop_authority.to_account_info(),
+            &[AIRDROP_AUTHORITY_SEED],
+            ctx.bumps.airdrop_authority,
+            AIRDROP_AMOUNT,
+        )?;
+
+        user_state.has_claimed = true;
+        Ok(())
+    }
+
+    /// Creates `user`'s state without claiming the airdrop, with `payer` covering the rent,
+    /// so program PDAs that cannot pay for it can stake.
+    pub fn create_user_state(ctx: Context<CreateUserState>) -> Result<()> {
+        let user_state = &mut ctx.accounts.user_state;
+        user_state.version = UserState::VERSION;
+        user_state.bump = ctx.bumps.user_state;
+        user_state.staking_authority_bump = ctx.bumps.staking_authority;
+        Ok(())
+    }
+
+    pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+        let user_state = &mut ctx.accounts.user_state;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Transfer tokens from user's token account to staking vault
+        transfer_from_user(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.staking_vault.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            amount,
+        )?;
+
+        user_state.staked_amount += amount;
+        user_state.staked_time = current_time;
+
+        Ok(())
+    }
+
+    pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
+        let user_state = &mut ctx.accounts.user_state;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Check if the unlocking period has passed
+        require!(
+            current_time >= user_state.staked_time + UNLOCK_PERIOD,
+            ErrorCode::TokensLocked
+        );
+        require!(user_state.staked_amount >= amount, ErrorCode::InsufficientStakedAmount);
+
+        // Transfer tokens back to user's token account
+        transfer_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.staking_vault.to_account_info(),
+            &ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.staking_authority.to_account_info(),
+            &[STAKING_AUTHORITY_SEED],
+            user_state.staking_authority_bump,
+            amount,
+        )?;
+
+        user_state.staked_amount -= amount;
+
+        Ok(())
+    }
+
+    /// Rewrites a user state stored in any earlier layout into the current one.
+    pub fn migrate_user_state(ctx: Context<MigrateUserState>) -> Result<()> {
+        let info = ctx.accounts.user_state.to_account_info();
+        let payer = ctx.accounts.user.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let bump = ctx.bumps.user_state;
+
+        let migrated = if info.data_len() == UserStateV0::LEN {
+            migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                UserStateV0::LEN,
+                |legacy: UserStateV0| Ok(legacy.upgrade(bump)),
+            )?
+        } else {
+            migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                UserStateV1::LEN,
+                |legacy: UserStateV1| Ok(legacy.upgrade(bump)),
+            )?
+        };
+
+        msg!("User state migrated to version {}", migrated.version);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaults<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = airdrop_authority,
+        seeds = [AIRDROP_VAULT_SEED],
+        bump,
+    )]
+    pub airdrop_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the airdrop vault
+    #[account(seeds = [AIRDROP_AUTHORITY_SEED], bump)]
+    pub airdrop_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = admin,
+        token::mint = mint,
+        token::authority = staking_authority,
+        seeds = [STAKING_VAULT_SEED],
+        bump,
+    )]
+    pub staking_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the staking vault
+    #[account(seeds = [STAKING_AUTHORITY_SEED], bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [AIRDROP_VAULT_SEED], bump)]
+    pub airdrop_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the airdrop vault
+    #[account(seeds = [AIRDROP_AUTHORITY_SEED], bump)]
+    pub airdrop_authority: UncheckedAccount<'info>,
+    #[account(init_if_needed, payer = user, space = UserState::LEN, seeds = [user.key().as_ref()], bump)]
+    pub user_state: Account<'info, UserState>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Staking) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CreateUserState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub user: Signer<'info>,
+    /// CHECK: PDA signer for the staking vault
+    #[account(seeds = [STAKING_AUTHORITY_SEED], bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(init, payer = payer, space = UserState::LEN, seeds = [user.key().as_ref()], bump)]
+    pub user_state: Account<'info, UserState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [STAKING_VAULT_SEED], bump)]
+    pub staking_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the staking vault
+    #[account(seeds = [STAKING_AUTHORITY_SEED], bump = user_state.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [user.key().as_ref()], bump = user_state.bump)]
+    pub user_state: Account<'info, UserState>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Staking) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [STAKING_VAULT_SEED], bump)]
+    pub staking_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA signer for the staking vault
+    #[account(seeds = [STAKING_AUTHORITY_SEED], bump = user_state.staking_authority_bump)]
+    pub staking_authority: UncheckedAccount<'info>,
+    #[account(mut, seeds = [user.key().as_ref()], bump = user_state.bump)]
+    pub user_state: Account<'info, UserState>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Staking) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserState<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Owner and discriminator are validated against the legacy layout
+    #[account(mut, seeds = [user.key().as_ref()], bump)]
+    pub user_state: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct UserState {
+    pub version: u8,
+    pub has_claimed: bool,
+    pub staked_amount: u64,
+    pub staked_time: i64,
+    pub bump: u8,
+    pub staking_authority_bump: u8,
+}
+
+impl Versioned for UserState {
+    const VERSION: u8 = 2;
+    const LEN: usize = 8 + 1 + 1 + 8 + 8 + 1 + 1;
+}
+
+/// Layout of `UserState` before the version header.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserStateV0 {
+    pub has_claimed: bool,
+    pub staked_amount: u64,
+    pub staked_time: i64,
+}
+
+impl UserStateV0 {
+    pub const LEN: usize = 8 + 40;
+
+    pub fn upgrade(self, bump: u8) -> UserState {
+        UserState {
+            version: UserState::VERSION,
+            has_claimed: self.has_claimed,
+            staked_amount: self.staked_amount,
+            staked_time: self.staked_time,
+            bump,
+            staking_authority_bump: staking_authority_address().1,
+        }
+    }
+}
+
+/// Version 1 layout of `UserState`, before bumps were stored.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UserStateV1 {
+    pub version: u8,
+    pub has_claimed: bool,
+    pub staked_amount: u64,
+    pub staked_time: i64,
+}
+
+impl UserStateV1 {
+    pub const LEN: usize = 8 + 1 + 1 + 8 + 8;
+
+    pub fn upgrade(self, bump: u8) -> UserState {
+        UserState {
+            version: UserState::VERSION,
+            has_claimed: self.has_claimed,
+            staked_amount: self.staked_amount,
+            staked_time: self.staked_time,
+            bump,
+            staking_authority_bump: staking_authority_address().1,
+        }
+    }
+}
+
+pub fn airdrop_vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AIRDROP_VAULT_SEED], &ID)
+}
+
+pub fn airdrop_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AIRDROP_AUTHORITY_SEED], &ID)
+}
+
+pub fn staking_vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKING_VAULT_SEED], &ID)
+}
+
+pub fn staking_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STAKING_AUTHORITY_SEED], &ID)
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("User has already claimed the airdrop.")]
+    AirdropAlreadyClaimed,
+    #[msg("Tokens are still locked.")]
+    TokensLocked,
+    #[msg("Insufficient staked amount.")]
+    InsufficientStakedAmount,
+    #[msg("Only the protocol admin can initialize the vaults.")]
+    Unauthorized,
+}
\ No newline at end of file
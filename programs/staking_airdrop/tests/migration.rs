@@ -0,0 +1,148 @@
+use anchor_lang::{
+    AccountDeserialize, AnchorSerialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use common::versioning::Versioned;
+use solana_program_test::{BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use staking_airdrop::{staking_authority_address, UserState, UserStateV0, UserStateV1};
+use test_utils::anchor_processor;
+
+fn legacy_account<T: AnchorSerialize>(state: &T, len: usize) -> Account {
+    let mut data = UserState::DISCRIMINATOR.to_vec();
+    data.extend(state.try_to_vec().unwrap());
+    data.resize(len, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: staking_airdrop::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn migrate<T: AnchorSerialize>(
+    signer: &Keypair,
+    user_state: Pubkey,
+    state: &T,
+    len: usize,
+) -> (Result<(), BanksClientError>, Option<Account>) {
+    let mut program_test = ProgramTest::new(
+        "staking_airdrop",
+        staking_airdrop::ID,
+        anchor_processor!(staking_airdrop::entry),
+    );
+    program_test.add_account(user_state, legacy_account(state, len));
+    program_test.add_account(
+        signer.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::ID),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let ix = Instruction {
+        program_id: staking_airdrop::ID,
+        accounts: staking_airdrop::accounts::MigrateUserState {
+            user: signer.pubkey(),
+            user_state,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: staking_airdrop::instruction::MigrateUserState {}.data(),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer, signer],
+        recent_blockhash,
+    );
+    let result = banks_client.process_transaction(tx).await;
+    let account = banks_client.get_account(user_state).await.unwrap();
+    (result, account)
+}
+
+#[tokio::test]
+async fn migrates_legacy_user_state() {
+    let user = Keypair::new();
+    let (user_state, bump) =
+        Pubkey::find_program_address(&[user.pubkey().as_ref()], &staking_airdrop::ID);
+    let legacy = UserStateV0 {
+        has_claimed: true,
+        staked_amount: 5_000,
+        staked_time: 1_234,
+    };
+
+    let (result, account) = migrate(&user, user_state, &legacy, UserStateV0::LEN).await;
+    result.unwrap();
+
+    let account = account.unwrap();
+    assert_eq!(account.data.len(), UserState::LEN);
+    assert_eq!(
+        account.lamports,
+        Rent::default().minimum_balance(UserState::LEN)
+    );
+
+    let migrated = UserState::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, UserState::VERSION);
+    assert_eq!(migrated.has_claimed, legacy.has_claimed);
+    assert_eq!(migrated.staked_amount, legacy.staked_amount);
+    assert_eq!(migrated.staked_time, legacy.staked_time);
+    assert_eq!(migrated.bump, bump);
+    assert_eq!(
+        migrated.staking_authority_bump,
+        staking_authority_address().1
+    );
+}
+
+#[tokio::test]
+async fn migrates_v1_user_state() {
+    let user = Keypair::new();
+    let (user_state, bump) =
+        Pubkey::find_program_address(&[user.pubkey().as_ref()], &staking_airdrop::ID);
+    let legacy = UserStateV1 {
+        version: 1,
+        has_claimed: true,
+        staked_amount: 5_000,
+        staked_time: 1_234,
+    };
+
+    let (result, account) = migrate(&user, user_state, &legacy, UserStateV1::LEN).await;
+    result.unwrap();
+
+    let account = account.unwrap();
+    assert_eq!(account.data.len(), UserState::LEN);
+    let migrated = UserState::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, UserState::VERSION);
+    assert_eq!(migrated.staked_amount, legacy.staked_amount);
+    assert_eq!(migrated.bump, bump);
+    assert_eq!(
+        migrated.staking_authority_bump,
+        staking_authority_address().1
+    );
+}
+
+#[tokio::test]
+async fn rejects_another_users_state() {
+    let intruder = Keypair::new();
+    let (user_state, _) =
+        Pubkey::find_program_address(&[Pubkey::new_unique().as_ref()], &staking_airdrop::ID);
+    let legacy = UserStateV0 {
+        has_claimed: false,
+        staked_amount: 0,
+        staked_time: 0,
+    };
+
+    let (result, account) = migrate(&intruder, user_state, &legacy, UserStateV0::LEN).await;
+    let code = u32::from(anchor_lang::error::ErrorCode::ConstraintSeeds);
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(code))
+    );
+    assert_eq!(account.unwrap().data.len(), UserStateV0::LEN);
+}
@@ -0,0 +1,180 @@
+//! Random stake/unstake sequences checked against the staking vault.
+
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use common::versioning::Versioned;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use solana_program_test::ProgramTest;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use staking_airdrop::{accounts, instruction as ix, UserState};
+use test_utils::{
+    anchor_processor, block_on, instruction, program_account, protocol_config, TestContext,
+};
+
+const UNLOCK_PERIOD: i64 = 21 * 24 * 60 * 60;
+const INITIAL_BALANCE: u64 = 100_000;
+const VAULT_RESERVE: u64 = 1_000_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Stake(u64),
+    Unstake(u64),
+    Warp(i64),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (1..40_000u64).prop_map(Op::Stake),
+        (1..40_000u64).prop_map(Op::Unstake),
+        (0..UNLOCK_PERIOD * 2).prop_map(Op::Warp),
+    ]
+}
+
+struct Staker {
+    user: Keypair,
+    user_state: Pubkey,
+    user_token_account: Pubkey,
+    vault: Pubkey,
+}
+
+async fn setup() -> (TestContext, Staker) {
+    let program_test = ProgramTest::new(
+        "staking_airdrop",
+        staking_airdrop::ID,
+        anchor_processor!(staking_airdrop::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+
+    let user = ctx.funded_keypair().await;
+    let mint = ctx.create_mint(6).await;
+    let payer = ctx.payer();
+    let initialize = instruction(
+        staking_airdrop::ID,
+        accounts::InitializeVaults {
+            admin: payer,
+            config: protocol_config(),
+            mint,
+            airdrop_vault: staking_airdrop::airdrop_vault_address().0,
+            airdrop_authority: staking_airdrop::airdrop_authority_address().0,
+            staking_vault: staking_airdrop::staking_vault_address().0,
+            staking_authority: staking_airdrop::staking_authority_address().0,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::InitializeVaults {},
+    );
+    ctx.process(&[initialize], &[]).await.unwrap();
+    let user_token_account = ctx.create_token_account(&mint, &user.pubkey()).await;
+    let vault = staking_airdrop::staking_vault_address().0;
+    ctx.mint_to(&mint, &user_token_account, INITIAL_BALANCE)
+        .await;
+    ctx.mint_to(&mint, &vault, VAULT_RESERVE).await;
+
+    let (user_state, bump) =
+        Pubkey::find_program_address(&[user.pubkey().as_ref()], &staking_airdrop::ID);
+    let state = UserState {
+        version: UserState::VERSION,
+        has_claimed: true,
+        staked_amount: 0,
+        staked_time: 0,
+        bump,
+        staking_authority_bump: staking_airdrop::staking_authority_address().1,
+    };
+    ctx.set_account(
+        &user_state,
+        program_account(staking_airdrop::ID, &state, UserState::LEN),
+    );
+
+    let staker = Staker {
+        user,
+        user_state,
+        user_token_account,
+        vault,
+    };
+    (ctx, staker)
+}
+
+async fn apply(ctx: &mut TestContext, staker: &Staker, op: &Op) -> bool {
+    let ix = match *op {
+        Op::Stake(amount) => instruction(
+            staking_airdrop::ID,
+            accounts::StakeTokens {
+                user: staker.user.pubkey(),
+                user_token_account: staker.user_token_account,
+                staking_vault: staker.vault,
+                staking_authority: staking_airdrop::staking_authority_address().0,
+                user_state: staker.user_state,
+                config: protocol_config(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::StakeTokens { amount },
+        ),
+        Op::Unstake(amount) => instruction(
+            staking_airdrop::ID,
+            accounts::UnstakeTokens {
+                user: staker.user.pubkey(),
+                staking_vault: staker.vault,
+                user_token_account: staker.user_token_account,
+                staking_authority: staking_airdrop::staking_authority_address().0,
+                user_state: staker.user_state,
+                config: protocol_config(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            ix::UnstakeTokens { amount },
+        ),
+        Op::Warp(seconds) => {
+            ctx.warp_forward(seconds).await;
+            return true;
+        }
+    };
+    ctx.process(&[ix], &[&staker.user]).await.is_ok()
+}
+
+/// Replays `ops`, checking that staked tokens stay in the vault until unlocked
+/// and that nobody withdraws more than they staked.
+async fn check(ops: &[Op]) -> Result<(), TestCaseError> {
+    let (mut ctx, staker) = setup().await;
+    let (mut staked, mut staked_time) = (0u64, 0i64);
+
+    for op in ops {
+        let now = ctx.now().await;
+        let expected = match *op {
+            Op::Stake(amount) => amount <= INITIAL_BALANCE - staked,
+            Op::Unstake(amount) => now >= staked_time + UNLOCK_PERIOD && amount <= staked,
+            Op::Warp(_) => true,
+        };
+        let accepted = apply(&mut ctx, &staker, op).await;
+        prop_assert_eq!(accepted, expected, "{:?}", op);
+        match (*op, accepted) {
+            (Op::Stake(amount), true) => {
+                staked += amount;
+                staked_time = now;
+            }
+            (Op::Unstake(amount), true) => staked -= amount,
+            _ => {}
+        }
+
+        let state: UserState = ctx.fetch(&staker.user_state).await;
+        let vault_balance = ctx.token_balance(&staker.vault).await;
+        let user_balance = ctx.token_balance(&staker.user_token_account).await;
+        prop_assert_eq!(state.staked_amount, staked);
+        prop_assert_eq!(vault_balance, VAULT_RESERVE + staked);
+        prop_assert_eq!(user_balance + staked, INITIAL_BALANCE);
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn vault_backs_every_stake(ops in vec(op(), 1..12)) {
+        block_on(check(&ops))?;
+    }
+}
@@ -0,0 +1,310 @@
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use common::versioning::Versioned;
+use protocol_config::{Module, ProtocolError};
+use solana_program_test::ProgramTest;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use staking_airdrop::{
+    accounts, airdrop_authority_address, airdrop_vault_address, instruction as ix,
+    staking_authority_address, staking_vault_address, ErrorCode, UserState,
+};
+use test_utils::{
+    anchor_processor, assert_error, instruction, program_account, protocol_config, TestContext,
+    TxResult,
+};
+
+const UNLOCK_PERIOD: i64 = 21 * 24 * 60 * 60;
+const AIRDROP_AMOUNT: u64 = 1000 * 1_000_000;
+
+struct Staker {
+    user: Keypair,
+    user_state: Pubkey,
+    user_state_bump: u8,
+    user_token_account: Pubkey,
+}
+
+async fn setup() -> (TestContext, Staker) {
+    let program_test = ProgramTest::new(
+        "staking_airdrop",
+        staking_airdrop::ID,
+        anchor_processor!(staking_airdrop::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+
+    let user = ctx.funded_keypair().await;
+    let mint = ctx.create_mint(6).await;
+    let admin = ctx.payer();
+    initialize_vaults(&mut ctx, admin, &mint, &[])
+        .await
+        .unwrap();
+    let user_token_account = ctx.create_token_account(&mint, &user.pubkey()).await;
+    ctx.mint_to(&mint, &user_token_account, 5_000).await;
+    ctx.mint_to(&mint, &airdrop_vault_address().0, AIRDROP_AMOUNT)
+        .await;
+
+    let (user_state, user_state_bump) =
+        Pubkey::find_program_address(&[user.pubkey().as_ref()], &staking_airdrop::ID);
+    let staker = Staker {
+        user,
+        user_state,
+        user_state_bump,
+        user_token_account,
+    };
+    (ctx, staker)
+}
+
+async fn initialize_vaults(
+    ctx: &mut TestContext,
+    admin: Pubkey,
+    mint: &Pubkey,
+    signers: &[&Keypair],
+) -> TxResult {
+    let initialize = instruction(
+        staking_airdrop::ID,
+        accounts::InitializeVaults {
+            admin,
+            config: protocol_config(),
+            mint: *mint,
+            airdrop_vault: airdrop_vault_address().0,
+            airdrop_authority: airdrop_authority_address().0,
+            staking_vault: staking_vault_address().0,
+            staking_authority: staking_authority_address().0,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::InitializeVaults {},
+    );
+    ctx.process(&[initialize], signers).await
+}
+
+fn set_user_state(ctx: &mut TestContext, staker: &Staker, has_claimed: bool) {
+    let state = UserState {
+        version: UserState::VERSION,
+        has_claimed,
+        staked_amount: 0,
+        staked_time: 0,
+        bump: staker.user_state_bump,
+        staking_authority_bump: staking_authority_address().1,
+    };
+    let account = program_account(staking_airdrop::ID, &state, UserState::LEN);
+    ctx.set_account(&staker.user_state, account);
+}
+
+async fn claim_airdrop(ctx: &mut TestContext, staker: &Staker) -> TxResult {
+    let claim = instruction(
+        staking_airdrop::ID,
+        accounts::ClaimAirdrop {
+            user: staker.user.pubkey(),
+            airdrop_vault: airdrop_vault_address().0,
+            user_token_account: staker.user_token_account,
+            airdrop_authority: airdrop_authority_address().0,
+            user_state: staker.user_state,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        ix::ClaimAirdrop {},
+    );
+    ctx.process(&[claim], &[&staker.user]).await
+}
+
+async fn stake(ctx: &mut TestContext, staker: &Staker, amount: u64) -> TxResult {
+    let stake = instruction(
+        staking_airdrop::ID,
+        accounts::StakeTokens {
+            user: staker.user.pubkey(),
+            user_token_account: staker.user_token_account,
+            staking_vault: staking_vault_address().0,
+            staking_authority: staking_authority_address().0,
+            user_state: staker.user_state,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::StakeTokens { amount },
+    );
+    ctx.process(&[stake], &[&staker.user]).await
+}
+
+async fn unstake(ctx: &mut TestContext, staker: &Staker, amount: u64) -> TxResult {
+    let unstake = instruction(
+        staking_airdrop::ID,
+        accounts::UnstakeTokens {
+            user: staker.user.pubkey(),
+            staking_vault: staking_vault_address().0,
+            user_token_account: staker.user_token_account,
+            staking_authority: staking_authority_address().0,
+            user_state: staker.user_state,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::UnstakeTokens { amount },
+    );
+    ctx.process(&[unstake], &[&staker.user]).await
+}
+
+#[tokio::test]
+async fn created_user_state_can_stake_without_airdrop() {
+    let (mut ctx, staker) = setup().await;
+    let payer = ctx.payer();
+
+    let create = instruction(
+        staking_airdrop::ID,
+        accounts::CreateUserState {
+            payer,
+            user: staker.user.pubkey(),
+            staking_authority: staking_authority_address().0,
+            user_state: staker.user_state,
+            system_program: system_program::ID,
+        },
+        ix::CreateUserState {},
+    );
+    ctx.process(&[create], &[&staker.user]).await.unwrap();
+    stake(&mut ctx, &staker, 1_000).await.unwrap();
+
+    let state: UserState = ctx.fetch(&staker.user_state).await;
+    assert!(!state.has_claimed);
+    assert_eq!(state.staked_amount, 1_000);
+    assert_eq!(state.bump, staker.user_state_bump);
+}
+
+#[tokio::test]
+async fn claim_airdrop_pays_once() {
+    let (mut ctx, staker) = setup().await;
+
+    claim_airdrop(&mut ctx, &staker).await.unwrap();
+
+    let state: UserState = ctx.fetch(&staker.user_state).await;
+    assert!(state.has_claimed);
+    assert_eq!(
+        ctx.token_balance(&staker.user_token_account).await,
+        5_000 + AIRDROP_AMOUNT
+    );
+    let result = claim_airdrop(&mut ctx, &staker).await;
+    assert_error(result, ErrorCode::AirdropAlreadyClaimed);
+}
+
+#[tokio::test]
+async fn second_airdrop_claim_fails() {
+    let (mut ctx, staker) = setup().await;
+    set_user_state(&mut ctx, &staker, true);
+
+    let result = claim_airdrop(&mut ctx, &staker).await;
+    assert_error(result, ErrorCode::AirdropAlreadyClaimed);
+}
+
+#[tokio::test]
+async fn stake_moves_tokens_to_vault() {
+    let (mut ctx, staker) = setup().await;
+    set_user_state(&mut ctx, &staker, false);
+    let now = ctx.now().await;
+
+    stake(&mut ctx, &staker, 2_000).await.unwrap();
+
+    let state: UserState = ctx.fetch(&staker.user_state).await;
+    assert_eq!(state.staked_amount, 2_000);
+    assert_eq!(state.staked_time, now);
+    assert_eq!(ctx.token_balance(&staker.user_token_account).await, 3_000);
+    assert_eq!(ctx.token_balance(&staking_vault_address().0).await, 2_000);
+    assert_eq!(
+        ctx.token_balance(&airdrop_vault_address().0).await,
+        AIRDROP_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn unstake_before_unlock_fails() {
+    let (mut ctx, staker) = setup().await;
+    set_user_state(&mut ctx, &staker, false);
+    stake(&mut ctx, &staker, 2_000).await.unwrap();
+    ctx.warp_forward(UNLOCK_PERIOD - 1).await;
+
+    let result = unstake(&mut ctx, &staker, 2_000).await;
+    assert_error(result, ErrorCode::TokensLocked);
+}
+
+#[tokio::test]
+async fn unstake_more_than_staked_fails() {
+    let (mut ctx, staker) = setup().await;
+    set_user_state(&mut ctx, &staker, false);
+    stake(&mut ctx, &staker, 2_000).await.unwrap();
+    ctx.warp_forward(UNLOCK_PERIOD).await;
+
+    let result = unstake(&mut ctx, &staker, 2_001).await;
+    assert_error(result, ErrorCode::InsufficientStakedAmount);
+}
+
+#[tokio::test]
+async fn unstake_after_unlock_returns_tokens() {
+    let (mut ctx, staker) = setup().await;
+    set_user_state(&mut ctx, &staker, false);
+    stake(&mut ctx, &staker, 2_000).await.unwrap();
+    ctx.warp_forward(UNLOCK_PERIOD).await;
+
+    unstake(&mut ctx, &staker, 1_500).await.unwrap();
+
+    let state: UserState = ctx.fetch(&staker.user_state).await;
+    assert_eq!(state.staked_amount, 500);
+    assert_eq!(ctx.token_balance(&staker.user_token_account).await, 4_500);
+}
+
+#[tokio::test]
+async fn stake_while_paused_fails() {
+    let (mut ctx, staker) = setup().await;
+    set_user_state(&mut ctx, &staker, false);
+    ctx.set_protocol_config(&[Module::Staking]);
+
+    let result = stake(&mut ctx, &staker, 2_000).await;
+    assert_error(result, ProtocolError::ModulePaused);
+    assert_eq!(ctx.token_balance(&staker.user_token_account).await, 5_000);
+}
+
+#[tokio::test]
+async fn initialize_vaults_by_non_admin_fails() {
+    let program_test = ProgramTest::new(
+        "staking_airdrop",
+        staking_airdrop::ID,
+        anchor_processor!(staking_airdrop::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+    let mint = ctx.create_mint(6).await;
+    let intruder = ctx.funded_keypair().await;
+
+    let result = initialize_vaults(&mut ctx, intruder.pubkey(), &mint, &[&intruder]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn unstake_cannot_drain_airdrop_vault() {
+    let (mut ctx, staker) = setup().await;
+    set_user_state(&mut ctx, &staker, false);
+    stake(&mut ctx, &staker, 2_000).await.unwrap();
+    ctx.warp_forward(UNLOCK_PERIOD).await;
+
+    let unstake = instruction(
+        staking_airdrop::ID,
+        accounts::UnstakeTokens {
+            user: staker.user.pubkey(),
+            staking_vault: airdrop_vault_address().0,
+            user_token_account: staker.user_token_account,
+            staking_authority: airdrop_authority_address().0,
+            user_state: staker.user_state,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::UnstakeTokens { amount: 2_000 },
+    );
+    let result = ctx.process(&[unstake], &[&staker.user]).await;
+    assert_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+    assert_eq!(
+        ctx.token_balance(&airdrop_vault_address().0).await,
+        AIRDROP_AMOUNT
+    );
+}
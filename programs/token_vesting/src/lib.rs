@@ -0,0 +1,3207 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use common::merkle;
+use common::token_utils::{
+    close_pda_token_account, harvest_withheld_fees, transfer_checked_from_pda,
+    transfer_checked_from_user,
+};
+use common::versioning::{close_account, migrate_account, read_legacy_account, Versioned};
+use protocol_config::{Module, ProtocolConfig, ProtocolError, CONFIG_SEED};
+use staking_airdrop::cpi::accounts::{CreateUserState, StakeTokens, UnstakeTokens};
+use staking_airdrop::program::StakingAirdrop;
+
+declare_id!("BhSdYL2GxWMZqag3JWrU1gPS7GHXVNe8P9TYBp4Cig1m");
+
+pub const VESTING_SEED: &[u8] = b"vesting";
+pub const CAMPAIGN_SEED: &[u8] = b"campaign";
+pub const CAMPAIGN_CLAIM_SEED: &[u8] = b"campaign-claim";
+pub const CAMPAIGN_VESTING_SEED: &[u8] = b"campaign-vesting";
+pub const VOTER_SEED: &[u8] = b"voter";
+pub const VESTING_ADMIN_SEED: &[u8] = b"vesting-admin";
+pub const ADMIN_PROPOSAL_SEED: &[u8] = b"admin-proposal";
+pub const MAX_ADMIN_SIGNERS: usize = 10;
+pub const MAX_MILESTONES: usize = 16;
+pub const BASIS_POINTS: u64 = 10_000;
+/// Remaining lock time at which a locked token carries its full governance weight.
+pub const MAX_LOCK_DURATION: i64 = 4 * 365 * 24 * 60 * 60;
+/// Seed of the PDA that owned every vault before schedules got their own.
+pub const VAULT_AUTHORITY_SEED: &[u8] = b"vault-authority";
+
+#[program]
+pub mod token_vesting {
+    use super::*;
+
+    /// Creates a schedule whose cliff and end are measured from `start_time`, which may
+    /// lie in the future so grants can be set up ahead of time.
+    ///
+    /// The schedule is credited with what actually reaches the vault, so for mints with a
+    /// transfer fee it is `total_amount` minus the fee and milestones are scaled down.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_vesting(
+        ctx: Context<InitializeVesting>,
+        schedule_id: u64,
+        start_time: i64,
+        cliff_duration: i64,
+        vesting_duration: i64,
+        total_amount: u64,
+        revocable: bool,
+        kind: VestingKind,
+    ) -> Result<()> {
+        let terms = VestingTerms {
+            schedule_id,
+            start_time,
+            cliff_duration,
+            vesting_duration,
+            total_amount,
+            revocable,
+            kind,
+        };
+        open_schedule(
+            &mut ctx.accounts.vesting_account,
+            &mut ctx.accounts.vesting_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.admin_token_account.to_account_info(),
+            &ctx.accounts.admin.to_account_info(),
+            None,
+            ctx.accounts.beneficiary.key(),
+            terms,
+            ctx.bumps.vesting_account,
+        )
+    }
+
+    /// Pays out `amount`, or everything claimable when `None`, signed by the beneficiary
+    /// or their claim delegate. The beneficiary picks any destination while a delegate
+    /// can only pay into the beneficiary's associated token account.
+    ///
+    /// When the vault holds less than `amount`, the rest is unstaked first, which needs the
+    /// optional staking accounts and fails while the stake is in its unlock period.
+    pub fn claim_tokens(ctx: Context<ClaimTokens>, amount: Option<u64>) -> Result<()> {
+        let vesting_info = ctx.accounts.vesting_account.to_account_info();
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if ctx.accounts.claimer.key() != vesting_account.beneficiary {
+            require_keys_eq!(
+                ctx.accounts.destination.key(),
+                get_associated_token_address_with_program_id(
+                    &vesting_account.beneficiary,
+                    &vesting_account.mint,
+                    ctx.accounts.token_program.key,
+                ),
+                ErrorCode::InvalidDestination
+            );
+        }
+
+        require!(current_time >= vesting_account.cliff_time, ErrorCode::CliffNotReached);
+
+        let vested_amount = get_vested_amount(vesting_account, current_time)?;
+        // A split can leave the claimed amount a unit ahead of the rounded down vested one
+        let claimable_amount = vested_amount.saturating_sub(vesting_account.claimed_amount);
+        require!(claimable_amount > 0, ErrorCode::NoTokensAvailable);
+        let amount = amount.unwrap_or(claimable_amount);
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(
+            amount <= claimable_amount,
+            ErrorCode::AmountExceedsClaimable
+        );
+
+        let in_vault = ctx.accounts.vesting_vault.amount;
+        if amount > in_vault {
+            let shortfall = amount - in_vault;
+            require!(
+                shortfall <= vesting_account.staked_amount,
+                ErrorCode::InsufficientStake
+            );
+            let (
+                Some(staking_program),
+                Some(staking_vault),
+                Some(staking_vault_authority),
+                Some(stake_state),
+                Some(system_program),
+            ) = (
+                ctx.accounts.staking_program.as_ref(),
+                ctx.accounts.staking_vault.as_ref(),
+                ctx.accounts.staking_vault_authority.as_ref(),
+                ctx.accounts.stake_state.as_ref(),
+                ctx.accounts.system_program.as_ref(),
+            )
+            else {
+                return err!(ErrorCode::StakingAccountsMissing);
+            };
+            unstake_to_vault(
+                staking_program.to_account_info(),
+                UnstakeTokens {
+                    user: vesting_info.clone(),
+                    staking_vault: staking_vault.to_account_info(),
+                    user_token_account: ctx.accounts.vesting_vault.to_account_info(),
+                    staking_authority: staking_vault_authority.to_account_info(),
+                    user_state: stake_state.to_account_info(),
+                    config: ctx.accounts.config.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: system_program.to_account_info(),
+                },
+                vesting_account,
+                shortfall,
+            )?;
+            vesting_account.staked_amount -= shortfall;
+        }
+
+        transfer_from_vault(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.vesting_vault.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.destination.to_account_info(),
+            &vesting_info,
+            vesting_account,
+            amount,
+        )?;
+
+        vesting_account.claimed_amount += amount;
+
+        emit!(TokensClaimed {
+            vesting_account: vesting_info.key(),
+            beneficiary: vesting_account.beneficiary,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            claimed_amount: vesting_account.claimed_amount,
+            total_amount: vesting_account.total_amount,
+            timestamp: current_time,
+        });
+        Ok(())
+    }
+
+    /// Reports the schedule's amounts at `at_timestamp`, or now, through return data so
+    /// clients can read them by simulating this instruction.
+    pub fn view_vesting(
+        ctx: Context<ViewVesting>,
+        at_timestamp: Option<i64>,
+    ) -> Result<VestingView> {
+        let at_timestamp = match at_timestamp {
+            Some(at_timestamp) => at_timestamp,
+            None => Clock::get()?.unix_timestamp,
+        };
+        ctx.accounts.vesting_account.view(at_timestamp)
+    }
+
+    /// Records the schedule's current governance weight in its `VoterRecord` and returns it.
+    ///
+    /// Anyone can refresh a record. The weight decays every second, so governance programs
+    /// should call this through CPI when counting a vote rather than trust an older record.
+    pub fn update_voter_record(ctx: Context<UpdateVoterRecord>) -> Result<u64> {
+        let vesting_account = &ctx.accounts.vesting_account;
+        let now = Clock::get()?.unix_timestamp;
+        let weight = governance_weight(vesting_account, now)?;
+        ctx.accounts.voter_record.set_inner(VoterRecord {
+            version: VoterRecord::VERSION,
+            vesting_account: vesting_account.key(),
+            voter: vesting_account.beneficiary,
+            mint: vesting_account.mint,
+            weight,
+            updated_at: now,
+            bump: ctx.bumps.voter_record,
+        });
+        Ok(weight)
+    }
+
+    /// Closes a fully paid out schedule and its empty vault, sending their rent to `receiver`.
+    /// Transfer fees withheld in the vault are first harvested to the mint.
+    pub fn close_vesting(ctx: Context<CloseVesting>) -> Result<()> {
+        let vesting_account = &ctx.accounts.vesting_account;
+        require!(
+            vesting_account.claimed_amount == vesting_account.total_amount,
+            ErrorCode::VestingNotComplete
+        );
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let vesting_vault = ctx.accounts.vesting_vault.to_account_info();
+        harvest_withheld_fees(
+            &token_program,
+            &ctx.accounts.mint.to_account_info(),
+            &vesting_vault,
+        )?;
+        close_pda_token_account(
+            &token_program,
+            &vesting_vault,
+            &ctx.accounts.receiver.to_account_info(),
+            &vesting_account.to_account_info(),
+            &[
+                vesting_account.seed_prefix(),
+                vesting_account.mint.as_ref(),
+                vesting_account.original_beneficiary.as_ref(),
+                &vesting_account.schedule_id.to_le_bytes(),
+            ],
+            vesting_account.bump,
+        )?;
+
+        emit!(VestingClosed {
+            vesting_account: vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
+            claimed_amount: vesting_account.claimed_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Ends a revocable schedule: the beneficiary keeps what has vested so far and the
+    /// unvested remainder goes back to the protocol treasury.
+    pub fn revoke_vesting(ctx: Context<RevokeVesting>) -> Result<()> {
+        revoke_schedule(
+            &mut ctx.accounts.vesting_account,
+            &ctx.accounts.vesting_vault.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.treasury_token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )
+    }
+
+    /// Adds `amount`, less any transfer fee, to the schedule's total. The curve keeps its
+    /// shape, so the extra tokens unlock on the same dates and in the same proportions as
+    /// the rest.
+    pub fn top_up_vesting(ctx: Context<TopUpVesting>, amount: u64) -> Result<()> {
+        top_up_schedule(
+            &mut ctx.accounts.vesting_account,
+            &mut ctx.accounts.vesting_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.admin_token_account.to_account_info(),
+            &ctx.accounts.admin.to_account_info(),
+            None,
+            amount,
+        )
+    }
+
+    /// Moves `amount` of the total to a new schedule of `new_beneficiary` with the same
+    /// id, dates and curve. The claimed amount is split in the same proportion, rounded
+    /// down for the new schedule, so both halves always add up to the original. A
+    /// transfer fee on the moved tokens comes out of the new schedule.
+    pub fn split_vesting(
+        ctx: Context<SplitVesting>,
+        amount: u64,
+        new_beneficiary: Pubkey,
+    ) -> Result<()> {
+        let vesting_info = ctx.accounts.vesting_account.to_account_info();
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(
+            amount < vesting_account.total_amount,
+            ErrorCode::InvalidSplitAmount
+        );
+
+        let total_amount = vesting_account.total_amount;
+        let claimed_amount = proportion(vesting_account.claimed_amount, amount, total_amount);
+        let (kind, new_kind) = vesting_account.kind.split(amount, total_amount);
+
+        transfer_from_vault(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.vesting_vault.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.new_vesting_vault.to_account_info(),
+            &vesting_info,
+            vesting_account,
+            amount - claimed_amount,
+        )?;
+        let new_total_amount =
+            claimed_amount + received_amount(&mut ctx.accounts.new_vesting_vault, 0)?;
+
+        ctx.accounts.new_vesting_account.set_inner(VestingAccount {
+            version: VestingAccount::VERSION,
+            beneficiary: new_beneficiary,
+            original_beneficiary: new_beneficiary,
+            admin: vesting_account.admin,
+            mint: vesting_account.mint,
+            schedule_id: vesting_account.schedule_id,
+            total_amount: new_total_amount,
+            claimed_amount,
+            start_time: vesting_account.start_time,
+            cliff_time: vesting_account.cliff_time,
+            vesting_end_time: vesting_account.vesting_end_time,
+            kind: new_kind.scaled(new_total_amount, amount),
+            revocable: vesting_account.revocable,
+            revoked: vesting_account.revoked,
+            pending_beneficiary: None,
+            claim_delegate: None,
+            staked_amount: 0,
+            campaign: vesting_account.campaign,
+            bump: ctx.bumps.new_vesting_account,
+        });
+        vesting_account.total_amount -= amount;
+        vesting_account.claimed_amount -= claimed_amount;
+        vesting_account.kind = kind;
+
+        emit!(VestingSplit {
+            vesting_account: vesting_info.key(),
+            new_vesting_account: ctx.accounts.new_vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
+            new_beneficiary,
+            moved_amount: amount - claimed_amount,
+            received_amount: new_total_amount - claimed_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Folds `source_vesting_account` into `vesting_account` and closes it and its vault.
+    /// Both must belong to the same beneficiary and admin and unlock on the same dates.
+    /// A transfer fee on the moved tokens is taken off the merged total.
+    pub fn merge_vesting(ctx: Context<MergeVesting>) -> Result<()> {
+        let source_info = ctx.accounts.source_vesting_account.to_account_info();
+        let source = &ctx.accounts.source_vesting_account;
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        // The source vault is closed, which would strand its stake
+        require!(source.staked_amount == 0, ErrorCode::StakeOutstanding);
+
+        let same_terms = source.admin == vesting_account.admin
+            && source.start_time == vesting_account.start_time
+            && source.cliff_time == vesting_account.cliff_time
+            && source.vesting_end_time == vesting_account.vesting_end_time
+            && source.revocable == vesting_account.revocable
+            && source.revoked == vesting_account.revoked;
+        let kind = vesting_account
+            .kind
+            .merged(&source.kind)
+            .filter(|_| same_terms)
+            .ok_or(ErrorCode::ScheduleMismatch)?;
+        let combined_amount = vesting_account
+            .total_amount
+            .checked_add(source.total_amount)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let mint = ctx.accounts.mint.to_account_info();
+        let source_vault = ctx.accounts.source_vesting_vault.to_account_info();
+        let sent = ctx.accounts.source_vesting_vault.amount;
+        let balance_before = ctx.accounts.vesting_vault.amount;
+        transfer_from_vault(
+            &token_program,
+            &source_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.vesting_vault.to_account_info(),
+            &source_info,
+            source,
+            sent,
+        )?;
+        // A transfer fee on the moved tokens comes off the merged total
+        let fee = sent - received_amount(&mut ctx.accounts.vesting_vault, balance_before)?;
+        let total_amount = combined_amount - fee;
+
+        vesting_account.total_amount = total_amount;
+        vesting_account.claimed_amount += source.claimed_amount;
+        vesting_account.kind = kind.scaled(total_amount, combined_amount);
+
+        let moved_amount = source.total_amount - source.claimed_amount;
+        emit!(VestingMerged {
+            vesting_account: vesting_account.key(),
+            source_vesting_account: source_info.key(),
+            beneficiary: vesting_account.beneficiary,
+            moved_amount,
+            received_amount: moved_amount - fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        harvest_withheld_fees(&token_program, &mint, &source_vault)?;
+        close_pda_token_account(
+            &token_program,
+            &source_vault,
+            &ctx.accounts.beneficiary.to_account_info(),
+            &source_info,
+            &[
+                source.seed_prefix(),
+                source.mint.as_ref(),
+                source.original_beneficiary.as_ref(),
+                &source.schedule_id.to_le_bytes(),
+            ],
+            source.bump,
+        )
+    }
+
+    /// Stakes `amount` of the vault in `staking_airdrop` with the schedule PDA as staker,
+    /// creating its stake state at the beneficiary's expense on first use. Staked tokens
+    /// keep vesting as before and `claim_tokens` unstakes them when the vault runs short.
+    ///
+    /// Only irrevocable schedules can stake, since revocation has to return unvested
+    /// tokens at once. The pool pays no rewards of its own, so unstaking returns exactly
+    /// what was staked.
+    pub fn stake_vesting(ctx: Context<StakeVesting>, amount: u64) -> Result<()> {
+        let vesting_info = ctx.accounts.vesting_account.to_account_info();
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(!vesting_account.revocable, ErrorCode::RevocableStake);
+        let staking_program = ctx.accounts.staking_program.to_account_info();
+
+        with_vesting_signer(vesting_account, |signer| {
+            if ctx.accounts.stake_state.data_is_empty() {
+                staking_airdrop::cpi::create_user_state(CpiContext::new_with_signer(
+                    staking_program.clone(),
+                    CreateUserState {
+                        payer: ctx.accounts.beneficiary.to_account_info(),
+                        user: vesting_info.clone(),
+                        staking_authority: ctx.accounts.staking_vault_authority.to_account_info(),
+                        user_state: ctx.accounts.stake_state.to_account_info(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                    },
+                    signer,
+                ))?;
+            }
+            staking_airdrop::cpi::stake_tokens(
+                CpiContext::new_with_signer(
+                    staking_program.clone(),
+                    StakeTokens {
+                        user: vesting_info.clone(),
+                        user_token_account: ctx.accounts.vesting_vault.to_account_info(),
+                        staking_vault: ctx.accounts.staking_vault.to_account_info(),
+                        staking_authority: ctx.accounts.staking_vault_authority.to_account_info(),
+                        user_state: ctx.accounts.stake_state.to_account_info(),
+                        config: ctx.accounts.config.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                    },
+                    signer,
+                ),
+                amount,
+            )
+        })?;
+
+        vesting_account.staked_amount = vesting_account
+            .staked_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Moves `amount` of the schedule's stake back to its vault once the pool's unlock
+    /// period has passed.
+    pub fn unstake_vesting(ctx: Context<StakeVesting>, amount: u64) -> Result<()> {
+        let vesting_info = ctx.accounts.vesting_account.to_account_info();
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(
+            amount <= vesting_account.staked_amount,
+            ErrorCode::InsufficientStake
+        );
+
+        unstake_to_vault(
+            ctx.accounts.staking_program.to_account_info(),
+            UnstakeTokens {
+                user: vesting_info,
+                staking_vault: ctx.accounts.staking_vault.to_account_info(),
+                user_token_account: ctx.accounts.vesting_vault.to_account_info(),
+                staking_authority: ctx.accounts.staking_vault_authority.to_account_info(),
+                user_state: ctx.accounts.stake_state.to_account_info(),
+                config: ctx.accounts.config.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            vesting_account,
+            amount,
+        )?;
+
+        vesting_account.staked_amount -= amount;
+        Ok(())
+    }
+
+    /// Moves a schedule stored in any earlier layout, whose tokens sit in a vault of the
+    /// shared vault authority, to its own PDA and vault. The legacy account is closed.
+    ///
+    /// Legacy schedules never recorded their vault or mint, so only the protocol admin
+    /// may pick them.
+    pub fn migrate_vesting(ctx: Context<MigrateVesting>, schedule_id: u64) -> Result<()> {
+        let info = ctx.accounts.legacy_vesting_account.to_account_info();
+        let mint = ctx.accounts.mint.key();
+        let bump = ctx.bumps.vesting_account;
+
+        let migrated = match info.data_len() {
+            VestingAccountV0::LEN => read_legacy_account::<VestingAccountV0, VestingAccount>(
+                &info,
+                VestingAccountV0::LEN,
+            )?
+            .upgrade(mint, schedule_id, bump)
+            .upgrade()
+            .upgrade()
+            .upgrade()
+            .upgrade()
+            .upgrade(),
+            VestingAccountV1::LEN => read_legacy_account::<VestingAccountV1, VestingAccount>(
+                &info,
+                VestingAccountV1::LEN,
+            )?
+            .upgrade(mint, schedule_id, bump)
+            .upgrade()
+            .upgrade()
+            .upgrade()
+            .upgrade()
+            .upgrade(),
+            _ => read_legacy_account::<VestingAccountV2, VestingAccount>(
+                &info,
+                VestingAccountV2::LEN,
+            )?
+            .upgrade(mint, schedule_id, bump)
+            .upgrade()
+            .upgrade()
+            .upgrade()
+            .upgrade()
+            .upgrade(),
+        };
+        require_keys_eq!(
+            migrated.beneficiary,
+            ctx.accounts.beneficiary.key(),
+            ErrorCode::Unauthorized
+        );
+
+        // Move whatever the schedule has not paid out yet into its own vault. Legacy
+        // vaults predate Token-2022 support, so no transfer fee can apply.
+        let remaining = migrated.total_amount - migrated.claimed_amount;
+        transfer_checked_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.legacy_vault.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.vesting_vault.to_account_info(),
+            &ctx.accounts.vault_authority.to_account_info(),
+            &[VAULT_AUTHORITY_SEED],
+            ctx.bumps.vault_authority,
+            remaining,
+        )?;
+
+        close_account(&info, &ctx.accounts.admin.to_account_info())?;
+        emit!(VestingMigrated {
+            vesting_account: ctx.accounts.vesting_account.key(),
+            legacy_vesting_account: info.key(),
+            beneficiary: migrated.beneficiary,
+            total_amount: migrated.total_amount,
+            claimed_amount: migrated.claimed_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        ctx.accounts.vesting_account.set_inner(migrated);
+
+        msg!(
+            "Vesting account migrated to version {}",
+            VestingAccount::VERSION
+        );
+        Ok(())
+    }
+
+    /// Rewrites a schedule PDA stored in an earlier layout into the current one.
+    pub fn upgrade_vesting(ctx: Context<UpgradeVesting>) -> Result<()> {
+        let info = ctx.accounts.vesting_account.to_account_info();
+        let payer = ctx.accounts.beneficiary.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let beneficiary = ctx.accounts.beneficiary.key();
+
+        let migrated = match info.data_len() {
+            VestingAccountV3::LEN => migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                VestingAccountV3::LEN,
+                |legacy: VestingAccountV3| {
+                    require_keys_eq!(legacy.beneficiary, beneficiary, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade().upgrade().upgrade().upgrade().upgrade())
+                },
+            )?,
+            VestingAccountV4::LEN => migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                VestingAccountV4::LEN,
+                |legacy: VestingAccountV4| {
+                    require_keys_eq!(legacy.beneficiary, beneficiary, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade().upgrade().upgrade().upgrade())
+                },
+            )?,
+            VestingAccountV5::LEN => migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                VestingAccountV5::LEN,
+                |legacy: VestingAccountV5| {
+                    require_keys_eq!(legacy.beneficiary, beneficiary, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade().upgrade().upgrade())
+                },
+            )?,
+            VestingAccountV6::LEN => migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                VestingAccountV6::LEN,
+                |legacy: VestingAccountV6| {
+                    require_keys_eq!(legacy.beneficiary, beneficiary, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade().upgrade())
+                },
+            )?,
+            _ => migrate_account(
+                &info,
+                &payer,
+                &system_program,
+                VestingAccountV7::LEN,
+                |legacy: VestingAccountV7| {
+                    require_keys_eq!(legacy.beneficiary, beneficiary, ErrorCode::Unauthorized);
+                    Ok(legacy.upgrade())
+                },
+            )?,
+        };
+
+        msg!("Vesting account upgraded to version {}", migrated.version);
+        Ok(())
+    }
+
+    /// First step of handing a schedule to another wallet; the new beneficiary must
+    /// call `accept_beneficiary`.
+    pub fn transfer_beneficiary(
+        ctx: Context<UpdateBeneficiary>,
+        new_beneficiary: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.vesting_account.pending_beneficiary = Some(new_beneficiary);
+        Ok(())
+    }
+
+    /// Completes a transfer. The schedule keeps its address and the claim delegate of
+    /// the previous beneficiary is dropped.
+    pub fn accept_beneficiary(ctx: Context<AcceptBeneficiary>) -> Result<()> {
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        let previous_beneficiary = vesting_account.beneficiary;
+        vesting_account.beneficiary = ctx.accounts.new_beneficiary.key();
+        vesting_account.pending_beneficiary = None;
+        vesting_account.claim_delegate = None;
+        msg!(
+            "Vesting beneficiary changed to {}",
+            vesting_account.beneficiary
+        );
+
+        emit!(BeneficiaryChanged {
+            vesting_account: vesting_account.key(),
+            previous_beneficiary,
+            new_beneficiary: vesting_account.beneficiary,
+            total_amount: vesting_account.total_amount,
+            claimed_amount: vesting_account.claimed_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Lets `claim_delegate` call `claim_tokens` for the beneficiary, or nobody when `None`.
+    pub fn set_claim_delegate(
+        ctx: Context<UpdateBeneficiary>,
+        claim_delegate: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.vesting_account.claim_delegate = claim_delegate;
+        Ok(())
+    }
+
+    /// Funds a campaign whose grants are committed to by `merkle_root` and only become
+    /// schedules once each beneficiary claims theirs with `claim_vesting`.
+    ///
+    /// The campaign is credited with what reaches its vault, so with a transfer fee mint
+    /// `total_amount` has to cover the fee on top of the grants.
+    pub fn create_campaign(
+        ctx: Context<CreateCampaign>,
+        campaign_id: u64,
+        merkle_root: [u8; 32],
+        start_time: i64,
+        total_amount: u64,
+        revocable: bool,
+    ) -> Result<()> {
+        require!(total_amount > 0, ErrorCode::ZeroAmount);
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.version = VestingCampaign::VERSION;
+        campaign.admin = ctx.accounts.admin.key();
+        campaign.mint = ctx.accounts.mint.key();
+        campaign.campaign_id = campaign_id;
+        campaign.merkle_root = merkle_root;
+        campaign.start_time = start_time;
+        campaign.claimed_amount = 0;
+        campaign.revocable = revocable;
+        campaign.bump = ctx.bumps.campaign;
+
+        transfer_checked_from_user(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.admin_token_account.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.campaign_vault.to_account_info(),
+            &ctx.accounts.admin.to_account_info(),
+            total_amount,
+        )?;
+        campaign.total_amount = received_amount(&mut ctx.accounts.campaign_vault, 0)?;
+        Ok(())
+    }
+
+    /// Creates the beneficiary's schedule from their campaign leaf, using the campaign id
+    /// as its schedule id, and moves its tokens from the campaign vault into its own vault.
+    /// The schedule PDA is derived from `CAMPAIGN_VESTING_SEED`, so a direct grant with the
+    /// same schedule id cannot occupy it.
+    pub fn claim_vesting(
+        ctx: Context<ClaimVesting>,
+        amount: u64,
+        cliff_time: i64,
+        vesting_end_time: i64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let beneficiary = ctx.accounts.beneficiary.key();
+        let leaf = campaign_leaf(&beneficiary, amount, cliff_time, vesting_end_time);
+        require!(
+            merkle::verify(&proof, &campaign.merkle_root, leaf),
+            ErrorCode::InvalidProof
+        );
+        require!(amount > 0, ErrorCode::ZeroAmount);
+        require!(
+            vesting_end_time > campaign.start_time,
+            ErrorCode::ZeroVestingPeriod
+        );
+        require!(
+            campaign.start_time <= cliff_time && cliff_time <= vesting_end_time,
+            ErrorCode::InvalidScheduleTimes
+        );
+
+        campaign.claimed_amount = campaign
+            .claimed_amount
+            .checked_add(amount)
+            .filter(|claimed| *claimed <= campaign.total_amount)
+            .ok_or(ErrorCode::CampaignExhausted)?;
+
+        ctx.accounts.campaign_claim.set_inner(CampaignClaim {
+            version: CampaignClaim::VERSION,
+            amount,
+            bump: ctx.bumps.campaign_claim,
+        });
+        ctx.accounts.vesting_account.set_inner(VestingAccount {
+            version: VestingAccount::VERSION,
+            beneficiary,
+            original_beneficiary: beneficiary,
+            admin: campaign.admin,
+            mint: campaign.mint,
+            schedule_id: campaign.campaign_id,
+            total_amount: amount,
+            claimed_amount: 0,
+            start_time: campaign.start_time,
+            cliff_time,
+            vesting_end_time,
+            kind: VestingKind::Linear,
+            revocable: campaign.revocable,
+            revoked: false,
+            pending_beneficiary: None,
+            claim_delegate: None,
+            staked_amount: 0,
+            campaign: Some(campaign.key()),
+            bump: ctx.bumps.vesting_account,
+        });
+
+        transfer_checked_from_pda(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.campaign_vault.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.vesting_vault.to_account_info(),
+            &campaign.to_account_info(),
+            &[
+                CAMPAIGN_SEED,
+                campaign.mint.as_ref(),
+                &campaign.campaign_id.to_le_bytes(),
+            ],
+            campaign.bump,
+            amount,
+        )?;
+        // The schedule holds the grant less any transfer fee
+        ctx.accounts.vesting_account.total_amount =
+            received_amount(&mut ctx.accounts.vesting_vault, 0)?;
+
+        let vesting_account = &ctx.accounts.vesting_account;
+        emit!(vesting_account.created_event(vesting_account.key(), Clock::get()?.unix_timestamp));
+        Ok(())
+    }
+
+    /// Creates an M-of-N signer set that creates, revokes and tops up grants through
+    /// proposals once `threshold` of `signers` approved them.
+    pub fn create_vesting_admin(
+        ctx: Context<CreateVestingAdmin>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_signer_set(&signers, threshold)?;
+        ctx.accounts.vesting_admin.set_inner(VestingAdmin {
+            version: VestingAdmin::VERSION,
+            creator: ctx.accounts.creator.key(),
+            signers,
+            threshold,
+            signer_set_seqno: 0,
+            proposal_count: 0,
+            bump: ctx.bumps.vesting_admin,
+        });
+        Ok(())
+    }
+
+    /// Proposes `action` on behalf of the signer set, counting the proposer's approval.
+    pub fn propose_admin_action(
+        ctx: Context<ProposeAdminAction>,
+        action: AdminAction,
+    ) -> Result<()> {
+        let vesting_admin = &mut ctx.accounts.vesting_admin;
+        let index = vesting_admin.signer_index(&ctx.accounts.proposer.key())?;
+        if let AdminAction::SetSigners { signers, threshold } = &action {
+            validate_signer_set(signers, *threshold)?;
+        }
+
+        let mut approvals = vec![false; vesting_admin.signers.len()];
+        approvals[index] = true;
+        ctx.accounts.proposal.set_inner(AdminProposal {
+            version: AdminProposal::VERSION,
+            vesting_admin: vesting_admin.key(),
+            proposal_id: vesting_admin.proposal_count,
+            proposer: ctx.accounts.proposer.key(),
+            signer_set_seqno: vesting_admin.signer_set_seqno,
+            action,
+            approvals,
+            executed: false,
+            bump: ctx.bumps.proposal,
+        });
+        vesting_admin.proposal_count += 1;
+        Ok(())
+    }
+
+    pub fn approve_admin_action(ctx: Context<ApproveAdminAction>) -> Result<()> {
+        let vesting_admin = &ctx.accounts.vesting_admin;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.check_open(vesting_admin)?;
+        let index = vesting_admin.signer_index(&ctx.accounts.signer.key())?;
+        require!(!proposal.approvals[index], ErrorCode::AlreadyApproved);
+        proposal.approvals[index] = true;
+        Ok(())
+    }
+
+    /// Creates the schedule of an approved `CreateVesting` proposal, funded from the
+    /// signer set's token account. Anyone can execute it and pays the rent.
+    pub fn execute_create_vesting(ctx: Context<ExecuteCreateVesting>) -> Result<()> {
+        let vesting_admin = &ctx.accounts.vesting_admin;
+        let AdminAction::CreateVesting {
+            beneficiary,
+            mint,
+            terms,
+        } = ctx.accounts.proposal.execute(vesting_admin)?
+        else {
+            return err!(ErrorCode::ActionMismatch);
+        };
+        require_keys_eq!(
+            beneficiary,
+            ctx.accounts.beneficiary.key(),
+            ErrorCode::ActionMismatch
+        );
+        require_keys_eq!(mint, ctx.accounts.mint.key(), ErrorCode::ActionMismatch);
+
+        open_schedule(
+            &mut ctx.accounts.vesting_account,
+            &mut ctx.accounts.vesting_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.admin_token_account.to_account_info(),
+            &vesting_admin.to_account_info(),
+            Some(vesting_admin),
+            beneficiary,
+            terms,
+            ctx.bumps.vesting_account,
+        )
+    }
+
+    pub fn execute_revoke_vesting(ctx: Context<ExecuteRevokeVesting>) -> Result<()> {
+        let AdminAction::RevokeVesting { vesting_account } =
+            ctx.accounts.proposal.execute(&ctx.accounts.vesting_admin)?
+        else {
+            return err!(ErrorCode::ActionMismatch);
+        };
+        require_keys_eq!(
+            vesting_account,
+            ctx.accounts.vesting_account.key(),
+            ErrorCode::ActionMismatch
+        );
+
+        revoke_schedule(
+            &mut ctx.accounts.vesting_account,
+            &ctx.accounts.vesting_vault.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.treasury_token_account.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )
+    }
+
+    pub fn execute_top_up_vesting(ctx: Context<ExecuteTopUpVesting>) -> Result<()> {
+        let vesting_admin = &ctx.accounts.vesting_admin;
+        let AdminAction::TopUpVesting {
+            vesting_account,
+            amount,
+        } = ctx.accounts.proposal.execute(vesting_admin)?
+        else {
+            return err!(ErrorCode::ActionMismatch);
+        };
+        require_keys_eq!(
+            vesting_account,
+            ctx.accounts.vesting_account.key(),
+            ErrorCode::ActionMismatch
+        );
+
+        top_up_schedule(
+            &mut ctx.accounts.vesting_account,
+            &mut ctx.accounts.vesting_vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.admin_token_account.to_account_info(),
+            &vesting_admin.to_account_info(),
+            Some(vesting_admin),
+            amount,
+        )
+    }
+
+    /// Replaces the signer set. Proposals still open under the old set can no longer be
+    /// approved or executed.
+    pub fn execute_set_signers(ctx: Context<ExecuteSetSigners>) -> Result<()> {
+        let AdminAction::SetSigners { signers, threshold } =
+            ctx.accounts.proposal.execute(&ctx.accounts.vesting_admin)?
+        else {
+            return err!(ErrorCode::ActionMismatch);
+        };
+
+        let vesting_admin = &mut ctx.accounts.vesting_admin;
+        vesting_admin.signers = signers;
+        vesting_admin.threshold = threshold;
+        vesting_admin.signer_set_seqno += 1;
+        Ok(())
+    }
+}
+
+/// Transfers `amount` out of the schedule's vault, signed by the schedule PDA.
+fn transfer_from_vault<'info>(
+    token_program: &AccountInfo<'info>,
+    vesting_vault: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    vesting_info: &AccountInfo<'info>,
+    vesting_account: &VestingAccount,
+    amount: u64,
+) -> Result<()> {
+    transfer_checked_from_pda(
+        token_program,
+        vesting_vault,
+        mint,
+        to,
+        vesting_info,
+        &[
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        vesting_account.bump,
+        amount,
+    )
+}
+
+/// Calls `f` with the signer seeds of the schedule PDA.
+fn with_vesting_signer<T>(
+    vesting_account: &VestingAccount,
+    f: impl FnOnce(&[&[&[u8]]]) -> Result<T>,
+) -> Result<T> {
+    let schedule_id = vesting_account.schedule_id.to_le_bytes();
+    let bump = [vesting_account.bump];
+    let seeds: &[&[u8]] = &[
+        vesting_account.seed_prefix(),
+        vesting_account.mint.as_ref(),
+        vesting_account.original_beneficiary.as_ref(),
+        &schedule_id,
+        &bump,
+    ];
+    f(&[seeds])
+}
+
+/// Unstakes `amount` of the schedule's stake from `staking_airdrop` into its vault.
+fn unstake_to_vault<'info>(
+    staking_program: AccountInfo<'info>,
+    accounts: UnstakeTokens<'info>,
+    vesting_account: &VestingAccount,
+    amount: u64,
+) -> Result<()> {
+    with_vesting_signer(vesting_account, |signer| {
+        staking_airdrop::cpi::unstake_tokens(
+            CpiContext::new_with_signer(staking_program, accounts, signer),
+            amount,
+        )
+    })
+}
+
+/// What `vault` gained since it held `balance_before`, which is less than what was sent
+/// when the mint withholds a transfer fee.
+fn received_amount(
+    vault: &mut InterfaceAccount<'_, TokenAccount>,
+    balance_before: u64,
+) -> Result<u64> {
+    vault.reload()?;
+    Ok(vault.amount - balance_before)
+}
+
+/// Signers must be unique and `threshold` reachable.
+fn validate_signer_set(signers: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_ADMIN_SIGNERS,
+        ErrorCode::InvalidSignerSet
+    );
+    let unique = signers
+        .iter()
+        .enumerate()
+        .all(|(i, signer)| !signers[..i].contains(signer));
+    require!(unique, ErrorCode::InvalidSignerSet);
+    require!(
+        threshold > 0 && usize::from(threshold) <= signers.len(),
+        ErrorCode::InvalidThreshold
+    );
+    Ok(())
+}
+
+/// Moves `amount` from the grant funder's token account. When the funder is a
+/// `VestingAdmin`, its PDA signs for the transfer.
+fn transfer_from_admin<'info>(
+    token_program: &AccountInfo<'info>,
+    admin_token_account: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    admin: &AccountInfo<'info>,
+    vesting_admin: Option<&VestingAdmin>,
+    amount: u64,
+) -> Result<()> {
+    match vesting_admin {
+        Some(vesting_admin) => transfer_checked_from_pda(
+            token_program,
+            admin_token_account,
+            mint,
+            to,
+            admin,
+            &[VESTING_ADMIN_SEED, vesting_admin.creator.as_ref()],
+            vesting_admin.bump,
+            amount,
+        ),
+        None => {
+            transfer_checked_from_user(token_program, admin_token_account, mint, to, admin, amount)
+        }
+    }
+}
+
+/// Funds a freshly created schedule according to `terms` and fills it in, crediting it
+/// with what reached the vault.
+#[allow(clippy::too_many_arguments)]
+fn open_schedule<'info>(
+    vesting_account: &mut Account<'info, VestingAccount>,
+    vesting_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &AccountInfo<'info>,
+    admin_token_account: &AccountInfo<'info>,
+    admin: &AccountInfo<'info>,
+    vesting_admin: Option<&VestingAdmin>,
+    beneficiary: Pubkey,
+    terms: VestingTerms,
+    bump: u8,
+) -> Result<()> {
+    let VestingTerms {
+        schedule_id,
+        start_time,
+        cliff_duration,
+        vesting_duration,
+        total_amount,
+        revocable,
+        kind,
+    } = terms;
+    require!(total_amount > 0, ErrorCode::ZeroAmount);
+    require!(vesting_duration > 0, ErrorCode::ZeroVestingPeriod);
+    require!(
+        (0..=vesting_duration).contains(&cliff_duration),
+        ErrorCode::InvalidScheduleTimes
+    );
+    let vesting_end_time = start_time
+        .checked_add(vesting_duration)
+        .ok_or(ErrorCode::InvalidScheduleTimes)?;
+    kind.validate(total_amount, vesting_end_time)?;
+
+    transfer_from_admin(
+        token_program,
+        admin_token_account,
+        mint,
+        &vesting_vault.to_account_info(),
+        admin,
+        vesting_admin,
+        total_amount,
+    )?;
+    let received = received_amount(vesting_vault, 0)?;
+    require!(received > 0, ErrorCode::ZeroAmount);
+
+    vesting_account.set_inner(VestingAccount {
+        version: VestingAccount::VERSION,
+        beneficiary,
+        original_beneficiary: beneficiary,
+        admin: admin.key(),
+        mint: mint.key(),
+        schedule_id,
+        total_amount: received,
+        claimed_amount: 0,
+        start_time,
+        cliff_time: start_time + cliff_duration,
+        vesting_end_time,
+        kind: kind.scaled(received, total_amount),
+        revocable,
+        revoked: false,
+        pending_beneficiary: None,
+        claim_delegate: None,
+        staked_amount: 0,
+        campaign: None,
+        bump,
+    });
+
+    emit!(vesting_account.created_event(vesting_account.key(), Clock::get()?.unix_timestamp));
+    Ok(())
+}
+
+/// Returns the unvested remainder of a revocable schedule to `treasury_token_account`
+/// and marks everything left as vested.
+fn revoke_schedule<'info>(
+    vesting_account: &mut Account<'info, VestingAccount>,
+    vesting_vault: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    treasury_token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let vesting_info = vesting_account.to_account_info();
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(vesting_account.revocable, ErrorCode::NotRevocable);
+    require!(!vesting_account.revoked, ErrorCode::AlreadyRevoked);
+
+    // What was already claimed stays with the beneficiary even if rounding after a
+    // split puts it a unit above the vested amount
+    let vested_amount =
+        get_vested_amount(vesting_account, current_time)?.max(vesting_account.claimed_amount);
+    let unvested_amount = vesting_account.total_amount - vested_amount;
+
+    if unvested_amount > 0 {
+        transfer_from_vault(
+            token_program,
+            vesting_vault,
+            mint,
+            treasury_token_account,
+            &vesting_info,
+            vesting_account,
+            unvested_amount,
+        )?;
+    }
+
+    // Everything left in the schedule is vested from now on
+    vesting_account.total_amount = vested_amount;
+    vesting_account.revoked = true;
+
+    emit!(VestingRevoked {
+        vesting_account: vesting_info.key(),
+        beneficiary: vesting_account.beneficiary,
+        vested_amount,
+        unvested_amount,
+        timestamp: current_time,
+    });
+    Ok(())
+}
+
+/// Adds `amount`, less any transfer fee, to the schedule's total, scaling its curve.
+#[allow(clippy::too_many_arguments)]
+fn top_up_schedule<'info>(
+    vesting_account: &mut Account<'info, VestingAccount>,
+    vesting_vault: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &AccountInfo<'info>,
+    admin_token_account: &AccountInfo<'info>,
+    admin: &AccountInfo<'info>,
+    vesting_admin: Option<&VestingAdmin>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::ZeroAmount);
+    require!(!vesting_account.revoked, ErrorCode::AlreadyRevoked);
+
+    let balance_before = vesting_vault.amount;
+    transfer_from_admin(
+        token_program,
+        admin_token_account,
+        mint,
+        &vesting_vault.to_account_info(),
+        admin,
+        vesting_admin,
+        amount,
+    )?;
+    let received = received_amount(vesting_vault, balance_before)?;
+    let total_amount = vesting_account
+        .total_amount
+        .checked_add(received)
+        .ok_or(ErrorCode::Overflow)?;
+
+    vesting_account.kind = vesting_account
+        .kind
+        .scaled(total_amount, vesting_account.total_amount);
+    vesting_account.total_amount = total_amount;
+
+    emit!(VestingToppedUp {
+        vesting_account: vesting_account.key(),
+        beneficiary: vesting_account.beneficiary,
+        amount: received,
+        total_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(schedule_id: u64)]
+pub struct InitializeVesting<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mut, token::mint = mint)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = VestingAccount::LEN,
+        seeds = [VESTING_SEED, mint.key().as_ref(), beneficiary.key().as_ref(), &schedule_id.to_le_bytes()],
+        bump,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Only stored as the schedule's beneficiary
+    pub beneficiary: AccountInfo<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokens<'info> {
+    /// The beneficiary or their claim delegate.
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = mint @ ErrorCode::InvalidMint,
+        constraint = vesting_account.can_claim(&claimer.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// The staking accounts are only needed when the claim has to unstake.
+    pub staking_program: Option<Program<'info, StakingAirdrop>>,
+    /// CHECK: Validated by `staking_airdrop`, whose vault authority must sign for it
+    #[account(mut)]
+    pub staking_vault: Option<UncheckedAccount<'info>>,
+    /// CHECK: Validated by `staking_airdrop`
+    pub staking_vault_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: Validated by `staking_airdrop` against the schedule PDA
+    #[account(mut)]
+    pub stake_state: Option<UncheckedAccount<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct StakeVesting<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [staking_airdrop::STAKING_VAULT_SEED],
+        bump,
+        seeds::program = staking_airdrop::ID,
+        token::mint = mint,
+        token::authority = staking_vault_authority,
+    )]
+    pub staking_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA signer for the staking vault
+    #[account(
+        seeds = [staking_airdrop::STAKING_AUTHORITY_SEED],
+        bump,
+        seeds::program = staking_airdrop::ID,
+    )]
+    pub staking_vault_authority: UncheckedAccount<'info>,
+    /// CHECK: `UserState` of the schedule PDA, created on first stake
+    #[account(
+        mut,
+        seeds = [vesting_account.key().as_ref()],
+        bump,
+        seeds::program = staking_airdrop::ID,
+    )]
+    pub stake_state: UncheckedAccount<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub staking_program: Program<'info, StakingAirdrop>,
+    /// The pool only holds SPL Token mints.
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ViewVesting<'info> {
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVoterRecord<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = VoterRecord::LEN,
+        seeds = [VOTER_SEED, vesting_account.key().as_ref()],
+        bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVestingAdmin<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = VestingAdmin::LEN,
+        seeds = [VESTING_ADMIN_SEED, creator.key().as_ref()],
+        bump,
+    )]
+    pub vesting_admin: Account<'info, VestingAdmin>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdminAction<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [VESTING_ADMIN_SEED, vesting_admin.creator.as_ref()],
+        bump = vesting_admin.bump,
+    )]
+    pub vesting_admin: Account<'info, VestingAdmin>,
+    #[account(
+        init,
+        payer = proposer,
+        space = AdminProposal::LEN,
+        seeds = [
+            ADMIN_PROPOSAL_SEED,
+            vesting_admin.key().as_ref(),
+            &vesting_admin.proposal_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveAdminAction<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [VESTING_ADMIN_SEED, vesting_admin.creator.as_ref()],
+        bump = vesting_admin.bump,
+    )]
+    pub vesting_admin: Account<'info, VestingAdmin>,
+    #[account(
+        mut,
+        seeds = [
+            ADMIN_PROPOSAL_SEED,
+            vesting_admin.key().as_ref(),
+            &proposal.proposal_id.to_le_bytes(),
+        ],
+        bump = proposal.bump,
+        has_one = vesting_admin @ ErrorCode::ActionMismatch,
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCreateVesting<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [VESTING_ADMIN_SEED, vesting_admin.creator.as_ref()],
+        bump = vesting_admin.bump,
+    )]
+    pub vesting_admin: Account<'info, VestingAdmin>,
+    #[account(
+        mut,
+        seeds = [
+            ADMIN_PROPOSAL_SEED,
+            vesting_admin.key().as_ref(),
+            &proposal.proposal_id.to_le_bytes(),
+        ],
+        bump = proposal.bump,
+        has_one = vesting_admin @ ErrorCode::ActionMismatch,
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+    /// Funds the grants, owned by the signer set's PDA.
+    #[account(mut, token::mint = mint, token::authority = vesting_admin)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        space = VestingAccount::LEN,
+        seeds = [
+            VESTING_SEED,
+            mint.key().as_ref(),
+            beneficiary.key().as_ref(),
+            &proposal.action.schedule_id().to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Checked against the proposal and only stored as the schedule's beneficiary
+    pub beneficiary: AccountInfo<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRevokeVesting<'info> {
+    #[account(
+        seeds = [VESTING_ADMIN_SEED, vesting_admin.creator.as_ref()],
+        bump = vesting_admin.bump,
+    )]
+    pub vesting_admin: Account<'info, VestingAdmin>,
+    #[account(
+        mut,
+        seeds = [
+            ADMIN_PROPOSAL_SEED,
+            vesting_admin.key().as_ref(),
+            &proposal.proposal_id.to_le_bytes(),
+        ],
+        bump = proposal.bump,
+        has_one = vesting_admin @ ErrorCode::ActionMismatch,
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        constraint = vesting_account.admin == vesting_admin.key() @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = treasury_token_account.owner == config.fee_treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTopUpVesting<'info> {
+    #[account(
+        seeds = [VESTING_ADMIN_SEED, vesting_admin.creator.as_ref()],
+        bump = vesting_admin.bump,
+    )]
+    pub vesting_admin: Account<'info, VestingAdmin>,
+    #[account(
+        mut,
+        seeds = [
+            ADMIN_PROPOSAL_SEED,
+            vesting_admin.key().as_ref(),
+            &proposal.proposal_id.to_le_bytes(),
+        ],
+        bump = proposal.bump,
+        has_one = vesting_admin @ ErrorCode::ActionMismatch,
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+    /// Funds the grants, owned by the signer set's PDA.
+    #[account(mut, token::mint = mint, token::authority = vesting_admin)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        constraint = vesting_account.admin == vesting_admin.key() @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSetSigners<'info> {
+    #[account(
+        mut,
+        seeds = [VESTING_ADMIN_SEED, vesting_admin.creator.as_ref()],
+        bump = vesting_admin.bump,
+    )]
+    pub vesting_admin: Account<'info, VestingAdmin>,
+    #[account(
+        mut,
+        seeds = [
+            ADMIN_PROPOSAL_SEED,
+            vesting_admin.key().as_ref(),
+            &proposal.proposal_id.to_le_bytes(),
+        ],
+        bump = proposal.bump,
+        has_one = vesting_admin @ ErrorCode::ActionMismatch,
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVesting<'info> {
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        close = receiver,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    /// Writable so transfer fees withheld in the vault can be harvested to it.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Only receives the rent of the closed accounts
+    #[account(mut)]
+    pub receiver: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpVesting<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, token::mint = mint)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, new_beneficiary: Pubkey)]
+pub struct SplitVesting<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = beneficiary,
+        space = VestingAccount::LEN,
+        seeds = [
+            vesting_account.seed_prefix(),
+            mint.key().as_ref(),
+            new_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub new_vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        init,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = new_vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub new_vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MergeVesting<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = beneficiary,
+        seeds = [
+            source_vesting_account.seed_prefix(),
+            source_vesting_account.mint.as_ref(),
+            source_vesting_account.original_beneficiary.as_ref(),
+            &source_vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = source_vesting_account.bump,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+        constraint = source_vesting_account.key() != vesting_account.key()
+            @ ErrorCode::ScheduleMismatch,
+    )]
+    pub source_vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = source_vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub source_vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    /// Writable so transfer fees withheld in the source vault can be harvested to it.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = treasury_token_account.owner == config.fee_treasury @ ErrorCode::InvalidTreasury,
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(schedule_id: u64)]
+pub struct MigrateVesting<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = config.admin == admin.key() @ ErrorCode::Unauthorized,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    /// CHECK: Owner, discriminator and beneficiary are validated against the legacy layout
+    #[account(mut)]
+    pub legacy_vesting_account: UncheckedAccount<'info>,
+    #[account(mut, token::mint = mint, token::authority = vault_authority)]
+    pub legacy_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA signer for the legacy vaults
+    #[account(seeds = [VAULT_AUTHORITY_SEED], bump)]
+    pub vault_authority: AccountInfo<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Compared against the beneficiary stored in the legacy account
+    pub beneficiary: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = VestingAccount::LEN,
+        seeds = [VESTING_SEED, mint.key().as_ref(), beneficiary.key().as_ref(), &schedule_id.to_le_bytes()],
+        bump,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeVesting<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    /// CHECK: Owner, discriminator and beneficiary are validated against the legacy layout
+    #[account(mut)]
+    pub vesting_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBeneficiary<'info> {
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptBeneficiary<'info> {
+    pub new_beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            vesting_account.seed_prefix(),
+            vesting_account.mint.as_ref(),
+            vesting_account.original_beneficiary.as_ref(),
+            &vesting_account.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_account.bump,
+        constraint = vesting_account.pending_beneficiary == Some(new_beneficiary.key())
+            @ ErrorCode::NotPendingBeneficiary,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct CreateCampaign<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mut, token::mint = mint)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = admin,
+        space = VestingCampaign::LEN,
+        seeds = [CAMPAIGN_SEED, mint.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump,
+    )]
+    pub campaign: Account<'info, VestingCampaign>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program,
+    )]
+    pub campaign_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVesting<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CAMPAIGN_SEED, campaign.mint.as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump,
+        has_one = mint @ ErrorCode::InvalidMint,
+    )]
+    pub campaign: Account<'info, VestingCampaign>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program,
+    )]
+    pub campaign_vault: InterfaceAccount<'info, TokenAccount>,
+    /// Exists once the beneficiary has claimed, so the leaf cannot be claimed again
+    /// even after the schedule itself is gone.
+    #[account(
+        init,
+        payer = beneficiary,
+        space = CampaignClaim::LEN,
+        seeds = [CAMPAIGN_CLAIM_SEED, campaign.key().as_ref(), beneficiary.key().as_ref()],
+        bump,
+    )]
+    pub campaign_claim: Account<'info, CampaignClaim>,
+    #[account(
+        init,
+        payer = beneficiary,
+        space = VestingAccount::LEN,
+        seeds = [
+            CAMPAIGN_VESTING_SEED,
+            mint.key().as_ref(),
+            beneficiary.key().as_ref(),
+            &campaign.campaign_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+    #[account(
+        init,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = vesting_account,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        seeds::program = protocol_config::ID,
+        constraint = !config.is_paused(Module::Vesting) @ ProtocolError::ModulePaused,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct VestingAccount {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    /// Beneficiary the schedule PDA was derived from, kept when it changes hands.
+    pub original_beneficiary: Pubkey,
+    /// Creator of the schedule, the only signer allowed to revoke it.
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub schedule_id: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub kind: VestingKind,
+    pub revocable: bool,
+    /// Set by `revoke_vesting`, after which `total_amount` only covers what had vested.
+    pub revoked: bool,
+    /// Wallet proposed by `transfer_beneficiary` that has not accepted yet.
+    pub pending_beneficiary: Option<Pubkey>,
+    /// Wallet allowed to claim into the beneficiary's associated token account.
+    pub claim_delegate: Option<Pubkey>,
+    /// Unclaimed tokens staked in `staking_airdrop` rather than held in the vault.
+    pub staked_amount: u64,
+    /// Campaign the schedule was claimed from. Such schedules are derived from
+    /// `CAMPAIGN_VESTING_SEED`, apart from the schedule ids admins pick for direct grants.
+    pub campaign: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl VestingAccount {
+    /// First seed of the schedule PDA, which tells campaign schedules from direct grants.
+    pub fn seed_prefix(&self) -> &'static [u8] {
+        if self.campaign.is_some() {
+            CAMPAIGN_VESTING_SEED
+        } else {
+            VESTING_SEED
+        }
+    }
+
+    pub fn can_claim(&self, claimer: &Pubkey) -> bool {
+        *claimer == self.beneficiary || self.claim_delegate == Some(*claimer)
+    }
+
+    fn created_event(&self, vesting_account: Pubkey, timestamp: i64) -> VestingCreated {
+        VestingCreated {
+            vesting_account,
+            beneficiary: self.beneficiary,
+            admin: self.admin,
+            mint: self.mint,
+            total_amount: self.total_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            timestamp,
+        }
+    }
+
+    /// Amounts at `at_timestamp`, as returned by `view_vesting`.
+    pub fn view(&self, at_timestamp: i64) -> Result<VestingView> {
+        let vested = get_vested_amount(self, at_timestamp)?;
+        let claimable = if at_timestamp < self.cliff_time {
+            0
+        } else {
+            vested.saturating_sub(self.claimed_amount)
+        };
+        let next_unlock_time = if vested >= self.total_amount {
+            None
+        } else if at_timestamp < self.cliff_time && get_vested_amount(self, self.cliff_time)? > 0 {
+            Some(self.cliff_time)
+        } else {
+            let after = at_timestamp.max(self.cliff_time);
+            let next = self
+                .kind
+                .next_increase(self.start_time, self.vesting_end_time, after);
+            Some(next.map_or(self.vesting_end_time, |next| {
+                next.min(self.vesting_end_time)
+            }))
+        };
+
+        Ok(VestingView {
+            vested,
+            claimed: self.claimed_amount,
+            claimable,
+            next_unlock_time,
+        })
+    }
+}
+
+/// Snapshot of a schedule returned by `view_vesting`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestingView {
+    pub vested: u64,
+    pub claimed: u64,
+    pub claimable: u64,
+    /// Next time more tokens vest, `None` once everything has. Continuous curves
+    /// unlock every second, so for them this is the next second.
+    pub next_unlock_time: Option<i64>,
+}
+
+impl Versioned for VestingAccount {
+    const VERSION: u8 = 8;
+    const LEN: usize = 8
+        + 1
+        + 32
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + VestingKind::MAX_LEN
+        + 1
+        + 1
+        + (1 + 32)
+        + (1 + 32)
+        + 8
+        + (1 + 32)
+        + 1;
+}
+
+/// Shape of the unlock curve between the start and the end of a schedule.
+///
+/// Every kind releases nothing before the cliff and everything from `vesting_end_time` on.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum VestingKind {
+    /// Unlocks continuously from the start to the end.
+    Linear,
+    /// Splits the time from the start to the end into `tranches` equal periods and
+    /// unlocks an equal share of the total at the end of each.
+    Periodic { tranches: u32 },
+    /// Unlocks `cliff_bps` basis points of the total at the cliff and the rest linearly
+    /// from the cliff to the end.
+    CliffPercentage { cliff_bps: u16 },
+    /// Unlocks the `cumulative_amount` of the latest milestone that has been reached.
+    Milestones(Vec<Milestone>),
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Milestone {
+    pub timestamp: i64,
+    pub cumulative_amount: u64,
+}
+
+impl VestingKind {
+    /// Size of the largest variant: tag + vector length + a full milestone table.
+    pub const MAX_LEN: usize = 1 + 4 + MAX_MILESTONES * (8 + 8);
+
+    pub fn validate(&self, total_amount: u64, vesting_end_time: i64) -> Result<()> {
+        let valid = match self {
+            VestingKind::Linear => true,
+            VestingKind::Periodic { tranches } => *tranches > 0,
+            VestingKind::CliffPercentage { cliff_bps } => u64::from(*cliff_bps) <= BASIS_POINTS,
+            VestingKind::Milestones(milestones) => {
+                let ordered = milestones.windows(2).all(|pair| {
+                    pair[0].timestamp < pair[1].timestamp
+                        && pair[0].cumulative_amount <= pair[1].cumulative_amount
+                });
+                let complete = milestones.last().is_some_and(|last| {
+                    last.cumulative_amount == total_amount && last.timestamp <= vesting_end_time
+                });
+                milestones.len() <= MAX_MILESTONES && ordered && complete
+            }
+        };
+        require!(valid, ErrorCode::InvalidVestingKind);
+        Ok(())
+    }
+
+    /// The curve of a `total_amount` schedule resized to `new_total`, with milestone
+    /// amounts rounded down. The last milestone lands exactly on `new_total`.
+    pub fn scaled(&self, new_total: u64, total_amount: u64) -> VestingKind {
+        match self {
+            VestingKind::Milestones(milestones) => VestingKind::Milestones(
+                milestones
+                    .iter()
+                    .map(|milestone| Milestone {
+                        timestamp: milestone.timestamp,
+                        cumulative_amount: proportion(
+                            milestone.cumulative_amount,
+                            new_total,
+                            total_amount,
+                        ),
+                    })
+                    .collect(),
+            ),
+            kind => kind.clone(),
+        }
+    }
+
+    /// Splits the curve of a `total_amount` schedule into the curves of the remaining
+    /// `total_amount - amount` and the split off `amount`. Split off milestone amounts
+    /// are rounded down and the remainder keeps the difference, so every milestone
+    /// still adds up to the original.
+    pub fn split(&self, amount: u64, total_amount: u64) -> (VestingKind, VestingKind) {
+        let split_off = self.scaled(amount, total_amount);
+        let remaining = match (self, &split_off) {
+            (VestingKind::Milestones(milestones), VestingKind::Milestones(split)) => {
+                VestingKind::Milestones(
+                    milestones
+                        .iter()
+                        .zip(split)
+                        .map(|(milestone, split)| Milestone {
+                            timestamp: milestone.timestamp,
+                            cumulative_amount: milestone.cumulative_amount
+                                - split.cumulative_amount,
+                        })
+                        .collect(),
+                )
+            }
+            _ => self.clone(),
+        };
+        (remaining, split_off)
+    }
+
+    /// The curve of two merged schedules, `None` unless both unlock on the same dates.
+    pub fn merged(&self, other: &VestingKind) -> Option<VestingKind> {
+        match (self, other) {
+            (VestingKind::Milestones(milestones), VestingKind::Milestones(others)) => {
+                let same_dates = milestones.len() == others.len()
+                    && milestones
+                        .iter()
+                        .zip(others)
+                        .all(|(milestone, other)| milestone.timestamp == other.timestamp);
+                same_dates.then(|| {
+                    VestingKind::Milestones(
+                        milestones
+                            .iter()
+                            .zip(others)
+                            .map(|(milestone, other)| Milestone {
+                                timestamp: milestone.timestamp,
+                                cumulative_amount: milestone.cumulative_amount
+                                    + other.cumulative_amount,
+                            })
+                            .collect(),
+                    )
+                })
+            }
+            _ => (self == other).then(|| self.clone()),
+        }
+    }
+
+    /// First time after `after` at which the curve, ignoring the cliff, rises.
+    fn next_increase(&self, start_time: i64, vesting_end_time: i64, after: i64) -> Option<i64> {
+        match self {
+            VestingKind::Linear | VestingKind::CliffPercentage { .. } => Some(after + 1),
+            VestingKind::Periodic { tranches } => {
+                let tranches = i128::from(*tranches);
+                let period = i128::from(vesting_end_time - start_time);
+                let next_tranche = i128::from(after - start_time) * tranches / period + 1;
+                // Round up to the first second at which that tranche has completed
+                let offset = (next_tranche * period + tranches - 1) / tranches;
+                Some(start_time + offset as i64)
+            }
+            VestingKind::Milestones(milestones) => milestones
+                .iter()
+                .map(|milestone| milestone.timestamp)
+                .find(|timestamp| *timestamp > after),
+        }
+    }
+}
+
+/// Layout of `VestingAccount` before the version header.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV0 {
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+}
+
+impl VestingAccountV0 {
+    pub const LEN: usize = 8 + 200;
+
+    pub fn upgrade(self, mint: Pubkey, schedule_id: u64, bump: u8) -> VestingAccountV3 {
+        VestingAccountV3 {
+            version: 3,
+            beneficiary: self.beneficiary,
+            mint,
+            schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            bump,
+        }
+    }
+}
+
+/// Version 1 layout of `VestingAccount`, before the vault authority bump was stored.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV1 {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+}
+
+impl VestingAccountV1 {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8 + 8 + 8 + 8;
+
+    pub fn upgrade(self, mint: Pubkey, schedule_id: u64, bump: u8) -> VestingAccountV3 {
+        VestingAccountV3 {
+            version: 3,
+            beneficiary: self.beneficiary,
+            mint,
+            schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            bump,
+        }
+    }
+}
+
+/// Version 2 layout of `VestingAccount`, a keypair account sharing the global vault authority.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV2 {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub vault_authority_bump: u8,
+}
+
+impl VestingAccountV2 {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn upgrade(self, mint: Pubkey, schedule_id: u64, bump: u8) -> VestingAccountV3 {
+        VestingAccountV3 {
+            version: 3,
+            beneficiary: self.beneficiary,
+            mint,
+            schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            bump,
+        }
+    }
+}
+
+/// Version 3 layout of `VestingAccount`, the first one stored at the schedule PDA.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV3 {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub schedule_id: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub bump: u8,
+}
+
+impl VestingAccountV3 {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Schedules created before revocation existed stay irrevocable.
+    pub fn upgrade(self) -> VestingAccountV4 {
+        VestingAccountV4 {
+            version: 4,
+            beneficiary: self.beneficiary,
+            admin: Pubkey::default(),
+            mint: self.mint,
+            schedule_id: self.schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            revocable: false,
+            revoked: false,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Version 4 layout of `VestingAccount`, before custom unlock curves.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV4 {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub schedule_id: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl VestingAccountV4 {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1;
+
+    pub fn upgrade(self) -> VestingAccountV5 {
+        VestingAccountV5 {
+            version: 5,
+            beneficiary: self.beneficiary,
+            admin: self.admin,
+            mint: self.mint,
+            schedule_id: self.schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            kind: VestingKind::Linear,
+            revocable: self.revocable,
+            revoked: self.revoked,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Version 5 layout of `VestingAccount`, before beneficiary transfers and claim delegates.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV5 {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub schedule_id: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub kind: VestingKind,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl VestingAccountV5 {
+    pub const LEN: usize =
+        8 + 1 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + VestingKind::MAX_LEN + 1 + 1 + 1;
+
+    pub fn upgrade(self) -> VestingAccountV6 {
+        VestingAccountV6 {
+            version: 6,
+            beneficiary: self.beneficiary,
+            original_beneficiary: self.beneficiary,
+            admin: self.admin,
+            mint: self.mint,
+            schedule_id: self.schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            kind: self.kind,
+            revocable: self.revocable,
+            revoked: self.revoked,
+            pending_beneficiary: None,
+            claim_delegate: None,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Version 6 layout of `VestingAccount`, before schedules could stake.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV6 {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    pub original_beneficiary: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub schedule_id: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub kind: VestingKind,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub pending_beneficiary: Option<Pubkey>,
+    pub claim_delegate: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl VestingAccountV6 {
+    pub const LEN: usize = 8
+        + 1
+        + 32
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + VestingKind::MAX_LEN
+        + 1
+        + 1
+        + (1 + 32)
+        + (1 + 32)
+        + 1;
+
+    pub fn upgrade(self) -> VestingAccountV7 {
+        VestingAccountV7 {
+            version: 7,
+            beneficiary: self.beneficiary,
+            original_beneficiary: self.original_beneficiary,
+            admin: self.admin,
+            mint: self.mint,
+            schedule_id: self.schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            kind: self.kind,
+            revocable: self.revocable,
+            revoked: self.revoked,
+            pending_beneficiary: self.pending_beneficiary,
+            claim_delegate: self.claim_delegate,
+            staked_amount: 0,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Version 7 layout of `VestingAccount`, before campaign schedules got their own seeds.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VestingAccountV7 {
+    pub version: u8,
+    pub beneficiary: Pubkey,
+    pub original_beneficiary: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub schedule_id: u64,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub kind: VestingKind,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub pending_beneficiary: Option<Pubkey>,
+    pub claim_delegate: Option<Pubkey>,
+    pub staked_amount: u64,
+    pub bump: u8,
+}
+
+impl VestingAccountV7 {
+    pub const LEN: usize = 8
+        + 1
+        + 32
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + VestingKind::MAX_LEN
+        + 1
+        + 1
+        + (1 + 32)
+        + (1 + 32)
+        + 8
+        + 1;
+
+    pub fn upgrade(self) -> VestingAccount {
+        VestingAccount {
+            version: VestingAccount::VERSION,
+            beneficiary: self.beneficiary,
+            original_beneficiary: self.original_beneficiary,
+            admin: self.admin,
+            mint: self.mint,
+            schedule_id: self.schedule_id,
+            total_amount: self.total_amount,
+            claimed_amount: self.claimed_amount,
+            start_time: self.start_time,
+            cliff_time: self.cliff_time,
+            vesting_end_time: self.vesting_end_time,
+            kind: self.kind,
+            revocable: self.revocable,
+            revoked: self.revoked,
+            pending_beneficiary: self.pending_beneficiary,
+            claim_delegate: self.claim_delegate,
+            staked_amount: self.staked_amount,
+            campaign: None,
+            bump: self.bump,
+        }
+    }
+}
+
+/// Grants committed to by a Merkle root of `campaign_leaf` hashes, funded up front.
+#[account]
+pub struct VestingCampaign {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub campaign_id: u64,
+    pub merkle_root: [u8; 32],
+    /// Start of every schedule claimed from the campaign.
+    pub start_time: i64,
+    pub total_amount: u64,
+    /// Sum of the grants claimed so far.
+    pub claimed_amount: u64,
+    pub revocable: bool,
+    pub bump: u8,
+}
+
+impl Versioned for VestingCampaign {
+    const VERSION: u8 = 1;
+    const LEN: usize = 8 + 1 + 32 + 32 + 8 + 32 + 8 + 8 + 8 + 1 + 1;
+}
+
+/// Marks a campaign leaf as claimed.
+#[account]
+pub struct CampaignClaim {
+    pub version: u8,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl Versioned for CampaignClaim {
+    const VERSION: u8 = 1;
+    const LEN: usize = 8 + 1 + 8 + 1;
+}
+
+/// Voting power of one schedule as of `updated_at`, written by `update_voter_record`.
+#[account]
+pub struct VoterRecord {
+    pub version: u8,
+    pub vesting_account: Pubkey,
+    /// Beneficiary of the schedule when the record was last updated.
+    pub voter: Pubkey,
+    pub mint: Pubkey,
+    pub weight: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl Versioned for VoterRecord {
+    const VERSION: u8 = 1;
+    const LEN: usize = 8 + 1 + 32 + 32 + 32 + 8 + 8 + 1;
+}
+
+/// Arguments of `initialize_vesting`, as carried by a `CreateVesting` proposal.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VestingTerms {
+    pub schedule_id: u64,
+    pub start_time: i64,
+    pub cliff_duration: i64,
+    pub vesting_duration: i64,
+    pub total_amount: u64,
+    pub revocable: bool,
+    pub kind: VestingKind,
+}
+
+impl VestingTerms {
+    pub const MAX_LEN: usize = 8 + 8 + 8 + 8 + 8 + 1 + VestingKind::MAX_LEN;
+}
+
+/// An M-of-N signer set administering grants. Schedules it creates record its PDA as
+/// their admin, so only its executed proposals can revoke or top them up.
+#[account]
+pub struct VestingAdmin {
+    pub version: u8,
+    pub creator: Pubkey,
+    pub signers: Vec<Pubkey>,
+    /// Approvals a proposal needs before it can be executed.
+    pub threshold: u8,
+    /// Bumped whenever the signer set changes, which invalidates open proposals.
+    pub signer_set_seqno: u32,
+    /// Id of the next proposal.
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+impl Versioned for VestingAdmin {
+    const VERSION: u8 = 1;
+    const LEN: usize = 8 + 1 + 32 + 4 + MAX_ADMIN_SIGNERS * 32 + 1 + 4 + 8 + 1;
+}
+
+impl VestingAdmin {
+    fn signer_index(&self, signer: &Pubkey) -> Result<usize> {
+        self.signers
+            .iter()
+            .position(|candidate| candidate == signer)
+            .ok_or_else(|| error!(ErrorCode::NotAdminSigner))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AdminAction {
+    CreateVesting {
+        beneficiary: Pubkey,
+        mint: Pubkey,
+        terms: VestingTerms,
+    },
+    RevokeVesting {
+        vesting_account: Pubkey,
+    },
+    TopUpVesting {
+        vesting_account: Pubkey,
+        amount: u64,
+    },
+    SetSigners {
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+}
+
+impl AdminAction {
+    pub const MAX_LEN: usize = 1 + {
+        let create = 32 + 32 + VestingTerms::MAX_LEN;
+        let set_signers = 4 + MAX_ADMIN_SIGNERS * 32 + 1;
+        if create > set_signers {
+            create
+        } else {
+            set_signers
+        }
+    };
+
+    /// Schedule id a `CreateVesting` action creates, zero for the other actions.
+    pub fn schedule_id(&self) -> u64 {
+        match self {
+            AdminAction::CreateVesting { terms, .. } => terms.schedule_id,
+            _ => 0,
+        }
+    }
+}
+
+/// An action awaiting approval by a `VestingAdmin`'s signers.
+#[account]
+pub struct AdminProposal {
+    pub version: u8,
+    pub vesting_admin: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    /// `signer_set_seqno` of the admin when proposed.
+    pub signer_set_seqno: u32,
+    pub action: AdminAction,
+    /// One flag per signer of the set the proposal was made under, in the same order.
+    pub approvals: Vec<bool>,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl Versioned for AdminProposal {
+    const VERSION: u8 = 1;
+    const LEN: usize =
+        8 + 1 + 32 + 8 + 32 + 4 + AdminAction::MAX_LEN + 4 + MAX_ADMIN_SIGNERS + 1 + 1;
+}
+
+impl AdminProposal {
+    fn check_open(&self, vesting_admin: &VestingAdmin) -> Result<()> {
+        require!(!self.executed, ErrorCode::ProposalExecuted);
+        require!(
+            self.signer_set_seqno == vesting_admin.signer_set_seqno,
+            ErrorCode::StaleProposal
+        );
+        Ok(())
+    }
+
+    /// Marks an open proposal with enough approvals as executed and returns its action.
+    fn execute(&mut self, vesting_admin: &VestingAdmin) -> Result<AdminAction> {
+        self.check_open(vesting_admin)?;
+        let approvals = self.approvals.iter().filter(|approved| **approved).count();
+        require!(
+            approvals >= usize::from(vesting_admin.threshold),
+            ErrorCode::NotEnoughApprovals
+        );
+        self.executed = true;
+        Ok(self.action.clone())
+    }
+}
+
+/// Leaf committing to one beneficiary's grant in a `VestingCampaign` tree.
+pub fn campaign_leaf(
+    beneficiary: &Pubkey,
+    amount: u64,
+    cliff_time: i64,
+    vesting_end_time: i64,
+) -> [u8; 32] {
+    merkle::hash_leaf(&[
+        beneficiary.as_ref(),
+        &amount.to_le_bytes(),
+        &cliff_time.to_le_bytes(),
+        &vesting_end_time.to_le_bytes(),
+    ])
+}
+
+pub fn campaign_address(mint: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CAMPAIGN_SEED, mint.as_ref(), &campaign_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+pub fn campaign_claim_address(campaign: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CAMPAIGN_CLAIM_SEED, campaign.as_ref(), beneficiary.as_ref()],
+        &ID,
+    )
+}
+
+pub fn vesting_address(mint: &Pubkey, beneficiary: &Pubkey, schedule_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VESTING_SEED,
+            mint.as_ref(),
+            beneficiary.as_ref(),
+            &schedule_id.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn campaign_vesting_address(
+    mint: &Pubkey,
+    beneficiary: &Pubkey,
+    campaign_id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CAMPAIGN_VESTING_SEED,
+            mint.as_ref(),
+            beneficiary.as_ref(),
+            &campaign_id.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn voter_record_address(vesting_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTER_SEED, vesting_account.as_ref()], &ID)
+}
+
+pub fn vesting_admin_address(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VESTING_ADMIN_SEED, creator.as_ref()], &ID)
+}
+
+pub fn admin_proposal_address(vesting_admin: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ADMIN_PROPOSAL_SEED,
+            vesting_admin.as_ref(),
+            &proposal_id.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+#[event]
+pub struct VestingCreated {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    /// Amount the schedule was credited with, after any transfer fee.
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub vesting_end_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensClaimed {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    /// Claimed over the schedule's lifetime, this claim included.
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Amount the beneficiary keeps, claimed or not.
+    pub vested_amount: u64,
+    /// Amount returned to the treasury.
+    pub unvested_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BeneficiaryChanged {
+    pub vesting_account: Pubkey,
+    pub previous_beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingClosed {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub claimed_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingToppedUp {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Amount the schedule was credited with, after any transfer fee.
+    pub amount: u64,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingSplit {
+    pub vesting_account: Pubkey,
+    pub new_vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    /// Unclaimed amount taken off the original schedule.
+    pub moved_amount: u64,
+    /// Unclaimed amount the new schedule was credited with, after any transfer fee.
+    pub received_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingMerged {
+    pub vesting_account: Pubkey,
+    /// Schedule folded into `vesting_account` and closed.
+    pub source_vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Unclaimed amount of the source schedule.
+    pub moved_amount: u64,
+    /// Part of `moved_amount` credited to `vesting_account`, after any transfer fee.
+    pub received_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingMigrated {
+    pub vesting_account: Pubkey,
+    pub legacy_vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64,
+    /// Claimed before the migration, so never reported by `TokensClaimed`.
+    pub claimed_amount: u64,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized access.")]
+    Unauthorized,
+    #[msg("Cliff period has not been reached.")]
+    CliffNotReached,
+    #[msg("No tokens available for claim.")]
+    NoTokensAvailable,
+    #[msg("Token mint does not match the vesting schedule.")]
+    InvalidMint,
+    #[msg("This vesting schedule cannot be revoked.")]
+    NotRevocable,
+    #[msg("This vesting schedule has already been revoked.")]
+    AlreadyRevoked,
+    #[msg("Treasury token account is not owned by the protocol fee treasury.")]
+    InvalidTreasury,
+    #[msg("Vesting kind parameters are invalid.")]
+    InvalidVestingKind,
+    #[msg("Vesting amount must be greater than zero.")]
+    ZeroAmount,
+    #[msg("Vesting duration must be greater than zero.")]
+    ZeroVestingPeriod,
+    #[msg("Schedule times must satisfy start <= cliff <= end.")]
+    InvalidScheduleTimes,
+    #[msg("Merkle proof does not match the campaign root.")]
+    InvalidProof,
+    #[msg("Claim exceeds the campaign's remaining funds.")]
+    CampaignExhausted,
+    #[msg("Signer is not the pending beneficiary.")]
+    NotPendingBeneficiary,
+    #[msg("A claim delegate can only pay into the beneficiary's associated token account.")]
+    InvalidDestination,
+    #[msg("Requested amount exceeds the claimable amount.")]
+    AmountExceedsClaimable,
+    #[msg("Schedule still holds unclaimed tokens.")]
+    VestingNotComplete,
+    #[msg("Arithmetic overflow.")]
+    Overflow,
+    #[msg("Split amount must be less than the schedule's total.")]
+    InvalidSplitAmount,
+    #[msg("Schedules can only be merged with identical terms.")]
+    ScheduleMismatch,
+    #[msg("Revocable schedules cannot stake.")]
+    RevocableStake,
+    #[msg("Amount exceeds the schedule's staked tokens.")]
+    InsufficientStake,
+    #[msg("Staking accounts are required to unstake for this claim.")]
+    StakingAccountsMissing,
+    #[msg("Schedule still has tokens staked.")]
+    StakeOutstanding,
+    #[msg("Admin signers must be unique, at least one and at most the maximum.")]
+    InvalidSignerSet,
+    #[msg("Threshold must be between one and the number of signers.")]
+    InvalidThreshold,
+    #[msg("Signer is not part of the vesting admin's signer set.")]
+    NotAdminSigner,
+    #[msg("Signer already approved this proposal.")]
+    AlreadyApproved,
+    #[msg("Proposal does not have enough approvals.")]
+    NotEnoughApprovals,
+    #[msg("Proposal was already executed.")]
+    ProposalExecuted,
+    #[msg("Signer set changed since the proposal was made.")]
+    StaleProposal,
+    #[msg("Accounts do not match the proposed action.")]
+    ActionMismatch,
+}
+
+pub fn get_vested_amount(vesting_account: &VestingAccount, current_time: i64) -> Result<u64> {
+    let total_amount = vesting_account.total_amount;
+    if vesting_account.revoked || current_time >= vesting_account.vesting_end_time {
+        return Ok(total_amount);
+    }
+    if current_time < vesting_account.cliff_time {
+        return Ok(0);
+    }
+
+    let time_elapsed = (current_time - vesting_account.start_time) as u128;
+    let vesting_period = (vesting_account.vesting_end_time - vesting_account.start_time) as u128;
+    let vested_amount = match &vesting_account.kind {
+        VestingKind::Linear => linear_amount(total_amount, time_elapsed, vesting_period),
+        VestingKind::Periodic { tranches } => {
+            let tranches = u128::from(*tranches);
+            let completed = time_elapsed * tranches / vesting_period;
+            (total_amount as u128 * completed / tranches) as u64
+        }
+        VestingKind::CliffPercentage { cliff_bps } => {
+            let at_cliff =
+                (total_amount as u128 * u128::from(*cliff_bps) / BASIS_POINTS as u128) as u64;
+            let since_cliff = (current_time - vesting_account.cliff_time) as u128;
+            let after_cliff =
+                (vesting_account.vesting_end_time - vesting_account.cliff_time) as u128;
+            at_cliff + linear_amount(total_amount - at_cliff, since_cliff, after_cliff)
+        }
+        VestingKind::Milestones(milestones) => milestones
+            .iter()
+            .take_while(|milestone| milestone.timestamp <= current_time)
+            .last()
+            .map_or(0, |milestone| milestone.cumulative_amount),
+    };
+    Ok(vested_amount)
+}
+
+/// Voting power of the tokens still locked in the schedule at `current_time`.
+///
+/// Each unvested token counts in proportion to the time left until the schedule ends,
+/// reaching full weight at `MAX_LOCK_DURATION` or more, so the weight decays to zero as
+/// the schedule vests. Vested tokens can be claimed at will and carry no weight, and
+/// neither do the tokens of revocable schedules, which the admin can take back.
+pub fn governance_weight(vesting_account: &VestingAccount, current_time: i64) -> Result<u64> {
+    if vesting_account.revocable {
+        return Ok(0);
+    }
+    let locked = vesting_account
+        .total_amount
+        .saturating_sub(get_vested_amount(vesting_account, current_time)?);
+    let remaining = (vesting_account.vesting_end_time - current_time).clamp(0, MAX_LOCK_DURATION);
+    Ok(proportion(
+        locked,
+        remaining as u64,
+        MAX_LOCK_DURATION as u64,
+    ))
+}
+
+/// `amount * numerator / denominator`, rounded down.
+fn proportion(amount: u64, numerator: u64, denominator: u64) -> u64 {
+    (u128::from(amount) * u128::from(numerator) / u128::from(denominator)) as u64
+}
+
+fn linear_amount(amount: u64, time_elapsed: u128, vesting_period: u128) -> u64 {
+    let vested_percentage = (time_elapsed * 1_000_000) / vesting_period;
+    ((amount as u128 * vested_percentage) / 1_000_000) as u64
+}
\ No newline at end of file
@@ -0,0 +1,506 @@
+//! Grants administered by an M-of-N `VestingAdmin` through proposals.
+
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use solana_program_test::ProgramTest;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::{anchor_processor, assert_error, instruction, protocol_config, TestContext};
+use token_vesting::{
+    accounts, admin_proposal_address, instruction as ix, vesting_address, vesting_admin_address,
+    AdminAction, AdminProposal, ErrorCode, VestingAccount, VestingAdmin, VestingKind, VestingTerms,
+};
+
+const TOTAL_AMOUNT: u64 = 1_000_000;
+const TREASURY_FUNDS: u64 = 10 * TOTAL_AMOUNT;
+const DURATION: i64 = 1_000;
+const SCHEDULE_ID: u64 = 1;
+
+struct Multisig {
+    signers: Vec<Keypair>,
+    vesting_admin: Pubkey,
+    mint: Pubkey,
+    /// Token account of the admin PDA that funds its grants.
+    funds: Pubkey,
+    beneficiary: Pubkey,
+}
+
+impl Multisig {
+    fn vesting_account(&self) -> Pubkey {
+        vesting_address(&self.mint, &self.beneficiary, SCHEDULE_ID).0
+    }
+}
+
+/// A 2-of-3 signer set holding `TREASURY_FUNDS`.
+async fn setup() -> (TestContext, Multisig) {
+    let program_test = ProgramTest::new(
+        "token_vesting",
+        token_vesting::ID,
+        anchor_processor!(token_vesting::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+
+    let mut signers = Vec::new();
+    for _ in 0..3 {
+        signers.push(ctx.funded_keypair().await);
+    }
+    let creator = ctx.payer();
+    let vesting_admin = vesting_admin_address(&creator).0;
+    let create = instruction(
+        token_vesting::ID,
+        accounts::CreateVestingAdmin {
+            creator,
+            vesting_admin,
+            system_program: system_program::ID,
+        },
+        ix::CreateVestingAdmin {
+            signers: signers.iter().map(Signer::pubkey).collect(),
+            threshold: 2,
+        },
+    );
+    ctx.process(&[create], &[]).await.unwrap();
+
+    let mint = ctx.create_mint(6).await;
+    let funds = ctx.create_token_account(&mint, &vesting_admin).await;
+    ctx.mint_to(&mint, &funds, TREASURY_FUNDS).await;
+    let beneficiary = Pubkey::new_unique();
+
+    let multisig = Multisig {
+        signers,
+        vesting_admin,
+        mint,
+        funds,
+        beneficiary,
+    };
+    (ctx, multisig)
+}
+
+fn create_action(multisig: &Multisig, start_time: i64) -> AdminAction {
+    AdminAction::CreateVesting {
+        beneficiary: multisig.beneficiary,
+        mint: multisig.mint,
+        terms: VestingTerms {
+            schedule_id: SCHEDULE_ID,
+            start_time,
+            cliff_duration: 0,
+            vesting_duration: DURATION,
+            total_amount: TOTAL_AMOUNT,
+            revocable: true,
+            kind: VestingKind::Linear,
+        },
+    }
+}
+
+fn proposal(multisig: &Multisig, proposal_id: u64) -> Pubkey {
+    admin_proposal_address(&multisig.vesting_admin, proposal_id).0
+}
+
+fn propose_ix(
+    multisig: &Multisig,
+    proposer: &Keypair,
+    proposal_id: u64,
+    action: AdminAction,
+) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ProposeAdminAction {
+            proposer: proposer.pubkey(),
+            vesting_admin: multisig.vesting_admin,
+            proposal: proposal(multisig, proposal_id),
+            system_program: system_program::ID,
+        },
+        ix::ProposeAdminAction { action },
+    )
+}
+
+fn approve_ix(multisig: &Multisig, signer: &Keypair, proposal_id: u64) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::ApproveAdminAction {
+            signer: signer.pubkey(),
+            vesting_admin: multisig.vesting_admin,
+            proposal: proposal(multisig, proposal_id),
+        },
+        ix::ApproveAdminAction {},
+    )
+}
+
+fn execute_create_ix(multisig: &Multisig, payer: Pubkey, proposal_id: u64) -> Instruction {
+    let vesting_account = multisig.vesting_account();
+    instruction(
+        token_vesting::ID,
+        accounts::ExecuteCreateVesting {
+            payer,
+            vesting_admin: multisig.vesting_admin,
+            proposal: proposal(multisig, proposal_id),
+            admin_token_account: multisig.funds,
+            mint: multisig.mint,
+            vesting_account,
+            vesting_vault: get_associated_token_address(&vesting_account, &multisig.mint),
+            beneficiary: multisig.beneficiary,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::ExecuteCreateVesting {},
+    )
+}
+
+/// Proposes `action` as `proposal_id` with the first signer and approves it with the second.
+async fn approve(
+    ctx: &mut TestContext,
+    multisig: &Multisig,
+    proposal_id: u64,
+    action: AdminAction,
+) {
+    let [first, second, _] = &multisig.signers[..] else {
+        unreachable!()
+    };
+    ctx.process(
+        &[
+            propose_ix(multisig, first, proposal_id, action),
+            approve_ix(multisig, second, proposal_id),
+        ],
+        &[first, second],
+    )
+    .await
+    .unwrap();
+}
+
+/// Creates the grant through proposal 0.
+async fn create_grant(ctx: &mut TestContext, multisig: &Multisig) {
+    let now = ctx.now().await;
+    approve(ctx, multisig, 0, create_action(multisig, now)).await;
+    let payer = ctx.payer();
+    ctx.process(&[execute_create_ix(multisig, payer, 0)], &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn create_vesting_admin_records_signer_set() {
+    let (mut ctx, multisig) = setup().await;
+
+    let admin: VestingAdmin = ctx.fetch(&multisig.vesting_admin).await;
+    assert_eq!(admin.creator, ctx.payer());
+    assert_eq!(admin.signers.len(), 3);
+    assert_eq!(admin.threshold, 2);
+    assert_eq!(admin.proposal_count, 0);
+}
+
+#[tokio::test]
+async fn invalid_signer_sets_are_rejected() {
+    let (mut ctx, _) = setup().await;
+    let creator = ctx.funded_keypair().await;
+    let signer = Pubkey::new_unique();
+
+    for (signers, threshold, error) in [
+        (vec![], 1, ErrorCode::InvalidSignerSet),
+        (vec![signer, signer], 1, ErrorCode::InvalidSignerSet),
+        (vec![signer], 0, ErrorCode::InvalidThreshold),
+        (vec![signer], 2, ErrorCode::InvalidThreshold),
+    ] {
+        let create = instruction(
+            token_vesting::ID,
+            accounts::CreateVestingAdmin {
+                creator: creator.pubkey(),
+                vesting_admin: vesting_admin_address(&creator.pubkey()).0,
+                system_program: system_program::ID,
+            },
+            ix::CreateVestingAdmin { signers, threshold },
+        );
+        let result = ctx.process(&[create], &[&creator]).await;
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn create_grant_waits_for_threshold() {
+    let (mut ctx, multisig) = setup().await;
+    let now = ctx.now().await;
+    let proposer = &multisig.signers[0];
+    ctx.process(
+        &[propose_ix(
+            &multisig,
+            proposer,
+            0,
+            create_action(&multisig, now),
+        )],
+        &[proposer],
+    )
+    .await
+    .unwrap();
+
+    let payer = ctx.payer();
+    let result = ctx
+        .process(&[execute_create_ix(&multisig, payer, 0)], &[])
+        .await;
+    assert_error(result, ErrorCode::NotEnoughApprovals);
+
+    let approver = &multisig.signers[2];
+    ctx.process(&[approve_ix(&multisig, approver, 0)], &[approver])
+        .await
+        .unwrap();
+    ctx.process(&[execute_create_ix(&multisig, payer, 0)], &[])
+        .await
+        .unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&multisig.vesting_account()).await;
+    assert_eq!(vesting.admin, multisig.vesting_admin);
+    assert_eq!(vesting.beneficiary, multisig.beneficiary);
+    assert_eq!(vesting.total_amount, TOTAL_AMOUNT);
+    assert_eq!(
+        ctx.token_balance(&multisig.funds).await,
+        TREASURY_FUNDS - TOTAL_AMOUNT
+    );
+    let proposal: AdminProposal = ctx.fetch(&proposal(&multisig, 0)).await;
+    assert!(proposal.executed);
+    assert_eq!(proposal.approvals, [true, false, true]);
+}
+
+#[tokio::test]
+async fn outsiders_cannot_propose_or_approve() {
+    let (mut ctx, multisig) = setup().await;
+    let outsider = ctx.funded_keypair().await;
+    let now = ctx.now().await;
+
+    let result = ctx
+        .process(
+            &[propose_ix(
+                &multisig,
+                &outsider,
+                0,
+                create_action(&multisig, now),
+            )],
+            &[&outsider],
+        )
+        .await;
+    assert_error(result, ErrorCode::NotAdminSigner);
+
+    let proposer = &multisig.signers[0];
+    ctx.process(
+        &[propose_ix(
+            &multisig,
+            proposer,
+            0,
+            create_action(&multisig, now),
+        )],
+        &[proposer],
+    )
+    .await
+    .unwrap();
+    let result = ctx
+        .process(&[approve_ix(&multisig, &outsider, 0)], &[&outsider])
+        .await;
+    assert_error(result, ErrorCode::NotAdminSigner);
+
+    let result = ctx
+        .process(&[approve_ix(&multisig, proposer, 0)], &[proposer])
+        .await;
+    assert_error(result, ErrorCode::AlreadyApproved);
+}
+
+#[tokio::test]
+async fn revoke_and_top_up_go_through_proposals() {
+    let (mut ctx, multisig) = setup().await;
+    create_grant(&mut ctx, &multisig).await;
+    let vesting_account = multisig.vesting_account();
+    let vesting_vault = get_associated_token_address(&vesting_account, &multisig.mint);
+
+    approve(
+        &mut ctx,
+        &multisig,
+        1,
+        AdminAction::TopUpVesting {
+            vesting_account,
+            amount: TOTAL_AMOUNT,
+        },
+    )
+    .await;
+    let top_up = instruction(
+        token_vesting::ID,
+        accounts::ExecuteTopUpVesting {
+            vesting_admin: multisig.vesting_admin,
+            proposal: proposal(&multisig, 1),
+            admin_token_account: multisig.funds,
+            vesting_account,
+            mint: multisig.mint,
+            vesting_vault,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::ExecuteTopUpVesting {},
+    );
+    ctx.process(&[top_up], &[]).await.unwrap();
+    let vesting: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert_eq!(vesting.total_amount, 2 * TOTAL_AMOUNT);
+
+    approve(
+        &mut ctx,
+        &multisig,
+        2,
+        AdminAction::RevokeVesting { vesting_account },
+    )
+    .await;
+    let payer = ctx.payer();
+    let treasury_token_account = ctx.create_token_account(&multisig.mint, &payer).await;
+    let revoke = instruction(
+        token_vesting::ID,
+        accounts::ExecuteRevokeVesting {
+            vesting_admin: multisig.vesting_admin,
+            proposal: proposal(&multisig, 2),
+            vesting_account,
+            mint: multisig.mint,
+            vesting_vault,
+            treasury_token_account,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::ExecuteRevokeVesting {},
+    );
+    ctx.process(std::slice::from_ref(&revoke), &[])
+        .await
+        .unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert!(vesting.revoked);
+    assert_eq!(
+        ctx.token_balance(&treasury_token_account).await,
+        2 * TOTAL_AMOUNT - vesting.total_amount
+    );
+
+    let result = ctx.process(&[revoke], &[]).await;
+    assert_error(result, ErrorCode::ProposalExecuted);
+}
+
+#[tokio::test]
+async fn execute_rejects_accounts_outside_the_action() {
+    let (mut ctx, multisig) = setup().await;
+    create_grant(&mut ctx, &multisig).await;
+
+    // Approved to revoke some other schedule
+    approve(
+        &mut ctx,
+        &multisig,
+        1,
+        AdminAction::RevokeVesting {
+            vesting_account: Pubkey::new_unique(),
+        },
+    )
+    .await;
+    let vesting_account = multisig.vesting_account();
+    let payer = ctx.payer();
+    let treasury_token_account = ctx.create_token_account(&multisig.mint, &payer).await;
+    let revoke = instruction(
+        token_vesting::ID,
+        accounts::ExecuteRevokeVesting {
+            vesting_admin: multisig.vesting_admin,
+            proposal: proposal(&multisig, 1),
+            vesting_account,
+            mint: multisig.mint,
+            vesting_vault: get_associated_token_address(&vesting_account, &multisig.mint),
+            treasury_token_account,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::ExecuteRevokeVesting {},
+    );
+    let result = ctx.process(&[revoke], &[]).await;
+    assert_error(result, ErrorCode::ActionMismatch);
+}
+
+#[tokio::test]
+async fn grants_of_the_multisig_cannot_be_revoked_directly() {
+    let (mut ctx, multisig) = setup().await;
+    create_grant(&mut ctx, &multisig).await;
+    let vesting_account = multisig.vesting_account();
+    let signer = &multisig.signers[0];
+    let treasury_token_account = ctx.create_token_account(&multisig.mint, &ctx.payer()).await;
+
+    let revoke = instruction(
+        token_vesting::ID,
+        accounts::RevokeVesting {
+            admin: signer.pubkey(),
+            vesting_account,
+            mint: multisig.mint,
+            vesting_vault: get_associated_token_address(&vesting_account, &multisig.mint),
+            treasury_token_account,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::RevokeVesting {},
+    );
+    let result = ctx.process(&[revoke], &[signer]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn changing_signers_invalidates_open_proposals() {
+    let (mut ctx, multisig) = setup().await;
+    let now = ctx.now().await;
+    let proposer = &multisig.signers[0];
+    ctx.process(
+        &[propose_ix(
+            &multisig,
+            proposer,
+            0,
+            create_action(&multisig, now),
+        )],
+        &[proposer],
+    )
+    .await
+    .unwrap();
+
+    let new_signer = ctx.funded_keypair().await;
+    approve(
+        &mut ctx,
+        &multisig,
+        1,
+        AdminAction::SetSigners {
+            signers: vec![proposer.pubkey(), new_signer.pubkey()],
+            threshold: 1,
+        },
+    )
+    .await;
+    let set_signers = instruction(
+        token_vesting::ID,
+        accounts::ExecuteSetSigners {
+            vesting_admin: multisig.vesting_admin,
+            proposal: proposal(&multisig, 1),
+        },
+        ix::ExecuteSetSigners {},
+    );
+    ctx.process(&[set_signers], &[]).await.unwrap();
+
+    let admin: VestingAdmin = ctx.fetch(&multisig.vesting_admin).await;
+    assert_eq!(admin.signers, [proposer.pubkey(), new_signer.pubkey()]);
+    assert_eq!(admin.threshold, 1);
+
+    let result = ctx
+        .process(&[approve_ix(&multisig, &new_signer, 0)], &[&new_signer])
+        .await;
+    assert_error(result, ErrorCode::StaleProposal);
+
+    // A proposal under the new set executes on the proposer's approval alone
+    ctx.process(
+        &[propose_ix(
+            &multisig,
+            &new_signer,
+            2,
+            create_action(&multisig, now),
+        )],
+        &[&new_signer],
+    )
+    .await
+    .unwrap();
+    let payer = ctx.payer();
+    ctx.process(&[execute_create_ix(&multisig, payer, 2)], &[])
+        .await
+        .unwrap();
+    let vesting: VestingAccount = ctx.fetch(&multisig.vesting_account()).await;
+    assert_eq!(vesting.admin, multisig.vesting_admin);
+}
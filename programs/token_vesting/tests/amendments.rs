@@ -0,0 +1,466 @@
+//! Topping up, splitting and merging existing schedules.
+
+use anchor_lang::system_program;
+use anchor_spl::associated_token;
+use anchor_spl::token::spl_token;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::vesting::{self, CLIFF, DURATION};
+use test_utils::{assert_error, instruction, protocol_config, TestContext};
+use token_vesting::{
+    accounts, get_vested_amount, instruction as ix, vesting_address, ErrorCode, Milestone,
+    VestingAccount, VestingKind, VestingMerged, VestingSplit, VestingToppedUp,
+};
+
+// Odd amounts so every proportional split has to round
+const TOTAL_AMOUNT: u64 = 1_000_003;
+
+struct Grant {
+    mint: Pubkey,
+    beneficiary: Keypair,
+    start_time: i64,
+}
+
+async fn setup() -> (TestContext, Grant) {
+    let mut ctx = vesting::start(vesting::program_test()).await;
+    let grant = Grant {
+        mint: ctx.create_mint(6).await,
+        beneficiary: ctx.funded_keypair().await,
+        start_time: ctx.now().await,
+    };
+    (ctx, grant)
+}
+
+async fn create_schedule(
+    ctx: &mut TestContext,
+    grant: &Grant,
+    schedule_id: u64,
+    start_time: i64,
+    kind: VestingKind,
+) -> Pubkey {
+    let terms = ix::InitializeVesting {
+        revocable: true,
+        kind,
+        ..vesting::terms(schedule_id, start_time, TOTAL_AMOUNT)
+    };
+    let beneficiary = grant.beneficiary.insecure_clone();
+    vesting::create_schedule(ctx, grant.mint, beneficiary, terms)
+        .await
+        .vesting_account
+}
+
+fn vault(grant: &Grant, vesting_account: &Pubkey) -> Pubkey {
+    vesting::vesting_vault(vesting_account, &grant.mint, &spl_token::ID)
+}
+
+fn milestones(start_time: i64) -> Vec<Milestone> {
+    vec![
+        Milestone {
+            timestamp: start_time + CLIFF,
+            cumulative_amount: 333_335,
+        },
+        Milestone {
+            timestamp: start_time + DURATION,
+            cumulative_amount: TOTAL_AMOUNT,
+        },
+    ]
+}
+
+/// Claims everything claimable into a fresh account of the beneficiary, returning it.
+async fn claim(
+    ctx: &mut TestContext,
+    grant: &Grant,
+    beneficiary: &Keypair,
+    vesting_account: Pubkey,
+) -> Pubkey {
+    let destination = ctx
+        .create_token_account(&grant.mint, &beneficiary.pubkey())
+        .await;
+    let claim = instruction(
+        token_vesting::ID,
+        vesting::claim_accounts(
+            beneficiary.pubkey(),
+            vesting_account,
+            grant.mint,
+            spl_token::ID,
+            destination,
+        ),
+        ix::ClaimTokens { amount: None },
+    );
+    ctx.process(&[claim], &[beneficiary]).await.unwrap();
+    destination
+}
+
+async fn top_up_ix(
+    ctx: &mut TestContext,
+    grant: &Grant,
+    admin: Pubkey,
+    vesting_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let admin_token_account = ctx.create_token_account(&grant.mint, &admin).await;
+    ctx.mint_to(&grant.mint, &admin_token_account, amount).await;
+    instruction(
+        token_vesting::ID,
+        accounts::TopUpVesting {
+            admin,
+            admin_token_account,
+            vesting_account,
+            mint: grant.mint,
+            vesting_vault: vault(grant, &vesting_account),
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::TopUpVesting { amount },
+    )
+}
+
+fn split_ix(
+    grant: &Grant,
+    signer: Pubkey,
+    vesting_account: Pubkey,
+    schedule_id: u64,
+    amount: u64,
+    new_beneficiary: Pubkey,
+) -> (Instruction, Pubkey) {
+    let (new_vesting_account, _) = vesting_address(&grant.mint, &new_beneficiary, schedule_id);
+    let split = instruction(
+        token_vesting::ID,
+        accounts::SplitVesting {
+            beneficiary: signer,
+            vesting_account,
+            mint: grant.mint,
+            vesting_vault: vault(grant, &vesting_account),
+            new_vesting_account,
+            new_vesting_vault: vault(grant, &new_vesting_account),
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::SplitVesting {
+            amount,
+            new_beneficiary,
+        },
+    );
+    (split, new_vesting_account)
+}
+
+fn merge_ix(grant: &Grant, vesting_account: Pubkey, source_vesting_account: Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::MergeVesting {
+            beneficiary: grant.beneficiary.pubkey(),
+            vesting_account,
+            vesting_vault: vault(grant, &vesting_account),
+            source_vesting_account,
+            source_vesting_vault: vault(grant, &source_vesting_account),
+            mint: grant.mint,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+        },
+        ix::MergeVesting {},
+    )
+}
+
+#[tokio::test]
+async fn top_up_keeps_the_curve() {
+    let (mut ctx, grant) = setup().await;
+    let vesting_account =
+        create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+
+    let admin = ctx.payer();
+    let top_up = top_up_ix(&mut ctx, &grant, admin, vesting_account, 499_997).await;
+    let events: Vec<VestingToppedUp> = ctx.process_events(&[top_up], &[]).await.unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].vesting_account, vesting_account);
+    assert_eq!(events[0].amount, 499_997);
+    assert_eq!(events[0].total_amount, 1_500_000);
+    let vesting: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert_eq!(vesting.total_amount, 1_500_000);
+    assert_eq!(
+        ctx.token_balance(&vault(&grant, &vesting_account)).await,
+        1_500_000
+    );
+    let halfway = grant.start_time + DURATION / 2;
+    assert_eq!(get_vested_amount(&vesting, halfway).unwrap(), 750_000);
+}
+
+#[tokio::test]
+async fn top_up_rescales_milestones() {
+    let (mut ctx, grant) = setup().await;
+    let kind = VestingKind::Milestones(milestones(grant.start_time));
+    let vesting_account = create_schedule(&mut ctx, &grant, 1, grant.start_time, kind).await;
+
+    let admin = ctx.payer();
+    let top_up = top_up_ix(&mut ctx, &grant, admin, vesting_account, 1_000).await;
+    ctx.process(&[top_up], &[]).await.unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&vesting_account).await;
+    // 333_335 * 1_001_003 / 1_000_003 = 333_668.33, rounded down
+    assert_eq!(
+        vesting.kind,
+        VestingKind::Milestones(vec![
+            Milestone {
+                timestamp: grant.start_time + CLIFF,
+                cumulative_amount: 333_668,
+            },
+            Milestone {
+                timestamp: grant.start_time + DURATION,
+                cumulative_amount: TOTAL_AMOUNT + 1_000,
+            },
+        ])
+    );
+    vesting
+        .kind
+        .validate(vesting.total_amount, vesting.vesting_end_time)
+        .unwrap();
+}
+
+#[tokio::test]
+async fn top_up_by_other_signer_fails() {
+    let (mut ctx, grant) = setup().await;
+    let vesting_account =
+        create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+
+    let top_up = top_up_ix(
+        &mut ctx,
+        &grant,
+        grant.beneficiary.pubkey(),
+        vesting_account,
+        1_000,
+    )
+    .await;
+    let result = ctx.process(&[top_up], &[&grant.beneficiary]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn split_moves_proportional_claimed_amount() {
+    let (mut ctx, grant) = setup().await;
+    let vesting_account =
+        create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+    ctx.warp_to_timestamp(grant.start_time + 337).await;
+    claim(&mut ctx, &grant, &grant.beneficiary, vesting_account).await;
+    let before: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert_eq!(before.claimed_amount, 337_001);
+
+    let new_beneficiary = ctx.funded_keypair().await;
+    let (split, new_vesting_account) = split_ix(
+        &grant,
+        grant.beneficiary.pubkey(),
+        vesting_account,
+        1,
+        333_333,
+        new_beneficiary.pubkey(),
+    );
+    let events: Vec<VestingSplit> = ctx
+        .process_events(&[split], &[&grant.beneficiary])
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].new_vesting_account, new_vesting_account);
+    assert_eq!(events[0].new_beneficiary, new_beneficiary.pubkey());
+    assert_eq!(events[0].moved_amount, 333_333 - 112_333);
+    assert_eq!(events[0].received_amount, 333_333 - 112_333);
+    let remaining: VestingAccount = ctx.fetch(&vesting_account).await;
+    let split_off: VestingAccount = ctx.fetch(&new_vesting_account).await;
+    // 337_001 * 333_333 / 1_000_003 = 112_333.22, rounded down for the new schedule
+    assert_eq!(split_off.claimed_amount, 112_333);
+    assert_eq!(remaining.claimed_amount, 337_001 - 112_333);
+    assert_eq!(split_off.total_amount, 333_333);
+    assert_eq!(remaining.total_amount, TOTAL_AMOUNT - 333_333);
+    assert_eq!(split_off.beneficiary, new_beneficiary.pubkey());
+    assert_eq!(split_off.schedule_id, 1);
+    assert_eq!(split_off.cliff_time, before.cliff_time);
+    assert_eq!(split_off.vesting_end_time, before.vesting_end_time);
+    assert_eq!(
+        ctx.token_balance(&vault(&grant, &new_vesting_account))
+            .await,
+        333_333 - 112_333
+    );
+    assert_eq!(
+        ctx.token_balance(&vault(&grant, &vesting_account)).await,
+        TOTAL_AMOUNT - 333_333 - (337_001 - 112_333)
+    );
+
+    // Both halves pay out exactly the original grant once fully vested
+    ctx.warp_to_timestamp(grant.start_time + DURATION).await;
+    let first = claim(&mut ctx, &grant, &grant.beneficiary, vesting_account).await;
+    let second = claim(&mut ctx, &grant, &new_beneficiary, new_vesting_account).await;
+    assert_eq!(
+        before.claimed_amount + ctx.token_balance(&first).await + ctx.token_balance(&second).await,
+        TOTAL_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn split_milestones_add_up_to_original() {
+    let (mut ctx, grant) = setup().await;
+    let kind = VestingKind::Milestones(milestones(grant.start_time));
+    let vesting_account = create_schedule(&mut ctx, &grant, 1, grant.start_time, kind).await;
+
+    let new_beneficiary = Pubkey::new_unique();
+    let (split, new_vesting_account) = split_ix(
+        &grant,
+        grant.beneficiary.pubkey(),
+        vesting_account,
+        1,
+        500_000,
+        new_beneficiary,
+    );
+    ctx.process(&[split], &[&grant.beneficiary]).await.unwrap();
+
+    let remaining: VestingAccount = ctx.fetch(&vesting_account).await;
+    let split_off: VestingAccount = ctx.fetch(&new_vesting_account).await;
+    // 333_335 * 500_000 / 1_000_003 = 166_666.99 is rounded down and the remainder
+    // keeps the difference
+    let amounts = |vesting: &VestingAccount| match &vesting.kind {
+        VestingKind::Milestones(milestones) => milestones
+            .iter()
+            .map(|milestone| milestone.cumulative_amount)
+            .collect::<Vec<_>>(),
+        kind => panic!("unexpected kind {kind:?}"),
+    };
+    assert_eq!(amounts(&split_off), vec![166_666, 500_000]);
+    assert_eq!(amounts(&remaining), vec![166_669, 500_003]);
+    for vesting in [&remaining, &split_off] {
+        vesting
+            .kind
+            .validate(vesting.total_amount, vesting.vesting_end_time)
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn split_whole_schedule_fails() {
+    let (mut ctx, grant) = setup().await;
+    let vesting_account =
+        create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+
+    let (split, _) = split_ix(
+        &grant,
+        grant.beneficiary.pubkey(),
+        vesting_account,
+        1,
+        TOTAL_AMOUNT,
+        Pubkey::new_unique(),
+    );
+    let result = ctx.process(&[split], &[&grant.beneficiary]).await;
+    assert_error(result, ErrorCode::InvalidSplitAmount);
+}
+
+#[tokio::test]
+async fn split_by_other_signer_fails() {
+    let (mut ctx, grant) = setup().await;
+    let vesting_account =
+        create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+    let intruder = ctx.funded_keypair().await;
+
+    let (split, _) = split_ix(
+        &grant,
+        intruder.pubkey(),
+        vesting_account,
+        1,
+        1_000,
+        intruder.pubkey(),
+    );
+    let result = ctx.process(&[split], &[&intruder]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn merge_folds_source_into_target() {
+    let (mut ctx, grant) = setup().await;
+    let target = create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+    let source = create_schedule(&mut ctx, &grant, 2, grant.start_time, VestingKind::Linear).await;
+    ctx.warp_to_timestamp(grant.start_time + 337).await;
+    claim(&mut ctx, &grant, &grant.beneficiary, source).await;
+
+    let merge = merge_ix(&grant, target, source);
+    let events: Vec<VestingMerged> = ctx
+        .process_events(&[merge], &[&grant.beneficiary])
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].source_vesting_account, source);
+    assert_eq!(events[0].moved_amount, TOTAL_AMOUNT - 337_001);
+    assert_eq!(events[0].received_amount, TOTAL_AMOUNT - 337_001);
+    let merged: VestingAccount = ctx.fetch(&target).await;
+    assert_eq!(merged.total_amount, 2 * TOTAL_AMOUNT);
+    assert_eq!(merged.claimed_amount, 337_001);
+    assert_eq!(
+        ctx.token_balance(&vault(&grant, &target)).await,
+        2 * TOTAL_AMOUNT - 337_001
+    );
+    assert!(ctx.get_account(&source).await.is_none());
+    assert!(ctx.get_account(&vault(&grant, &source)).await.is_none());
+}
+
+#[tokio::test]
+async fn merge_sums_milestones() {
+    let (mut ctx, grant) = setup().await;
+    let kind = VestingKind::Milestones(milestones(grant.start_time));
+    let target = create_schedule(&mut ctx, &grant, 1, grant.start_time, kind.clone()).await;
+    let source = create_schedule(&mut ctx, &grant, 2, grant.start_time, kind).await;
+
+    let merge = merge_ix(&grant, target, source);
+    ctx.process(&[merge], &[&grant.beneficiary]).await.unwrap();
+
+    let merged: VestingAccount = ctx.fetch(&target).await;
+    assert_eq!(
+        merged.kind,
+        VestingKind::Milestones(vec![
+            Milestone {
+                timestamp: grant.start_time + CLIFF,
+                cumulative_amount: 2 * 333_335,
+            },
+            Milestone {
+                timestamp: grant.start_time + DURATION,
+                cumulative_amount: 2 * TOTAL_AMOUNT,
+            },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn merge_with_different_terms_fails() {
+    let (mut ctx, grant) = setup().await;
+    let target = create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+    let later = create_schedule(
+        &mut ctx,
+        &grant,
+        2,
+        grant.start_time + 1,
+        VestingKind::Linear,
+    )
+    .await;
+    let periodic = create_schedule(
+        &mut ctx,
+        &grant,
+        3,
+        grant.start_time,
+        VestingKind::Periodic { tranches: 4 },
+    )
+    .await;
+
+    for source in [later, periodic] {
+        let merge = merge_ix(&grant, target, source);
+        let result = ctx.process(&[merge], &[&grant.beneficiary]).await;
+        assert_error(result, ErrorCode::ScheduleMismatch);
+    }
+}
+
+#[tokio::test]
+async fn merge_into_itself_fails() {
+    let (mut ctx, grant) = setup().await;
+    let target = create_schedule(&mut ctx, &grant, 1, grant.start_time, VestingKind::Linear).await;
+
+    let merge = merge_ix(&grant, target, target);
+    let result = ctx.process(&[merge], &[&grant.beneficiary]).await;
+    assert_error(result, ErrorCode::ScheduleMismatch);
+}
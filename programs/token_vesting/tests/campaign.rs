@@ -0,0 +1,294 @@
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::merkle::MerkleTree;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::vesting;
+use test_utils::{assert_error, instruction, protocol_config, TestContext, TxResult};
+use token_vesting::{
+    accounts, campaign_address, campaign_claim_address, campaign_leaf, campaign_vesting_address,
+    instruction as ix, ErrorCode, VestingAccount, VestingCampaign, VestingKind,
+};
+
+const CAMPAIGN_ID: u64 = 9;
+const CLIFF: i64 = 100;
+const DURATION: i64 = 1_000;
+
+struct Grant {
+    beneficiary: Keypair,
+    amount: u64,
+    cliff_time: i64,
+    vesting_end_time: i64,
+}
+
+struct Campaign {
+    mint: Pubkey,
+    campaign: Pubkey,
+    campaign_vault: Pubkey,
+    start_time: i64,
+    grants: Vec<Grant>,
+    tree: MerkleTree,
+}
+
+impl Campaign {
+    fn total_amount(&self) -> u64 {
+        self.grants.iter().map(|grant| grant.amount).sum()
+    }
+}
+
+fn leaf(grant: &Grant) -> [u8; 32] {
+    campaign_leaf(
+        &grant.beneficiary.pubkey(),
+        grant.amount,
+        grant.cliff_time,
+        grant.vesting_end_time,
+    )
+}
+
+async fn setup() -> (TestContext, Campaign) {
+    let mut ctx = vesting::start(vesting::program_test()).await;
+    let start_time = ctx.now().await;
+
+    let mut grants = Vec::new();
+    for amount in [1_000, 2_000, 3_000] {
+        grants.push(Grant {
+            beneficiary: ctx.funded_keypair().await,
+            amount,
+            cliff_time: start_time + CLIFF,
+            vesting_end_time: start_time + DURATION,
+        });
+    }
+    let tree = MerkleTree::new(grants.iter().map(leaf).collect());
+
+    let admin = ctx.payer();
+    let mint = ctx.create_mint(6).await;
+    let admin_token_account = ctx.create_token_account(&mint, &admin).await;
+    let (campaign, _) = campaign_address(&mint, CAMPAIGN_ID);
+    let campaign_vault = get_associated_token_address(&campaign, &mint);
+    let campaign = Campaign {
+        mint,
+        campaign,
+        campaign_vault,
+        start_time,
+        grants,
+        tree,
+    };
+    ctx.mint_to(&mint, &admin_token_account, campaign.total_amount())
+        .await;
+
+    let create = instruction(
+        token_vesting::ID,
+        accounts::CreateCampaign {
+            admin,
+            admin_token_account,
+            mint,
+            campaign: campaign.campaign,
+            campaign_vault,
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::CreateCampaign {
+            campaign_id: CAMPAIGN_ID,
+            merkle_root: campaign.tree.root(),
+            start_time,
+            total_amount: campaign.total_amount(),
+            revocable: true,
+        },
+    );
+    ctx.process(&[create], &[]).await.unwrap();
+    (ctx, campaign)
+}
+
+fn claim_ix(campaign: &Campaign, grant: &Grant, amount: u64, proof: Vec<[u8; 32]>) -> Instruction {
+    let beneficiary = grant.beneficiary.pubkey();
+    let (vesting_account, _) = campaign_vesting_address(&campaign.mint, &beneficiary, CAMPAIGN_ID);
+    instruction(
+        token_vesting::ID,
+        accounts::ClaimVesting {
+            beneficiary,
+            campaign: campaign.campaign,
+            mint: campaign.mint,
+            campaign_vault: campaign.campaign_vault,
+            campaign_claim: campaign_claim_address(&campaign.campaign, &beneficiary).0,
+            vesting_account,
+            vesting_vault: get_associated_token_address(&vesting_account, &campaign.mint),
+            config: protocol_config(),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::ClaimVesting {
+            amount,
+            cliff_time: grant.cliff_time,
+            vesting_end_time: grant.vesting_end_time,
+            proof,
+        },
+    )
+}
+
+async fn claim(ctx: &mut TestContext, campaign: &Campaign, index: usize) -> TxResult {
+    let grant = &campaign.grants[index];
+    let claim = claim_ix(campaign, grant, grant.amount, campaign.tree.proof(index));
+    ctx.process(&[claim], &[&grant.beneficiary]).await
+}
+
+#[tokio::test]
+async fn create_campaign_funds_vault() {
+    let (mut ctx, campaign) = setup().await;
+
+    let state: VestingCampaign = ctx.fetch(&campaign.campaign).await;
+    assert_eq!(state.merkle_root, campaign.tree.root());
+    assert_eq!(state.total_amount, campaign.total_amount());
+    assert_eq!(state.claimed_amount, 0);
+    assert_eq!(
+        ctx.token_balance(&campaign.campaign_vault).await,
+        campaign.total_amount()
+    );
+}
+
+#[tokio::test]
+async fn claim_vesting_materializes_schedule() {
+    let (mut ctx, campaign) = setup().await;
+
+    claim(&mut ctx, &campaign, 1).await.unwrap();
+
+    let grant = &campaign.grants[1];
+    let (vesting_account, _) =
+        campaign_vesting_address(&campaign.mint, &grant.beneficiary.pubkey(), CAMPAIGN_ID);
+    let vesting: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert_eq!(vesting.beneficiary, grant.beneficiary.pubkey());
+    assert_eq!(vesting.admin, ctx.payer());
+    assert_eq!(vesting.schedule_id, CAMPAIGN_ID);
+    assert_eq!(vesting.total_amount, grant.amount);
+    assert_eq!(vesting.start_time, campaign.start_time);
+    assert_eq!(vesting.cliff_time, grant.cliff_time);
+    assert_eq!(vesting.vesting_end_time, grant.vesting_end_time);
+    assert_eq!(vesting.kind, VestingKind::Linear);
+    assert!(vesting.revocable);
+    assert_eq!(vesting.campaign, Some(campaign.campaign));
+
+    let vault = get_associated_token_address(&vesting_account, &campaign.mint);
+    assert_eq!(ctx.token_balance(&vault).await, grant.amount);
+    assert_eq!(
+        ctx.token_balance(&campaign.campaign_vault).await,
+        campaign.total_amount() - grant.amount
+    );
+    let state: VestingCampaign = ctx.fetch(&campaign.campaign).await;
+    assert_eq!(state.claimed_amount, grant.amount);
+}
+
+#[tokio::test]
+async fn every_grant_can_be_claimed() {
+    let (mut ctx, campaign) = setup().await;
+
+    for index in 0..campaign.grants.len() {
+        claim(&mut ctx, &campaign, index).await.unwrap();
+    }
+    assert_eq!(ctx.token_balance(&campaign.campaign_vault).await, 0);
+}
+
+#[tokio::test]
+async fn claim_vesting_twice_fails() {
+    let (mut ctx, campaign) = setup().await;
+    claim(&mut ctx, &campaign, 0).await.unwrap();
+
+    let result = claim(&mut ctx, &campaign, 0).await;
+    assert!(result.is_err());
+    assert_eq!(
+        ctx.token_balance(&campaign.campaign_vault).await,
+        campaign.total_amount() - campaign.grants[0].amount
+    );
+}
+
+#[tokio::test]
+async fn claim_vesting_with_inflated_amount_fails() {
+    let (mut ctx, campaign) = setup().await;
+    let grant = &campaign.grants[0];
+
+    let claim = claim_ix(&campaign, grant, grant.amount + 1, campaign.tree.proof(0));
+    let result = ctx.process(&[claim], &[&grant.beneficiary]).await;
+    assert_error(result, ErrorCode::InvalidProof);
+}
+
+#[tokio::test]
+async fn claim_vesting_with_another_proof_fails() {
+    let (mut ctx, campaign) = setup().await;
+    let grant = &campaign.grants[0];
+
+    let claim = claim_ix(&campaign, grant, grant.amount, campaign.tree.proof(1));
+    let result = ctx.process(&[claim], &[&grant.beneficiary]).await;
+    assert_error(result, ErrorCode::InvalidProof);
+}
+
+#[tokio::test]
+async fn claim_vesting_for_unlisted_beneficiary_fails() {
+    let (mut ctx, campaign) = setup().await;
+    let outsider = Grant {
+        beneficiary: ctx.funded_keypair().await,
+        ..campaign.grants[0]
+    };
+
+    let claim = claim_ix(
+        &campaign,
+        &outsider,
+        outsider.amount,
+        campaign.tree.proof(0),
+    );
+    let result = ctx.process(&[claim], &[&outsider.beneficiary]).await;
+    assert_error(result, ErrorCode::InvalidProof);
+}
+
+#[tokio::test]
+async fn direct_grant_with_campaign_id_does_not_block_claim() {
+    let (mut ctx, campaign) = setup().await;
+    let grant = &campaign.grants[0];
+    let now = ctx.now().await;
+    let beneficiary = grant.beneficiary.insecure_clone();
+    let direct = vesting::create_schedule(
+        &mut ctx,
+        campaign.mint,
+        beneficiary,
+        vesting::terms(CAMPAIGN_ID, now, 5_000),
+    )
+    .await;
+
+    claim(&mut ctx, &campaign, 0).await.unwrap();
+
+    let (claimed, _) =
+        campaign_vesting_address(&campaign.mint, &grant.beneficiary.pubkey(), CAMPAIGN_ID);
+    assert_ne!(claimed, direct.vesting_account);
+    let vesting: VestingAccount = ctx.fetch(&claimed).await;
+    assert_eq!(vesting.total_amount, grant.amount);
+    let vesting: VestingAccount = ctx.fetch(&direct.vesting_account).await;
+    assert_eq!(vesting.total_amount, 5_000);
+    assert_eq!(vesting.campaign, None);
+}
+
+#[tokio::test]
+async fn claimed_schedule_pays_out() {
+    let (mut ctx, campaign) = setup().await;
+    let grant = &campaign.grants[1];
+    claim(&mut ctx, &campaign, 1).await.unwrap();
+    ctx.warp_to_timestamp(grant.vesting_end_time).await;
+
+    let beneficiary = grant.beneficiary.pubkey();
+    let (vesting_account, _) = campaign_vesting_address(&campaign.mint, &beneficiary, CAMPAIGN_ID);
+    let destination = ctx.create_token_account(&campaign.mint, &beneficiary).await;
+    let claim = instruction(
+        token_vesting::ID,
+        vesting::claim_accounts(
+            beneficiary,
+            vesting_account,
+            campaign.mint,
+            spl_token::ID,
+            destination,
+        ),
+        ix::ClaimTokens { amount: None },
+    );
+    ctx.process(&[claim], &[&grant.beneficiary]).await.unwrap();
+    assert_eq!(ctx.token_balance(&destination).await, grant.amount);
+}
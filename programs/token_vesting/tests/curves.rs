@@ -0,0 +1,294 @@
+//! Boundaries of each `VestingKind` unlock curve.
+
+use anchor_lang::prelude::Pubkey;
+use token_vesting::{
+    get_vested_amount, governance_weight, ErrorCode, Milestone, VestingAccount, VestingKind,
+    VestingView, MAX_LOCK_DURATION,
+};
+
+const TOTAL: u64 = 1_000;
+const START: i64 = 0;
+const CLIFF: i64 = 1_000;
+const END: i64 = 2_000;
+
+fn schedule(kind: VestingKind) -> VestingAccount {
+    VestingAccount {
+        version: 6,
+        beneficiary: Pubkey::default(),
+        original_beneficiary: Pubkey::default(),
+        admin: Pubkey::default(),
+        mint: Pubkey::default(),
+        schedule_id: 0,
+        total_amount: TOTAL,
+        claimed_amount: 0,
+        start_time: START,
+        cliff_time: CLIFF,
+        vesting_end_time: END,
+        kind,
+        revocable: false,
+        revoked: false,
+        pending_beneficiary: None,
+        claim_delegate: None,
+        staked_amount: 0,
+        campaign: None,
+        bump: 0,
+    }
+}
+
+fn vested(vesting: &VestingAccount, at: i64) -> u64 {
+    get_vested_amount(vesting, at).unwrap()
+}
+
+fn milestones() -> Vec<Milestone> {
+    vec![
+        Milestone {
+            timestamp: 1_200,
+            cumulative_amount: 100,
+        },
+        Milestone {
+            timestamp: 1_500,
+            cumulative_amount: 600,
+        },
+        Milestone {
+            timestamp: END,
+            cumulative_amount: TOTAL,
+        },
+    ]
+}
+
+#[test]
+fn linear_unlocks_time_since_start_after_cliff() {
+    let vesting = schedule(VestingKind::Linear);
+    assert_eq!(vested(&vesting, START), 0);
+    assert_eq!(vested(&vesting, CLIFF - 1), 0);
+    assert_eq!(vested(&vesting, CLIFF), 500);
+    assert_eq!(vested(&vesting, 1_500), 750);
+    assert_eq!(vested(&vesting, END - 1), 999);
+    assert_eq!(vested(&vesting, END), TOTAL);
+}
+
+#[test]
+fn periodic_unlocks_whole_tranches() {
+    let vesting = schedule(VestingKind::Periodic { tranches: 4 });
+    // The first tranche completes at 500 but is held back by the cliff
+    assert_eq!(vested(&vesting, CLIFF - 1), 0);
+    assert_eq!(vested(&vesting, CLIFF), 500);
+    assert_eq!(vested(&vesting, 1_499), 500);
+    assert_eq!(vested(&vesting, 1_500), 750);
+    assert_eq!(vested(&vesting, END - 1), 750);
+    assert_eq!(vested(&vesting, END), TOTAL);
+}
+
+#[test]
+fn periodic_rounds_tranches_down() {
+    let vesting = schedule(VestingKind::Periodic { tranches: 3 });
+    assert_eq!(vested(&vesting, 1_333), 333);
+    assert_eq!(vested(&vesting, 1_334), 666);
+    assert_eq!(vested(&vesting, END), TOTAL);
+}
+
+#[test]
+fn cliff_percentage_unlocks_share_at_cliff() {
+    let vesting = schedule(VestingKind::CliffPercentage { cliff_bps: 2_500 });
+    assert_eq!(vested(&vesting, CLIFF - 1), 0);
+    assert_eq!(vested(&vesting, CLIFF), 250);
+    assert_eq!(vested(&vesting, 1_500), 250 + 375);
+    assert_eq!(vested(&vesting, END), TOTAL);
+}
+
+#[test]
+fn cliff_percentage_of_everything_unlocks_all_at_cliff() {
+    let vesting = schedule(VestingKind::CliffPercentage { cliff_bps: 10_000 });
+    assert_eq!(vested(&vesting, CLIFF - 1), 0);
+    assert_eq!(vested(&vesting, CLIFF), TOTAL);
+}
+
+#[test]
+fn milestones_unlock_latest_reached_amount() {
+    let vesting = schedule(VestingKind::Milestones(milestones()));
+    assert_eq!(vested(&vesting, CLIFF), 0);
+    assert_eq!(vested(&vesting, 1_199), 0);
+    assert_eq!(vested(&vesting, 1_200), 100);
+    assert_eq!(vested(&vesting, 1_499), 100);
+    assert_eq!(vested(&vesting, 1_500), 600);
+    assert_eq!(vested(&vesting, END - 1), 600);
+    assert_eq!(vested(&vesting, END), TOTAL);
+}
+
+#[test]
+fn milestones_before_cliff_wait_for_cliff() {
+    let mut table = milestones();
+    table[0].timestamp = CLIFF - 100;
+    let vesting = schedule(VestingKind::Milestones(table));
+    assert_eq!(vested(&vesting, CLIFF - 1), 0);
+    assert_eq!(vested(&vesting, CLIFF), 100);
+}
+
+#[test]
+fn revoked_schedule_is_fully_vested() {
+    let mut vesting = schedule(VestingKind::Periodic { tranches: 4 });
+    vesting.total_amount = 250;
+    vesting.revoked = true;
+    assert_eq!(vested(&vesting, CLIFF - 1), 250);
+}
+
+fn next_unlock(vesting: &VestingAccount, at: i64) -> Option<i64> {
+    vesting.view(at).unwrap().next_unlock_time
+}
+
+#[test]
+fn view_reports_nothing_claimable_before_cliff() {
+    let vesting = schedule(VestingKind::Linear);
+    assert_eq!(
+        vesting.view(START).unwrap(),
+        VestingView {
+            vested: 0,
+            claimed: 0,
+            claimable: 0,
+            next_unlock_time: Some(CLIFF),
+        }
+    );
+}
+
+#[test]
+fn view_subtracts_claimed_amount() {
+    let mut vesting = schedule(VestingKind::Linear);
+    vesting.claimed_amount = 600;
+    assert_eq!(
+        vesting.view(1_800).unwrap(),
+        VestingView {
+            vested: 900,
+            claimed: 600,
+            claimable: 300,
+            next_unlock_time: Some(1_801),
+        }
+    );
+}
+
+#[test]
+fn view_has_no_next_unlock_once_fully_vested() {
+    let vesting = schedule(VestingKind::Linear);
+    assert_eq!(next_unlock(&vesting, END - 1), Some(END));
+    assert_eq!(next_unlock(&vesting, END), None);
+
+    let mut revoked = schedule(VestingKind::Linear);
+    revoked.revoked = true;
+    assert_eq!(next_unlock(&revoked, START), None);
+}
+
+#[test]
+fn view_finds_next_tranche() {
+    let vesting = schedule(VestingKind::Periodic { tranches: 3 });
+    assert_eq!(next_unlock(&vesting, CLIFF), Some(1_334));
+    assert_eq!(next_unlock(&vesting, 1_333), Some(1_334));
+    assert_eq!(next_unlock(&vesting, 1_334), Some(END));
+    // Each reported time is the first at which the vested amount grows
+    assert!(vested(&vesting, 1_333) < vested(&vesting, 1_334));
+}
+
+#[test]
+fn view_finds_next_milestone() {
+    let vesting = schedule(VestingKind::Milestones(milestones()));
+    // Nothing vests at the cliff, so the first milestone is next
+    assert_eq!(next_unlock(&vesting, START), Some(1_200));
+    assert_eq!(next_unlock(&vesting, 1_200), Some(1_500));
+    assert_eq!(next_unlock(&vesting, 1_700), Some(END));
+}
+
+/// A linear schedule locked for twice the longest weighted lock, without a cliff.
+fn long_schedule() -> VestingAccount {
+    let mut vesting = schedule(VestingKind::Linear);
+    vesting.cliff_time = START;
+    vesting.vesting_end_time = START + 2 * MAX_LOCK_DURATION;
+    vesting
+}
+
+#[test]
+fn governance_weight_is_capped_at_max_lock() {
+    let vesting = long_schedule();
+    // Everything is locked and the lock is past the cap
+    assert_eq!(governance_weight(&vesting, START).unwrap(), TOTAL);
+    // Half is locked for exactly the cap
+    assert_eq!(
+        governance_weight(&vesting, START + MAX_LOCK_DURATION).unwrap(),
+        TOTAL / 2
+    );
+}
+
+#[test]
+fn governance_weight_decays_with_remaining_lock() {
+    let vesting = long_schedule();
+    // A quarter is locked for half the cap
+    let at = START + 3 * MAX_LOCK_DURATION / 2;
+    assert_eq!(governance_weight(&vesting, at).unwrap(), TOTAL / 8);
+    assert_eq!(
+        governance_weight(&vesting, vesting.vesting_end_time).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn governance_weight_ignores_claimed_and_revoked_tokens() {
+    let mut vesting = long_schedule();
+    // Vested tokens weigh nothing whether claimed or not
+    vesting.claimed_amount = TOTAL / 2;
+    assert_eq!(
+        governance_weight(&vesting, START + MAX_LOCK_DURATION).unwrap(),
+        TOTAL / 2
+    );
+
+    vesting.revoked = true;
+    assert_eq!(governance_weight(&vesting, START).unwrap(), 0);
+}
+
+#[test]
+fn governance_weight_ignores_revocable_schedules() {
+    let mut vesting = long_schedule();
+    vesting.revocable = true;
+    assert_eq!(governance_weight(&vesting, START).unwrap(), 0);
+    assert_eq!(
+        governance_weight(&vesting, START + MAX_LOCK_DURATION).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn validate_accepts_well_formed_kinds() {
+    VestingKind::Linear.validate(TOTAL, END).unwrap();
+    VestingKind::Periodic { tranches: 1 }
+        .validate(TOTAL, END)
+        .unwrap();
+    VestingKind::CliffPercentage { cliff_bps: 10_000 }
+        .validate(TOTAL, END)
+        .unwrap();
+    VestingKind::Milestones(milestones())
+        .validate(TOTAL, END)
+        .unwrap();
+}
+
+#[test]
+fn validate_rejects_malformed_kinds() {
+    let mut unordered = milestones();
+    unordered.swap(0, 1);
+    let mut decreasing = milestones();
+    decreasing[1].cumulative_amount = 50;
+    let mut short = milestones();
+    short.pop();
+    let mut late = milestones();
+    late[2].timestamp = END + 1;
+
+    for kind in [
+        VestingKind::Periodic { tranches: 0 },
+        VestingKind::CliffPercentage { cliff_bps: 10_001 },
+        VestingKind::Milestones(vec![]),
+        VestingKind::Milestones(unordered),
+        VestingKind::Milestones(decreasing),
+        VestingKind::Milestones(short),
+        VestingKind::Milestones(late),
+    ] {
+        assert_eq!(
+            kind.validate(TOTAL, END).unwrap_err(),
+            ErrorCode::InvalidVestingKind.into()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Lifecycle events emitted by schedules.
+
+use solana_sdk::signature::Signer;
+use test_utils::vesting::{self, Schedule, CLIFF, DURATION};
+use test_utils::{instruction, protocol_config, TestContext};
+use token_vesting::{
+    accounts, instruction as ix, BeneficiaryChanged, TokensClaimed, VestingClosed, VestingCreated,
+    VestingRevoked,
+};
+
+const TOTAL_AMOUNT: u64 = 1_000_000;
+
+async fn setup() -> (TestContext, Schedule, VestingCreated) {
+    let mut ctx = vesting::start(vesting::program_test()).await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let now = ctx.now().await;
+    let terms = ix::InitializeVesting {
+        revocable: true,
+        ..vesting::terms(1, now, TOTAL_AMOUNT)
+    };
+    let (init, schedule) = vesting::initialize_ix(&mut ctx, mint, beneficiary, terms).await;
+    let mut events = ctx.process_events(&[init], &[]).await.unwrap();
+    assert_eq!(events.len(), 1);
+    (ctx, schedule, events.remove(0))
+}
+
+#[tokio::test]
+async fn initialize_emits_vesting_created() {
+    let (_, schedule, created) = setup().await;
+
+    assert_eq!(created.vesting_account, schedule.vesting_account);
+    assert_eq!(created.beneficiary, schedule.beneficiary.pubkey());
+    assert_eq!(created.mint, schedule.mint);
+    assert_eq!(created.total_amount, TOTAL_AMOUNT);
+    assert_eq!(created.cliff_time, schedule.start_time + CLIFF);
+    assert_eq!(created.vesting_end_time, schedule.start_time + DURATION);
+    assert_eq!(created.timestamp, schedule.start_time);
+}
+
+#[tokio::test]
+async fn claims_emit_cumulative_totals() {
+    let (mut ctx, schedule, _) = setup().await;
+    ctx.warp_forward(DURATION).await;
+
+    let events: Vec<TokensClaimed> = ctx
+        .process_events(
+            &[
+                schedule.claim_ix(schedule.beneficiary.pubkey(), Some(300_000)),
+                schedule.claim_ix(schedule.beneficiary.pubkey(), Some(200_000)),
+            ],
+            &[&schedule.beneficiary],
+        )
+        .await
+        .unwrap();
+
+    let amounts: Vec<_> = events
+        .iter()
+        .map(|event| (event.amount, event.claimed_amount))
+        .collect();
+    assert_eq!(amounts, [(300_000, 300_000), (200_000, 500_000)]);
+    assert_eq!(events[0].destination, schedule.beneficiary_token_account);
+    assert_eq!(events[0].total_amount, TOTAL_AMOUNT);
+    assert_eq!(events[0].timestamp, ctx.now().await);
+}
+
+#[tokio::test]
+async fn revoke_emits_timestamped_split() {
+    let (mut ctx, schedule, _) = setup().await;
+    let midpoint = schedule.start_time + DURATION / 2;
+    ctx.warp_to_timestamp(midpoint).await;
+    let admin = ctx.payer();
+    let treasury_token_account = ctx.create_token_account(&schedule.mint, &admin).await;
+
+    let revoke = instruction(
+        token_vesting::ID,
+        accounts::RevokeVesting {
+            admin,
+            vesting_account: schedule.vesting_account,
+            mint: schedule.mint,
+            vesting_vault: schedule.vesting_vault,
+            treasury_token_account,
+            config: protocol_config(),
+            token_program: schedule.token_program,
+        },
+        ix::RevokeVesting {},
+    );
+    let events: Vec<VestingRevoked> = ctx.process_events(&[revoke], &[]).await.unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].vested_amount, TOTAL_AMOUNT / 2);
+    assert_eq!(events[0].unvested_amount, TOTAL_AMOUNT / 2);
+    assert_eq!(events[0].timestamp, midpoint);
+}
+
+#[tokio::test]
+async fn accept_beneficiary_emits_both_parties() {
+    let (mut ctx, schedule, _) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    ctx.process(
+        &[schedule.claim_ix(schedule.beneficiary.pubkey(), Some(100_000))],
+        &[&schedule.beneficiary],
+    )
+    .await
+    .unwrap();
+
+    let new_beneficiary = ctx.funded_keypair().await;
+    let transfer = instruction(
+        token_vesting::ID,
+        accounts::UpdateBeneficiary {
+            beneficiary: schedule.beneficiary.pubkey(),
+            vesting_account: schedule.vesting_account,
+        },
+        ix::TransferBeneficiary {
+            new_beneficiary: new_beneficiary.pubkey(),
+        },
+    );
+    let accept = instruction(
+        token_vesting::ID,
+        accounts::AcceptBeneficiary {
+            new_beneficiary: new_beneficiary.pubkey(),
+            vesting_account: schedule.vesting_account,
+        },
+        ix::AcceptBeneficiary {},
+    );
+    let events: Vec<BeneficiaryChanged> = ctx
+        .process_events(
+            &[transfer, accept],
+            &[&schedule.beneficiary, &new_beneficiary],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].previous_beneficiary,
+        schedule.beneficiary.pubkey()
+    );
+    assert_eq!(events[0].new_beneficiary, new_beneficiary.pubkey());
+    assert_eq!(events[0].total_amount, TOTAL_AMOUNT);
+    assert_eq!(events[0].claimed_amount, 100_000);
+}
+
+#[tokio::test]
+async fn close_emits_vesting_closed() {
+    let (mut ctx, schedule, _) = setup().await;
+    ctx.warp_forward(DURATION).await;
+
+    let close = instruction(
+        token_vesting::ID,
+        accounts::CloseVesting {
+            beneficiary: schedule.beneficiary.pubkey(),
+            vesting_account: schedule.vesting_account,
+            mint: schedule.mint,
+            vesting_vault: schedule.vesting_vault,
+            receiver: schedule.beneficiary.pubkey(),
+            token_program: schedule.token_program,
+        },
+        ix::CloseVesting {},
+    );
+    let events: Vec<VestingClosed> = ctx
+        .process_events(
+            &[
+                schedule.claim_ix(schedule.beneficiary.pubkey(), None),
+                close,
+            ],
+            &[&schedule.beneficiary],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].vesting_account, schedule.vesting_account);
+    assert_eq!(events[0].beneficiary, schedule.beneficiary.pubkey());
+    assert_eq!(events[0].claimed_amount, TOTAL_AMOUNT);
+}
@@ -0,0 +1,152 @@
+//! Governance weight recorded in `VoterRecord`s.
+
+use anchor_lang::system_program;
+use anchor_lang::AnchorDeserialize;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use test_utils::vesting::{self, Schedule};
+use test_utils::{instruction, TestContext};
+use token_vesting::{
+    accounts, instruction as ix, voter_record_address, VoterRecord, MAX_LOCK_DURATION,
+};
+
+const TOTAL_AMOUNT: u64 = 1_000_000;
+const SCHEDULE_ID: u64 = 1;
+
+/// A linear schedule without a cliff, locked for twice the longest weighted lock.
+async fn setup(revocable: bool) -> (TestContext, Schedule) {
+    let mut ctx = vesting::start(vesting::program_test()).await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let now = ctx.now().await;
+    let terms = ix::InitializeVesting {
+        cliff_duration: 0,
+        vesting_duration: 2 * MAX_LOCK_DURATION,
+        revocable,
+        ..vesting::terms(SCHEDULE_ID, now, TOTAL_AMOUNT)
+    };
+    let schedule = vesting::create_schedule(&mut ctx, mint, beneficiary, terms).await;
+    (ctx, schedule)
+}
+
+fn update_ix(schedule: &Schedule, payer: Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::UpdateVoterRecord {
+            payer,
+            vesting_account: schedule.vesting_account,
+            voter_record: voter_record_address(&schedule.vesting_account).0,
+            system_program: system_program::ID,
+        },
+        ix::UpdateVoterRecord {},
+    )
+}
+
+async fn voter_record(ctx: &mut TestContext, schedule: &Schedule) -> VoterRecord {
+    ctx.fetch(&voter_record_address(&schedule.vesting_account).0)
+        .await
+}
+
+#[tokio::test]
+async fn update_voter_record_records_full_weight_of_long_lock() {
+    let (mut ctx, schedule) = setup(false).await;
+    let payer = ctx.payer();
+
+    ctx.process(&[update_ix(&schedule, payer)], &[])
+        .await
+        .unwrap();
+
+    let record = voter_record(&mut ctx, &schedule).await;
+    assert_eq!(record.vesting_account, schedule.vesting_account);
+    assert_eq!(record.voter, schedule.beneficiary.pubkey());
+    assert_eq!(record.weight, TOTAL_AMOUNT);
+    assert_eq!(record.updated_at, schedule.start_time);
+}
+
+#[tokio::test]
+async fn revocable_schedule_records_no_weight() {
+    let (mut ctx, schedule) = setup(true).await;
+    let payer = ctx.payer();
+
+    ctx.process(&[update_ix(&schedule, payer)], &[])
+        .await
+        .unwrap();
+
+    let record = voter_record(&mut ctx, &schedule).await;
+    assert_eq!(record.voter, schedule.beneficiary.pubkey());
+    assert_eq!(record.weight, 0);
+}
+
+#[tokio::test]
+async fn update_voter_record_returns_weight() {
+    let (mut ctx, schedule) = setup(false).await;
+    let payer = ctx.payer();
+
+    let data = ctx
+        .simulate_return_data(&[update_ix(&schedule, payer)])
+        .await
+        .unwrap();
+    assert_eq!(u64::try_from_slice(&data).unwrap(), TOTAL_AMOUNT);
+}
+
+#[tokio::test]
+async fn voter_weight_decays_as_schedule_vests() {
+    let (mut ctx, schedule) = setup(false).await;
+    let payer = ctx.payer();
+    ctx.process(&[update_ix(&schedule, payer)], &[])
+        .await
+        .unwrap();
+
+    // A quarter is still locked, for half the longest weighted lock
+    let at = schedule.start_time + 3 * MAX_LOCK_DURATION / 2;
+    ctx.warp_to_timestamp(at).await;
+    // Anyone can refresh the record
+    let keeper = ctx.funded_keypair().await;
+    ctx.process(&[update_ix(&schedule, keeper.pubkey())], &[&keeper])
+        .await
+        .unwrap();
+
+    let record = voter_record(&mut ctx, &schedule).await;
+    assert_eq!(record.weight, TOTAL_AMOUNT / 8);
+    assert_eq!(record.updated_at, at);
+}
+
+#[tokio::test]
+async fn voter_record_follows_beneficiary_transfer() {
+    let (mut ctx, schedule) = setup(false).await;
+    let payer = ctx.payer();
+    ctx.process(&[update_ix(&schedule, payer)], &[])
+        .await
+        .unwrap();
+
+    let new_beneficiary = ctx.funded_keypair().await;
+    let transfer = instruction(
+        token_vesting::ID,
+        accounts::UpdateBeneficiary {
+            beneficiary: schedule.beneficiary.pubkey(),
+            vesting_account: schedule.vesting_account,
+        },
+        ix::TransferBeneficiary {
+            new_beneficiary: new_beneficiary.pubkey(),
+        },
+    );
+    let accept = instruction(
+        token_vesting::ID,
+        accounts::AcceptBeneficiary {
+            new_beneficiary: new_beneficiary.pubkey(),
+            vesting_account: schedule.vesting_account,
+        },
+        ix::AcceptBeneficiary {},
+    );
+    ctx.process(
+        &[transfer, accept, update_ix(&schedule, payer)],
+        &[&schedule.beneficiary, &new_beneficiary],
+    )
+    .await
+    .unwrap();
+
+    let record = voter_record(&mut ctx, &schedule).await;
+    assert_eq!(record.voter, new_beneficiary.pubkey());
+    assert_eq!(record.weight, TOTAL_AMOUNT);
+}
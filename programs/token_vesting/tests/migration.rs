@@ -0,0 +1,531 @@
+use anchor_lang::{AnchorSerialize, Discriminator};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::versioning::Versioned;
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{
+    anchor_processor, assert_error, instruction, protocol_config, TestContext, TxResult,
+};
+use token_vesting::{
+    accounts, instruction as ix, vesting_address, ErrorCode, VestingAccount, VestingAccountV0,
+    VestingAccountV1, VestingAccountV2, VestingAccountV3, VestingAccountV4, VestingAccountV5,
+    VestingAccountV6, VestingAccountV7, VestingKind, VestingMigrated, VAULT_AUTHORITY_SEED,
+};
+
+const SCHEDULE_ID: u64 = 3;
+/// Balance of the shared legacy vault, more than any single schedule is owed.
+const LEGACY_VAULT_BALANCE: u64 = 5_000;
+
+fn legacy_vesting(beneficiary: Pubkey) -> VestingAccountV0 {
+    VestingAccountV0 {
+        beneficiary,
+        total_amount: 1_000,
+        claimed_amount: 250,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+    }
+}
+
+fn legacy_account<T: AnchorSerialize>(state: &T, len: usize) -> Account {
+    let mut data = VestingAccount::DISCRIMINATOR.to_vec();
+    data.extend(state.try_to_vec().unwrap());
+    data.resize(len, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: token_vesting::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+struct Legacy {
+    mint: Pubkey,
+    legacy_vesting_account: Pubkey,
+    legacy_vault: Pubkey,
+}
+
+async fn setup<T: AnchorSerialize>(state: &T, len: usize) -> (TestContext, Legacy) {
+    let program_test = ProgramTest::new(
+        "token_vesting",
+        token_vesting::ID,
+        anchor_processor!(token_vesting::entry),
+    );
+    let mut ctx = TestContext::new(program_test.start_with_context().await);
+    ctx.set_protocol_config(&[]);
+
+    let (vault_authority, _) =
+        Pubkey::find_program_address(&[VAULT_AUTHORITY_SEED], &token_vesting::ID);
+    let mint = ctx.create_mint(6).await;
+    let legacy_vault = ctx.create_token_account(&mint, &vault_authority).await;
+    ctx.mint_to(&mint, &legacy_vault, LEGACY_VAULT_BALANCE)
+        .await;
+
+    let legacy_vesting_account = Pubkey::new_unique();
+    ctx.set_account(&legacy_vesting_account, legacy_account(state, len));
+
+    let legacy = Legacy {
+        mint,
+        legacy_vesting_account,
+        legacy_vault,
+    };
+    (ctx, legacy)
+}
+
+async fn migrate(
+    ctx: &mut TestContext,
+    admin: &Keypair,
+    legacy: &Legacy,
+    beneficiary: Pubkey,
+) -> TxResult {
+    let ix = migrate_ix(admin, legacy, beneficiary);
+    ctx.process(&[ix], &[admin]).await
+}
+
+fn migrate_ix(admin: &Keypair, legacy: &Legacy, beneficiary: Pubkey) -> Instruction {
+    let (vesting_account, _) = vesting_address(&legacy.mint, &beneficiary, SCHEDULE_ID);
+    instruction(
+        token_vesting::ID,
+        accounts::MigrateVesting {
+            admin: admin.pubkey(),
+            config: protocol_config(),
+            legacy_vesting_account: legacy.legacy_vesting_account,
+            legacy_vault: legacy.legacy_vault,
+            vault_authority: Pubkey::find_program_address(
+                &[VAULT_AUTHORITY_SEED],
+                &token_vesting::ID,
+            )
+            .0,
+            mint: legacy.mint,
+            beneficiary,
+            vesting_account,
+            vesting_vault: get_associated_token_address(&vesting_account, &legacy.mint),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        ix::MigrateVesting {
+            schedule_id: SCHEDULE_ID,
+        },
+    )
+}
+
+/// The payer, which `set_protocol_config` makes the protocol admin.
+fn admin(ctx: &TestContext) -> Keypair {
+    ctx.context.payer.insecure_clone()
+}
+
+#[tokio::test]
+async fn migrates_legacy_vesting_account() {
+    let beneficiary = Pubkey::new_unique();
+    let legacy_state = legacy_vesting(beneficiary);
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV0::LEN).await;
+    let admin = admin(&ctx);
+
+    migrate(&mut ctx, &admin, &legacy, beneficiary)
+        .await
+        .unwrap();
+
+    let (vesting_account, bump) = vesting_address(&legacy.mint, &beneficiary, SCHEDULE_ID);
+    let account = ctx.get_account(&vesting_account).await.unwrap();
+    assert_eq!(account.data.len(), VestingAccount::LEN);
+    let migrated: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert_eq!(migrated.version, VestingAccount::VERSION);
+    assert_eq!(migrated.beneficiary, beneficiary);
+    assert_eq!(migrated.original_beneficiary, beneficiary);
+    assert_eq!(migrated.mint, legacy.mint);
+    assert_eq!(migrated.schedule_id, SCHEDULE_ID);
+    assert_eq!(migrated.total_amount, legacy_state.total_amount);
+    assert_eq!(migrated.claimed_amount, legacy_state.claimed_amount);
+    assert_eq!(migrated.start_time, legacy_state.start_time);
+    assert_eq!(migrated.cliff_time, legacy_state.cliff_time);
+    assert_eq!(migrated.vesting_end_time, legacy_state.vesting_end_time);
+    assert_eq!(migrated.bump, bump);
+    assert_eq!(migrated.kind, VestingKind::Linear);
+
+    // Only what the schedule still owes leaves the shared vault
+    let vault = get_associated_token_address(&vesting_account, &legacy.mint);
+    assert_eq!(ctx.token_balance(&vault).await, 750);
+    assert_eq!(
+        ctx.token_balance(&legacy.legacy_vault).await,
+        LEGACY_VAULT_BALANCE - 750
+    );
+    assert!(ctx
+        .get_account(&legacy.legacy_vesting_account)
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn migration_emits_unclaimed_remainder() {
+    let beneficiary = Pubkey::new_unique();
+    let legacy_state = legacy_vesting(beneficiary);
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV0::LEN).await;
+    let admin = admin(&ctx);
+
+    let events: Vec<VestingMigrated> = ctx
+        .process_events(&[migrate_ix(&admin, &legacy, beneficiary)], &[&admin])
+        .await
+        .unwrap();
+
+    let (vesting_account, _) = vesting_address(&legacy.mint, &beneficiary, SCHEDULE_ID);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].vesting_account, vesting_account);
+    assert_eq!(
+        events[0].legacy_vesting_account,
+        legacy.legacy_vesting_account
+    );
+    assert_eq!(events[0].beneficiary, beneficiary);
+    assert_eq!(events[0].total_amount, legacy_state.total_amount);
+    assert_eq!(events[0].claimed_amount, legacy_state.claimed_amount);
+}
+
+#[tokio::test]
+async fn migrates_v1_vesting_account() {
+    let beneficiary = Pubkey::new_unique();
+    let legacy_state = VestingAccountV1 {
+        version: 1,
+        beneficiary,
+        total_amount: 1_000,
+        claimed_amount: 400,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+    };
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV1::LEN).await;
+    let admin = admin(&ctx);
+
+    migrate(&mut ctx, &admin, &legacy, beneficiary)
+        .await
+        .unwrap();
+
+    let (vesting_account, _) = vesting_address(&legacy.mint, &beneficiary, SCHEDULE_ID);
+    let migrated: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert_eq!(migrated.version, VestingAccount::VERSION);
+    assert_eq!(migrated.claimed_amount, legacy_state.claimed_amount);
+    let vault = get_associated_token_address(&vesting_account, &legacy.mint);
+    assert_eq!(ctx.token_balance(&vault).await, 600);
+}
+
+#[tokio::test]
+async fn migrates_v2_vesting_account() {
+    let beneficiary = Pubkey::new_unique();
+    let legacy_state = VestingAccountV2 {
+        version: 2,
+        beneficiary,
+        total_amount: 1_000,
+        claimed_amount: 0,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+        vault_authority_bump: 255,
+    };
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV2::LEN).await;
+    let admin = admin(&ctx);
+
+    migrate(&mut ctx, &admin, &legacy, beneficiary)
+        .await
+        .unwrap();
+
+    let (vesting_account, _) = vesting_address(&legacy.mint, &beneficiary, SCHEDULE_ID);
+    let migrated: VestingAccount = ctx.fetch(&vesting_account).await;
+    assert_eq!(migrated.total_amount, legacy_state.total_amount);
+    let vault = get_associated_token_address(&vesting_account, &legacy.mint);
+    assert_eq!(ctx.token_balance(&vault).await, 1_000);
+}
+
+#[tokio::test]
+async fn rejects_migration_by_another_signer() {
+    let beneficiary = Pubkey::new_unique();
+    let (mut ctx, legacy) = setup(&legacy_vesting(beneficiary), VestingAccountV0::LEN).await;
+    let intruder = ctx.funded_keypair().await;
+
+    let result = migrate(&mut ctx, &intruder, &legacy, beneficiary).await;
+    assert_error(result, ErrorCode::Unauthorized);
+    let account = ctx
+        .get_account(&legacy.legacy_vesting_account)
+        .await
+        .unwrap();
+    assert_eq!(account.data.len(), VestingAccountV0::LEN);
+}
+
+#[tokio::test]
+async fn rejects_migration_to_another_beneficiary() {
+    let beneficiary = Pubkey::new_unique();
+    let (mut ctx, legacy) = setup(&legacy_vesting(beneficiary), VestingAccountV0::LEN).await;
+    let admin = admin(&ctx);
+
+    let result = migrate(&mut ctx, &admin, &legacy, Pubkey::new_unique()).await;
+    assert_error(result, ErrorCode::Unauthorized);
+    assert_eq!(
+        ctx.token_balance(&legacy.legacy_vault).await,
+        LEGACY_VAULT_BALANCE
+    );
+}
+
+async fn upgrade(ctx: &mut TestContext, signer: &Keypair, vesting_account: Pubkey) -> TxResult {
+    let ix = instruction(
+        token_vesting::ID,
+        accounts::UpgradeVesting {
+            beneficiary: signer.pubkey(),
+            vesting_account,
+            system_program: system_program::ID,
+        },
+        ix::UpgradeVesting {},
+    );
+    ctx.process(&[ix], &[signer]).await
+}
+
+fn v3_vesting(beneficiary: Pubkey, mint: Pubkey) -> VestingAccountV3 {
+    VestingAccountV3 {
+        version: 3,
+        beneficiary,
+        mint,
+        schedule_id: SCHEDULE_ID,
+        total_amount: 1_000,
+        claimed_amount: 250,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+        bump: 254,
+    }
+}
+
+#[tokio::test]
+async fn upgrades_v3_schedule_in_place() {
+    let beneficiary = Keypair::new();
+    let legacy_state = v3_vesting(beneficiary.pubkey(), Pubkey::new_unique());
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV3::LEN).await;
+    let payer = admin(&ctx);
+    ctx.process(
+        &[solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &beneficiary.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    upgrade(&mut ctx, &beneficiary, legacy.legacy_vesting_account)
+        .await
+        .unwrap();
+
+    let account = ctx
+        .get_account(&legacy.legacy_vesting_account)
+        .await
+        .unwrap();
+    assert_eq!(account.data.len(), VestingAccount::LEN);
+    let upgraded: VestingAccount = ctx.fetch(&legacy.legacy_vesting_account).await;
+    assert_eq!(upgraded.version, VestingAccount::VERSION);
+    assert_eq!(upgraded.mint, legacy_state.mint);
+    assert_eq!(upgraded.claimed_amount, legacy_state.claimed_amount);
+    assert_eq!(upgraded.bump, legacy_state.bump);
+    assert!(!upgraded.revocable);
+    assert!(!upgraded.revoked);
+    assert_eq!(upgraded.kind, VestingKind::Linear);
+}
+
+#[tokio::test]
+async fn upgrades_v4_schedule_in_place() {
+    let beneficiary = Keypair::new();
+    let legacy_state = VestingAccountV4 {
+        version: 4,
+        beneficiary: beneficiary.pubkey(),
+        admin: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        schedule_id: SCHEDULE_ID,
+        total_amount: 1_000,
+        claimed_amount: 250,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+        revocable: true,
+        revoked: false,
+        bump: 254,
+    };
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV4::LEN).await;
+    let payer = admin(&ctx);
+    ctx.process(
+        &[solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &beneficiary.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    upgrade(&mut ctx, &beneficiary, legacy.legacy_vesting_account)
+        .await
+        .unwrap();
+
+    let upgraded: VestingAccount = ctx.fetch(&legacy.legacy_vesting_account).await;
+    assert_eq!(upgraded.version, VestingAccount::VERSION);
+    assert_eq!(upgraded.admin, legacy_state.admin);
+    assert_eq!(upgraded.claimed_amount, legacy_state.claimed_amount);
+    assert!(upgraded.revocable);
+    assert_eq!(upgraded.kind, VestingKind::Linear);
+}
+
+#[tokio::test]
+async fn rejects_upgrade_by_another_signer() {
+    let legacy_state = v3_vesting(Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV3::LEN).await;
+    let intruder = ctx.funded_keypair().await;
+
+    let result = upgrade(&mut ctx, &intruder, legacy.legacy_vesting_account).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn upgrades_v5_schedule_in_place() {
+    let beneficiary = Keypair::new();
+    let legacy_state = VestingAccountV5 {
+        version: 5,
+        beneficiary: beneficiary.pubkey(),
+        admin: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        schedule_id: SCHEDULE_ID,
+        total_amount: 1_000,
+        claimed_amount: 250,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+        kind: VestingKind::Periodic { tranches: 10 },
+        revocable: true,
+        revoked: false,
+        bump: 254,
+    };
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV5::LEN).await;
+    let payer = admin(&ctx);
+    ctx.process(
+        &[solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &beneficiary.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    upgrade(&mut ctx, &beneficiary, legacy.legacy_vesting_account)
+        .await
+        .unwrap();
+
+    let upgraded: VestingAccount = ctx.fetch(&legacy.legacy_vesting_account).await;
+    assert_eq!(upgraded.version, VestingAccount::VERSION);
+    assert_eq!(upgraded.original_beneficiary, beneficiary.pubkey());
+    assert_eq!(upgraded.kind, legacy_state.kind);
+    assert_eq!(upgraded.claim_delegate, None);
+    assert_eq!(upgraded.pending_beneficiary, None);
+}
+
+#[tokio::test]
+async fn upgrades_v6_schedule_in_place() {
+    let beneficiary = Keypair::new();
+    let delegate = Pubkey::new_unique();
+    let legacy_state = VestingAccountV6 {
+        version: 6,
+        beneficiary: beneficiary.pubkey(),
+        original_beneficiary: Pubkey::new_unique(),
+        admin: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        schedule_id: SCHEDULE_ID,
+        total_amount: 1_000,
+        claimed_amount: 250,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+        kind: VestingKind::CliffPercentage { cliff_bps: 2_000 },
+        revocable: false,
+        revoked: false,
+        pending_beneficiary: None,
+        claim_delegate: Some(delegate),
+        bump: 254,
+    };
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV6::LEN).await;
+    let payer = admin(&ctx);
+    ctx.process(
+        &[solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &beneficiary.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    upgrade(&mut ctx, &beneficiary, legacy.legacy_vesting_account)
+        .await
+        .unwrap();
+
+    let upgraded: VestingAccount = ctx.fetch(&legacy.legacy_vesting_account).await;
+    assert_eq!(upgraded.version, VestingAccount::VERSION);
+    assert_eq!(
+        upgraded.original_beneficiary,
+        legacy_state.original_beneficiary
+    );
+    assert_eq!(upgraded.kind, legacy_state.kind);
+    assert_eq!(upgraded.claim_delegate, Some(delegate));
+    assert_eq!(upgraded.staked_amount, 0);
+}
+
+#[tokio::test]
+async fn upgrades_v7_schedule_in_place() {
+    let beneficiary = Keypair::new();
+    let legacy_state = VestingAccountV7 {
+        version: 7,
+        beneficiary: beneficiary.pubkey(),
+        original_beneficiary: beneficiary.pubkey(),
+        admin: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        schedule_id: SCHEDULE_ID,
+        total_amount: 1_000,
+        claimed_amount: 250,
+        start_time: 100,
+        cliff_time: 200,
+        vesting_end_time: 1_100,
+        kind: VestingKind::Linear,
+        revocable: false,
+        revoked: false,
+        pending_beneficiary: None,
+        claim_delegate: None,
+        staked_amount: 400,
+        bump: 254,
+    };
+    let (mut ctx, legacy) = setup(&legacy_state, VestingAccountV7::LEN).await;
+    let payer = admin(&ctx);
+    ctx.process(
+        &[solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &beneficiary.pubkey(),
+            1_000_000_000,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    upgrade(&mut ctx, &beneficiary, legacy.legacy_vesting_account)
+        .await
+        .unwrap();
+
+    let upgraded: VestingAccount = ctx.fetch(&legacy.legacy_vesting_account).await;
+    assert_eq!(upgraded.version, VestingAccount::VERSION);
+    assert_eq!(upgraded.staked_amount, 400);
+    assert_eq!(upgraded.campaign, None);
+}
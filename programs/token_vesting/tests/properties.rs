@@ -0,0 +1,149 @@
+//! Vesting curve properties and random claim sequences against the vault.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use test_utils::vesting::{self, Schedule};
+use test_utils::{block_on, TestContext};
+use token_vesting::{get_vested_amount, instruction as ix, Milestone, VestingAccount, VestingKind};
+
+const CLIFF: i64 = 1_000;
+const DURATION: i64 = 10_000;
+
+fn schedule(
+    total_amount: u64,
+    start_time: i64,
+    cliff_time: i64,
+    vesting_end_time: i64,
+) -> VestingAccount {
+    VestingAccount {
+        version: 1,
+        beneficiary: Pubkey::default(),
+        original_beneficiary: Pubkey::default(),
+        admin: Pubkey::default(),
+        mint: Pubkey::default(),
+        schedule_id: 0,
+        total_amount,
+        claimed_amount: 0,
+        start_time,
+        cliff_time,
+        vesting_end_time,
+        kind: VestingKind::Linear,
+        revocable: false,
+        revoked: false,
+        pending_beneficiary: None,
+        claim_delegate: None,
+        staked_amount: 0,
+        campaign: None,
+        bump: 0,
+    }
+}
+
+proptest! {
+    #[test]
+    fn vested_amount_is_monotonic_and_bounded(
+        total_amount in any::<u64>(),
+        start_time in 0..1_000_000i64,
+        period in 1..1_000_000i64,
+        cliff_fraction in 0.0..=1.0f64,
+        a in 0..2_000_000i64,
+        b in 0..2_000_000i64,
+    ) {
+        let cliff_time = start_time + (period as f64 * cliff_fraction) as i64;
+        let vesting = schedule(total_amount, start_time, cliff_time, start_time + period);
+        let (earlier, later) = (start_time + a.min(b), start_time + a.max(b));
+
+        let vested_earlier = get_vested_amount(&vesting, earlier).unwrap();
+        let vested_later = get_vested_amount(&vesting, later).unwrap();
+        prop_assert!(vested_earlier <= vested_later);
+        prop_assert!(vested_later <= total_amount);
+        prop_assert_eq!(get_vested_amount(&vesting, start_time).unwrap(), 0);
+        prop_assert_eq!(get_vested_amount(&vesting, cliff_time - 1).unwrap(), 0);
+        prop_assert_eq!(
+            get_vested_amount(&vesting, start_time + period).unwrap(),
+            total_amount
+        );
+    }
+}
+
+proptest! {
+    /// Splitting a milestone table keeps both halves valid and adds up at every milestone.
+    #[test]
+    fn split_milestones_add_up(
+        steps in vec(0..u64::MAX / 32, 1..16),
+        split_fraction in 0.0..1.0f64,
+    ) {
+        let mut cumulative_amount = 0;
+        let milestones: Vec<Milestone> = steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                cumulative_amount += step;
+                Milestone {
+                    timestamp: index as i64,
+                    cumulative_amount,
+                }
+            })
+            .collect();
+        let total_amount = cumulative_amount;
+        prop_assume!(total_amount > 1);
+        let amount = ((total_amount as f64 * split_fraction) as u64).clamp(1, total_amount - 1);
+        let end = milestones.len() as i64;
+        let kind = VestingKind::Milestones(milestones.clone());
+
+        let (remaining, split_off) = kind.split(amount, total_amount);
+        remaining.validate(total_amount - amount, end).unwrap();
+        split_off.validate(amount, end).unwrap();
+        prop_assert_eq!(remaining.merged(&split_off), Some(kind));
+    }
+}
+
+async fn setup(total_amount: u64) -> (TestContext, Schedule) {
+    let mut ctx = vesting::start(vesting::program_test()).await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let now = ctx.now().await;
+    let terms = ix::InitializeVesting {
+        cliff_duration: CLIFF,
+        vesting_duration: DURATION,
+        ..vesting::terms(0, now, total_amount)
+    };
+    let schedule = vesting::create_schedule(&mut ctx, mint, beneficiary, terms).await;
+    (ctx, schedule)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    /// Claims never release more than has vested and the vault always backs the rest.
+    #[test]
+        fn claims_never_exceed_vested_amount(
+        total_amount in 1..u64::MAX / 2,
+        warps in vec(0..DURATION / 4, 1..10),
+    ) {
+        block_on(async {
+            let (mut ctx, schedule) = setup(total_amount).await;
+
+            for seconds in warps {
+                ctx.warp_forward(seconds).await;
+                let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+                let _ = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+
+                let now = ctx.now().await;
+                let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+                let claimed = ctx.token_balance(&schedule.beneficiary_token_account).await;
+                let vault_balance = ctx.token_balance(&schedule.vesting_vault).await;
+
+                prop_assert_eq!(claimed, vesting.claimed_amount);
+                prop_assert_eq!(claimed + vault_balance, total_amount);
+                if now >= vesting.cliff_time {
+                    prop_assert!(claimed <= get_vested_amount(&vesting, now).unwrap());
+                } else {
+                    prop_assert_eq!(claimed, 0);
+                }
+            }
+            Ok(())
+        })?;
+    }
+}
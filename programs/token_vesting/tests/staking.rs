@@ -0,0 +1,242 @@
+//! Schedules staking their locked tokens in `staking_airdrop`.
+
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use staking_airdrop::UserState;
+use test_utils::vesting::{self, Schedule, DURATION};
+use test_utils::{anchor_processor, assert_error, instruction, protocol_config, TestContext};
+use token_vesting::{accounts, instruction as ix, ErrorCode, VestingAccount};
+
+const TOTAL_AMOUNT: u64 = 1_000_000;
+const STAKED: u64 = 600_000;
+const UNLOCK_PERIOD: i64 = 21 * 24 * 60 * 60;
+
+fn staking_vault() -> Pubkey {
+    staking_airdrop::staking_vault_address().0
+}
+
+fn staking_vault_authority() -> Pubkey {
+    staking_airdrop::staking_authority_address().0
+}
+
+fn stake_state(schedule: &Schedule) -> Pubkey {
+    Pubkey::find_program_address(&[schedule.vesting_account.as_ref()], &staking_airdrop::ID).0
+}
+
+async fn setup(revocable: bool) -> (TestContext, Schedule) {
+    let mut program_test = vesting::program_test();
+    program_test.add_program(
+        "staking_airdrop",
+        staking_airdrop::ID,
+        anchor_processor!(staking_airdrop::entry),
+    );
+    let mut ctx = vesting::start(program_test).await;
+
+    let admin = ctx.payer();
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let initialize_vaults = instruction(
+        staking_airdrop::ID,
+        staking_airdrop::accounts::InitializeVaults {
+            admin,
+            config: protocol_config(),
+            mint,
+            airdrop_vault: staking_airdrop::airdrop_vault_address().0,
+            airdrop_authority: staking_airdrop::airdrop_authority_address().0,
+            staking_vault: staking_vault(),
+            staking_authority: staking_vault_authority(),
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        staking_airdrop::instruction::InitializeVaults {},
+    );
+    ctx.process(&[initialize_vaults], &[]).await.unwrap();
+    let now = ctx.now().await;
+    let terms = ix::InitializeVesting {
+        revocable,
+        ..vesting::terms(1, now, TOTAL_AMOUNT)
+    };
+    let schedule = vesting::create_schedule(&mut ctx, mint, beneficiary, terms).await;
+    (ctx, schedule)
+}
+
+fn stake_accounts(schedule: &Schedule, staking_vault: Pubkey) -> accounts::StakeVesting {
+    accounts::StakeVesting {
+        beneficiary: schedule.beneficiary.pubkey(),
+        vesting_account: schedule.vesting_account,
+        mint: schedule.mint,
+        vesting_vault: schedule.vesting_vault,
+        staking_vault,
+        staking_vault_authority: staking_vault_authority(),
+        stake_state: stake_state(schedule),
+        config: protocol_config(),
+        staking_program: staking_airdrop::ID,
+        token_program: spl_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+fn stake_ix(schedule: &Schedule, amount: u64) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        stake_accounts(schedule, staking_vault()),
+        ix::StakeVesting { amount },
+    )
+}
+
+fn unstake_ix(schedule: &Schedule, amount: u64) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        stake_accounts(schedule, staking_vault()),
+        ix::UnstakeVesting { amount },
+    )
+}
+
+/// Claims `amount`, or everything claimable, passing the staking accounts when `unstake` is set.
+fn claim_ix(schedule: &Schedule, amount: Option<u64>, unstake: bool) -> Instruction {
+    let staking = |address: Pubkey| unstake.then_some(address);
+    instruction(
+        token_vesting::ID,
+        accounts::ClaimTokens {
+            staking_program: staking(staking_airdrop::ID),
+            staking_vault: staking(staking_vault()),
+            staking_vault_authority: staking(staking_vault_authority()),
+            stake_state: staking(stake_state(schedule)),
+            system_program: staking(system_program::ID),
+            ..schedule.claim_accounts(schedule.beneficiary.pubkey())
+        },
+        ix::ClaimTokens { amount },
+    )
+}
+
+async fn stake(ctx: &mut TestContext, schedule: &Schedule, amount: u64) {
+    ctx.process(&[stake_ix(schedule, amount)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn stake_moves_vault_tokens_to_pool() {
+    let (mut ctx, schedule) = setup(false).await;
+
+    stake(&mut ctx, &schedule, STAKED).await;
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.staked_amount, STAKED);
+    let state: UserState = ctx.fetch(&stake_state(&schedule)).await;
+    assert_eq!(state.staked_amount, STAKED);
+    assert_eq!(
+        ctx.token_balance(&schedule.vesting_vault).await,
+        TOTAL_AMOUNT - STAKED
+    );
+    assert_eq!(ctx.token_balance(&staking_vault()).await, STAKED);
+}
+
+#[tokio::test]
+async fn claim_unstakes_what_the_vault_lacks() {
+    let (mut ctx, schedule) = setup(false).await;
+    stake(&mut ctx, &schedule, STAKED).await;
+    ctx.warp_forward(UNLOCK_PERIOD).await;
+
+    ctx.process(&[claim_ix(&schedule, None, true)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.claimed_amount, TOTAL_AMOUNT);
+    assert_eq!(vesting.staked_amount, 0);
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT
+    );
+    assert_eq!(ctx.token_balance(&staking_vault()).await, 0);
+}
+
+#[tokio::test]
+async fn claim_from_vault_needs_no_staking_accounts() {
+    let (mut ctx, schedule) = setup(false).await;
+    stake(&mut ctx, &schedule, STAKED).await;
+    ctx.warp_forward(DURATION).await;
+
+    let unstaked = TOTAL_AMOUNT - STAKED;
+    ctx.process(
+        &[claim_ix(&schedule, Some(unstaked), false)],
+        &[&schedule.beneficiary],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        unstaked
+    );
+
+    let result = ctx
+        .process(
+            &[claim_ix(&schedule, None, false)],
+            &[&schedule.beneficiary],
+        )
+        .await;
+    assert_error(result, ErrorCode::StakingAccountsMissing);
+}
+
+#[tokio::test]
+async fn claim_during_unlock_period_fails() {
+    let (mut ctx, schedule) = setup(false).await;
+    stake(&mut ctx, &schedule, STAKED).await;
+    ctx.warp_forward(DURATION).await;
+
+    let result = ctx
+        .process(&[claim_ix(&schedule, None, true)], &[&schedule.beneficiary])
+        .await;
+    assert_error(result, staking_airdrop::ErrorCode::TokensLocked);
+}
+
+#[tokio::test]
+async fn unstake_returns_tokens_to_vault() {
+    let (mut ctx, schedule) = setup(false).await;
+    stake(&mut ctx, &schedule, STAKED).await;
+    ctx.warp_forward(UNLOCK_PERIOD).await;
+
+    ctx.process(&[unstake_ix(&schedule, STAKED)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.staked_amount, 0);
+    assert_eq!(
+        ctx.token_balance(&schedule.vesting_vault).await,
+        TOTAL_AMOUNT
+    );
+
+    let result = ctx
+        .process(&[unstake_ix(&schedule, 1)], &[&schedule.beneficiary])
+        .await;
+    assert_error(result, ErrorCode::InsufficientStake);
+}
+
+#[tokio::test]
+async fn revocable_schedule_cannot_stake() {
+    let (mut ctx, schedule) = setup(true).await;
+
+    let result = ctx
+        .process(&[stake_ix(&schedule, STAKED)], &[&schedule.beneficiary])
+        .await;
+    assert_error(result, ErrorCode::RevocableStake);
+}
+
+#[tokio::test]
+async fn stake_into_account_outside_pool_fails() {
+    let (mut ctx, schedule) = setup(false).await;
+
+    let stake = instruction(
+        token_vesting::ID,
+        stake_accounts(&schedule, schedule.beneficiary_token_account),
+        ix::StakeVesting { amount: STAKED },
+    );
+    let result = ctx.process(&[stake], &[&schedule.beneficiary]).await;
+    assert_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+}
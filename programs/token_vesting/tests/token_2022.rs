@@ -0,0 +1,164 @@
+//! Schedules of Token-2022 mints, with and without a transfer fee.
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::vesting::{self, create_schedule, terms, Schedule, DURATION};
+use test_utils::{instruction, protocol_config, TestContext};
+use token_vesting::{accounts, instruction as ix, VestingAccount};
+
+const TOTAL_AMOUNT: u64 = 1_000_000;
+/// A 1% fee, uncapped for the amounts used here.
+const FEE: Option<(u16, u64)> = Some((100, u64::MAX));
+
+async fn setup(transfer_fee: Option<(u16, u64)>) -> (TestContext, Pubkey, Keypair) {
+    let mut ctx = vesting::start(vesting::program_test()).await;
+    let mint = ctx.create_token_2022_mint(6, transfer_fee).await;
+    let beneficiary = ctx.funded_keypair().await;
+    (ctx, mint, beneficiary)
+}
+
+fn close_ix(schedule: &Schedule) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::CloseVesting {
+            beneficiary: schedule.beneficiary.pubkey(),
+            vesting_account: schedule.vesting_account,
+            mint: schedule.mint,
+            vesting_vault: schedule.vesting_vault,
+            receiver: schedule.beneficiary.pubkey(),
+            token_program: schedule.token_program,
+        },
+        ix::CloseVesting {},
+    )
+}
+
+#[tokio::test]
+async fn token_2022_schedule_claims_and_closes() {
+    let (mut ctx, mint, beneficiary) = setup(None).await;
+    let now = ctx.now().await;
+    let schedule = create_schedule(&mut ctx, mint, beneficiary, terms(1, now, TOTAL_AMOUNT)).await;
+    assert_eq!(
+        ctx.token_balance(&schedule.vesting_vault).await,
+        TOTAL_AMOUNT
+    );
+
+    ctx.warp_forward(DURATION).await;
+    ctx.process(
+        &[
+            schedule.claim_ix(schedule.beneficiary.pubkey(), None),
+            close_ix(&schedule),
+        ],
+        &[&schedule.beneficiary],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT
+    );
+    assert!(ctx.get_account(&schedule.vesting_account).await.is_none());
+    assert!(ctx.get_account(&schedule.vesting_vault).await.is_none());
+}
+
+#[tokio::test]
+async fn transfer_fee_is_taken_from_the_schedule() {
+    let (mut ctx, mint, beneficiary) = setup(FEE).await;
+    let now = ctx.now().await;
+    let schedule = create_schedule(&mut ctx, mint, beneficiary, terms(1, now, TOTAL_AMOUNT)).await;
+
+    // The schedule only covers what reached the vault
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.total_amount, 990_000);
+    assert_eq!(ctx.token_balance(&schedule.vesting_vault).await, 990_000);
+
+    ctx.warp_forward(DURATION).await;
+    ctx.process(
+        &[schedule.claim_ix(schedule.beneficiary.pubkey(), None)],
+        &[&schedule.beneficiary],
+    )
+    .await
+    .unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.claimed_amount, 990_000);
+    assert_eq!(ctx.token_balance(&schedule.vesting_vault).await, 0);
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        980_100
+    );
+
+    // Fees withheld in the vault are harvested to the mint before it closes
+    ctx.process(&[close_ix(&schedule)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert!(ctx.get_account(&schedule.vesting_vault).await.is_none());
+}
+
+#[tokio::test]
+async fn top_up_credits_amount_after_fee() {
+    let (mut ctx, mint, beneficiary) = setup(FEE).await;
+    let now = ctx.now().await;
+    let schedule = create_schedule(&mut ctx, mint, beneficiary, terms(1, now, TOTAL_AMOUNT)).await;
+
+    let admin = ctx.payer();
+    let admin_token_account = ctx.create_token_account(&mint, &admin).await;
+    ctx.mint_to(&mint, &admin_token_account, 100_000).await;
+    let top_up = instruction(
+        token_vesting::ID,
+        accounts::TopUpVesting {
+            admin,
+            admin_token_account,
+            vesting_account: schedule.vesting_account,
+            mint,
+            vesting_vault: schedule.vesting_vault,
+            config: protocol_config(),
+            token_program: schedule.token_program,
+        },
+        ix::TopUpVesting { amount: 100_000 },
+    );
+    ctx.process(&[top_up], &[]).await.unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.total_amount, 990_000 + 99_000);
+    assert_eq!(
+        ctx.token_balance(&schedule.vesting_vault).await,
+        990_000 + 99_000
+    );
+}
+
+#[tokio::test]
+async fn merge_credits_amount_after_fee() {
+    let (mut ctx, mint, beneficiary) = setup(FEE).await;
+    let now = ctx.now().await;
+    let target = create_schedule(&mut ctx, mint, beneficiary, terms(1, now, TOTAL_AMOUNT)).await;
+    let beneficiary = target.beneficiary.insecure_clone();
+    let source = create_schedule(&mut ctx, mint, beneficiary, terms(2, now, TOTAL_AMOUNT)).await;
+
+    let merge = instruction(
+        token_vesting::ID,
+        accounts::MergeVesting {
+            beneficiary: target.beneficiary.pubkey(),
+            vesting_account: target.vesting_account,
+            vesting_vault: target.vesting_vault,
+            source_vesting_account: source.vesting_account,
+            source_vesting_vault: source.vesting_vault,
+            mint,
+            config: protocol_config(),
+            token_program: target.token_program,
+        },
+        ix::MergeVesting {},
+    );
+    ctx.process(&[merge], &[&target.beneficiary]).await.unwrap();
+
+    // Moving the source's 990_000 costs another 9_900
+    let vesting: VestingAccount = ctx.fetch(&target.vesting_account).await;
+    assert_eq!(vesting.total_amount, 990_000 + 980_100);
+    assert_eq!(
+        ctx.token_balance(&target.vesting_vault).await,
+        990_000 + 980_100
+    );
+    assert!(ctx.get_account(&source.vesting_account).await.is_none());
+    assert!(ctx.get_account(&source.vesting_vault).await.is_none());
+}
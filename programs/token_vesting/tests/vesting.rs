@@ -0,0 +1,757 @@
+use anchor_lang::AnchorDeserialize;
+use protocol_config::{Module, ProtocolError};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use test_utils::vesting::{self, Schedule, CLIFF, DURATION};
+use test_utils::{assert_error, instruction, protocol_config, TestContext};
+use token_vesting::{
+    accounts, instruction as ix, ErrorCode, VestingAccount, VestingKind, VestingView,
+};
+
+const TOTAL_AMOUNT: u64 = 1_000_000;
+const SCHEDULE_ID: u64 = 1;
+
+async fn start() -> TestContext {
+    vesting::start(vesting::program_test()).await
+}
+
+/// Arguments for a linear schedule of `TOTAL_AMOUNT` starting at `start_time`.
+fn terms(schedule_id: u64, start_time: i64, revocable: bool) -> ix::InitializeVesting {
+    ix::InitializeVesting {
+        revocable,
+        ..vesting::terms(schedule_id, start_time, TOTAL_AMOUNT)
+    }
+}
+
+async fn create_schedule(
+    ctx: &mut TestContext,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    schedule_id: u64,
+    revocable: bool,
+) -> Schedule {
+    let now = ctx.now().await;
+    vesting::create_schedule(ctx, mint, beneficiary, terms(schedule_id, now, revocable)).await
+}
+
+async fn setup() -> (TestContext, Schedule) {
+    let mut ctx = start().await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let schedule = create_schedule(&mut ctx, mint, beneficiary, SCHEDULE_ID, true).await;
+    (ctx, schedule)
+}
+
+fn revoke_ix(schedule: &Schedule, admin: Pubkey, treasury_token_account: Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::RevokeVesting {
+            admin,
+            vesting_account: schedule.vesting_account,
+            mint: schedule.mint,
+            vesting_vault: schedule.vesting_vault,
+            treasury_token_account,
+            config: protocol_config(),
+            token_program: schedule.token_program,
+        },
+        ix::RevokeVesting {},
+    )
+}
+
+#[tokio::test]
+async fn initialize_vesting_funds_vault() {
+    let (mut ctx, schedule) = setup().await;
+    let now = ctx.now().await;
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.beneficiary, schedule.beneficiary.pubkey());
+    assert_eq!(vesting.mint, schedule.mint);
+    assert_eq!(vesting.schedule_id, SCHEDULE_ID);
+    assert_eq!(vesting.total_amount, TOTAL_AMOUNT);
+    assert_eq!(vesting.claimed_amount, 0);
+    assert_eq!(vesting.cliff_time, now + CLIFF);
+    assert_eq!(vesting.vesting_end_time, now + DURATION);
+    assert_eq!(
+        ctx.token_balance(&schedule.vesting_vault).await,
+        TOTAL_AMOUNT
+    );
+}
+
+/// Tries to create a schedule with `terms` adjusted by `adjust` and expects `error`.
+async fn assert_rejected_terms(adjust: impl FnOnce(&mut ix::InitializeVesting), error: ErrorCode) {
+    let mut ctx = start().await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let mut terms = terms(SCHEDULE_ID, ctx.now().await, false);
+    adjust(&mut terms);
+
+    let result = vesting::try_create_schedule(&mut ctx, mint, beneficiary, terms).await;
+    assert_error(result.map(|_| ()), error);
+}
+
+#[tokio::test]
+async fn initialize_rejects_invalid_kind() {
+    assert_rejected_terms(
+        |terms| terms.kind = VestingKind::Periodic { tranches: 0 },
+        ErrorCode::InvalidVestingKind,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn initialize_rejects_zero_amount() {
+    assert_rejected_terms(|terms| terms.total_amount = 0, ErrorCode::ZeroAmount).await;
+}
+
+#[tokio::test]
+async fn initialize_rejects_zero_duration() {
+    assert_rejected_terms(
+        |terms| {
+            terms.cliff_duration = 0;
+            terms.vesting_duration = 0;
+        },
+        ErrorCode::ZeroVestingPeriod,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn initialize_rejects_cliff_after_end() {
+    assert_rejected_terms(
+        |terms| terms.cliff_duration = DURATION + 1,
+        ErrorCode::InvalidScheduleTimes,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn initialize_rejects_negative_cliff() {
+    assert_rejected_terms(
+        |terms| terms.cliff_duration = -1,
+        ErrorCode::InvalidScheduleTimes,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn schedule_can_start_in_the_future() {
+    let mut ctx = start().await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let start_time = ctx.now().await + 10 * DURATION;
+    let schedule = vesting::try_create_schedule(
+        &mut ctx,
+        mint,
+        beneficiary,
+        terms(SCHEDULE_ID, start_time, false),
+    )
+    .await
+    .unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.start_time, start_time);
+    assert_eq!(vesting.cliff_time, start_time + CLIFF);
+    assert_eq!(vesting.vesting_end_time, start_time + DURATION);
+
+    // Past what would have been the end had the schedule started at creation
+    ctx.warp_forward(2 * DURATION).await;
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, ErrorCode::CliffNotReached);
+}
+
+#[tokio::test]
+async fn claim_periodic_schedule_releases_completed_tranches() {
+    let mut ctx = start().await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let mut terms = terms(SCHEDULE_ID, ctx.now().await, false);
+    terms.kind = VestingKind::Periodic { tranches: 4 };
+    let schedule = vesting::try_create_schedule(&mut ctx, mint, beneficiary, terms)
+        .await
+        .unwrap();
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    let tranche_length = DURATION / 4;
+    // One second short of the second tranche
+    ctx.warp_to_timestamp(vesting.start_time + 2 * tranche_length - 1)
+        .await;
+
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT / 4
+    );
+}
+
+#[tokio::test]
+async fn claim_before_cliff_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(CLIFF - 1).await;
+
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, ErrorCode::CliffNotReached);
+}
+
+#[tokio::test]
+async fn claim_by_other_signer_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+
+    let intruder = ctx.funded_keypair().await;
+    let claim = schedule.claim_ix(intruder.pubkey(), None);
+    let result = ctx.process(&[claim], &[&intruder]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn claim_at_cliff_releases_time_since_start() {
+    let (mut ctx, schedule) = setup().await;
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    ctx.warp_to_timestamp(vesting.cliff_time).await;
+
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT * CLIFF as u64 / DURATION as u64
+    );
+}
+
+async fn simulate_view(
+    ctx: &mut TestContext,
+    schedule: &Schedule,
+    at_timestamp: Option<i64>,
+) -> VestingView {
+    let view = instruction(
+        token_vesting::ID,
+        accounts::ViewVesting {
+            vesting_account: schedule.vesting_account,
+        },
+        ix::ViewVesting { at_timestamp },
+    );
+    let data = ctx.simulate_return_data(&[view]).await.unwrap();
+    VestingView::try_from_slice(&data).unwrap()
+}
+
+#[tokio::test]
+async fn view_vesting_returns_amounts_through_simulation() {
+    let (mut ctx, schedule) = setup().await;
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    ctx.warp_to_timestamp(vesting.cliff_time).await;
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+
+    let now = ctx.now().await;
+    assert_eq!(
+        simulate_view(&mut ctx, &schedule, None).await,
+        vesting.view(now).unwrap()
+    );
+    let halfway = vesting.start_time + DURATION / 2;
+    assert_eq!(
+        simulate_view(&mut ctx, &schedule, Some(halfway)).await,
+        VestingView {
+            vested: TOTAL_AMOUNT / 2,
+            claimed: vesting.claimed_amount,
+            claimable: TOTAL_AMOUNT / 2 - vesting.claimed_amount,
+            next_unlock_time: Some(halfway + 1),
+        }
+    );
+}
+
+#[tokio::test]
+async fn claim_after_end_releases_everything() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.claimed_amount, TOTAL_AMOUNT);
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT
+    );
+    assert_eq!(ctx.token_balance(&schedule.vesting_vault).await, 0);
+}
+
+#[tokio::test]
+async fn claim_while_paused_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    ctx.set_protocol_config(&[Module::Vesting]);
+
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, ProtocolError::ModulePaused);
+    assert_eq!(
+        ctx.token_balance(&schedule.vesting_vault).await,
+        TOTAL_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn schedules_of_one_beneficiary_get_separate_vaults() {
+    let (mut ctx, first) = setup().await;
+    let beneficiary = first.beneficiary.insecure_clone();
+    let second = create_schedule(&mut ctx, first.mint, beneficiary, SCHEDULE_ID + 1, true).await;
+
+    assert_ne!(first.vesting_account, second.vesting_account);
+    assert_ne!(first.vesting_vault, second.vesting_vault);
+    assert_eq!(ctx.token_balance(&first.vesting_vault).await, TOTAL_AMOUNT);
+    assert_eq!(ctx.token_balance(&second.vesting_vault).await, TOTAL_AMOUNT);
+}
+
+#[tokio::test]
+async fn claim_from_another_schedules_vault_fails() {
+    let (mut ctx, first) = setup().await;
+    let other_beneficiary = ctx.funded_keypair().await;
+    let second = create_schedule(&mut ctx, first.mint, other_beneficiary, SCHEDULE_ID, true).await;
+    ctx.warp_forward(DURATION).await;
+
+    let mut accounts = first.claim_accounts(first.beneficiary.pubkey());
+    accounts.vesting_vault = second.vesting_vault;
+    let claim = instruction(
+        token_vesting::ID,
+        accounts,
+        ix::ClaimTokens { amount: None },
+    );
+    let result = ctx.process(&[claim], &[&first.beneficiary]).await;
+    assert_error(result, anchor_lang::error::ErrorCode::ConstraintTokenOwner);
+    assert_eq!(ctx.token_balance(&second.vesting_vault).await, TOTAL_AMOUNT);
+}
+
+#[tokio::test]
+async fn claim_with_other_mint_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    let other_mint = ctx.create_mint(6).await;
+
+    let mut accounts = schedule.claim_accounts(schedule.beneficiary.pubkey());
+    accounts.mint = other_mint;
+    let claim = instruction(
+        token_vesting::ID,
+        accounts,
+        ix::ClaimTokens { amount: None },
+    );
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, ErrorCode::InvalidMint);
+}
+
+#[tokio::test]
+async fn claim_into_account_of_other_mint_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    let other_mint = ctx.create_mint(6).await;
+    let destination = ctx
+        .create_token_account(&other_mint, &schedule.beneficiary.pubkey())
+        .await;
+
+    let mut accounts = schedule.claim_accounts(schedule.beneficiary.pubkey());
+    accounts.destination = destination;
+    let claim = instruction(
+        token_vesting::ID,
+        accounts,
+        ix::ClaimTokens { amount: None },
+    );
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, anchor_lang::error::ErrorCode::ConstraintTokenMint);
+}
+
+#[tokio::test]
+async fn revoke_midway_keeps_vested_and_returns_the_rest() {
+    let (mut ctx, schedule) = setup().await;
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    let midpoint = vesting.start_time + (vesting.vesting_end_time - vesting.start_time) / 2;
+    ctx.warp_to_timestamp(midpoint).await;
+    let treasury = ctx.create_token_account(&schedule.mint, &ctx.payer()).await;
+
+    let revoke = revoke_ix(&schedule, ctx.payer(), treasury);
+    ctx.process(&[revoke], &[]).await.unwrap();
+
+    let vested = TOTAL_AMOUNT / 2;
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert!(vesting.revoked);
+    assert_eq!(vesting.total_amount, vested);
+    assert_eq!(ctx.token_balance(&treasury).await, TOTAL_AMOUNT - vested);
+    assert_eq!(ctx.token_balance(&schedule.vesting_vault).await, vested);
+
+    // Accrual stops: waiting past the end releases only what had vested
+    ctx.warp_forward(DURATION).await;
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        vested
+    );
+    assert_eq!(ctx.token_balance(&schedule.vesting_vault).await, 0);
+}
+
+#[tokio::test]
+async fn revoke_before_cliff_returns_everything() {
+    let (mut ctx, schedule) = setup().await;
+    let treasury = ctx.create_token_account(&schedule.mint, &ctx.payer()).await;
+
+    let revoke = revoke_ix(&schedule, ctx.payer(), treasury);
+    ctx.process(&[revoke], &[]).await.unwrap();
+    assert_eq!(ctx.token_balance(&treasury).await, TOTAL_AMOUNT);
+
+    ctx.warp_forward(DURATION).await;
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, ErrorCode::NoTokensAvailable);
+}
+
+#[tokio::test]
+async fn revoke_irrevocable_schedule_fails() {
+    let mut ctx = start().await;
+    let mint = ctx.create_mint(6).await;
+    let beneficiary = ctx.funded_keypair().await;
+    let schedule = create_schedule(&mut ctx, mint, beneficiary, SCHEDULE_ID, false).await;
+    let treasury = ctx.create_token_account(&mint, &ctx.payer()).await;
+
+    let revoke = revoke_ix(&schedule, ctx.payer(), treasury);
+    let result = ctx.process(&[revoke], &[]).await;
+    assert_error(result, ErrorCode::NotRevocable);
+}
+
+#[tokio::test]
+async fn revoke_by_other_signer_fails() {
+    let (mut ctx, schedule) = setup().await;
+    let intruder = ctx.funded_keypair().await;
+    let treasury = ctx.create_token_account(&schedule.mint, &ctx.payer()).await;
+
+    let revoke = revoke_ix(&schedule, intruder.pubkey(), treasury);
+    let result = ctx.process(&[revoke], &[&intruder]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn revoke_twice_fails() {
+    let (mut ctx, schedule) = setup().await;
+    let treasury = ctx.create_token_account(&schedule.mint, &ctx.payer()).await;
+
+    let revoke = revoke_ix(&schedule, ctx.payer(), treasury);
+    ctx.process(&[revoke], &[]).await.unwrap();
+    let revoke = revoke_ix(&schedule, ctx.payer(), treasury);
+    let result = ctx.process(&[revoke], &[]).await;
+    assert_error(result, ErrorCode::AlreadyRevoked);
+}
+
+#[tokio::test]
+async fn revoke_to_foreign_treasury_fails() {
+    let (mut ctx, schedule) = setup().await;
+    let outsider = Pubkey::new_unique();
+    let treasury = ctx.create_token_account(&schedule.mint, &outsider).await;
+
+    let revoke = revoke_ix(&schedule, ctx.payer(), treasury);
+    let result = ctx.process(&[revoke], &[]).await;
+    assert_error(result, ErrorCode::InvalidTreasury);
+    assert_eq!(
+        ctx.token_balance(&schedule.vesting_vault).await,
+        TOTAL_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn beneficiary_claims_into_any_destination() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    let destination = ctx
+        .create_token_account(&schedule.mint, &Pubkey::new_unique())
+        .await;
+
+    let mut accounts = schedule.claim_accounts(schedule.beneficiary.pubkey());
+    accounts.destination = destination;
+    let claim = instruction(
+        token_vesting::ID,
+        accounts,
+        ix::ClaimTokens { amount: None },
+    );
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(ctx.token_balance(&destination).await, TOTAL_AMOUNT);
+}
+
+fn partial_claim_ix(schedule: &Schedule, amount: u64) -> Instruction {
+    schedule.claim_ix(schedule.beneficiary.pubkey(), Some(amount))
+}
+
+#[tokio::test]
+async fn partial_claims_leave_the_rest_claimable() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+
+    let claim = partial_claim_ix(&schedule, 300_000);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.claimed_amount, 300_000);
+
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn partial_claim_above_claimable_fails() {
+    let (mut ctx, schedule) = setup().await;
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    ctx.warp_to_timestamp(vesting.start_time + DURATION / 2)
+        .await;
+
+    let claim = partial_claim_ix(&schedule, TOTAL_AMOUNT / 2 + 1);
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, ErrorCode::AmountExceedsClaimable);
+}
+
+#[tokio::test]
+async fn partial_claim_of_zero_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+
+    let claim = partial_claim_ix(&schedule, 0);
+    let result = ctx.process(&[claim], &[&schedule.beneficiary]).await;
+    assert_error(result, ErrorCode::ZeroAmount);
+}
+
+fn close_ix(schedule: &Schedule, signer: Pubkey, receiver: Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::CloseVesting {
+            beneficiary: signer,
+            vesting_account: schedule.vesting_account,
+            mint: schedule.mint,
+            vesting_vault: schedule.vesting_vault,
+            receiver,
+            token_program: schedule.token_program,
+        },
+        ix::CloseVesting {},
+    )
+}
+
+#[tokio::test]
+async fn close_vesting_reclaims_rent_once_fully_claimed() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    let receiver = Pubkey::new_unique();
+    let rent = ctx
+        .get_account(&schedule.vesting_account)
+        .await
+        .unwrap()
+        .lamports
+        + ctx
+            .get_account(&schedule.vesting_vault)
+            .await
+            .unwrap()
+            .lamports;
+    let close = close_ix(&schedule, schedule.beneficiary.pubkey(), receiver);
+    ctx.process(&[close], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    assert!(ctx.get_account(&schedule.vesting_account).await.is_none());
+    assert!(ctx.get_account(&schedule.vesting_vault).await.is_none());
+    assert_eq!(ctx.get_account(&receiver).await.unwrap().lamports, rent);
+}
+
+#[tokio::test]
+async fn close_vesting_with_unclaimed_tokens_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    let claim = partial_claim_ix(&schedule, TOTAL_AMOUNT - 1);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    let close = close_ix(&schedule, schedule.beneficiary.pubkey(), ctx.payer());
+    let result = ctx.process(&[close], &[&schedule.beneficiary]).await;
+    assert_error(result, ErrorCode::VestingNotComplete);
+}
+
+#[tokio::test]
+async fn close_vesting_by_other_signer_fails() {
+    let (mut ctx, schedule) = setup().await;
+    ctx.warp_forward(DURATION).await;
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    let intruder = ctx.funded_keypair().await;
+
+    let close = close_ix(&schedule, intruder.pubkey(), intruder.pubkey());
+    let result = ctx.process(&[close], &[&intruder]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+fn transfer_ix(schedule: &Schedule, signer: Pubkey, new_beneficiary: Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::UpdateBeneficiary {
+            beneficiary: signer,
+            vesting_account: schedule.vesting_account,
+        },
+        ix::TransferBeneficiary { new_beneficiary },
+    )
+}
+
+fn accept_ix(schedule: &Schedule, signer: Pubkey) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::AcceptBeneficiary {
+            new_beneficiary: signer,
+            vesting_account: schedule.vesting_account,
+        },
+        ix::AcceptBeneficiary {},
+    )
+}
+
+fn set_delegate_ix(schedule: &Schedule, claim_delegate: Option<Pubkey>) -> Instruction {
+    instruction(
+        token_vesting::ID,
+        accounts::UpdateBeneficiary {
+            beneficiary: schedule.beneficiary.pubkey(),
+            vesting_account: schedule.vesting_account,
+        },
+        ix::SetClaimDelegate { claim_delegate },
+    )
+}
+
+#[tokio::test]
+async fn transferred_schedule_is_claimed_by_new_beneficiary() {
+    let (mut ctx, mut schedule) = setup().await;
+    let old_beneficiary = schedule.beneficiary.insecure_clone();
+    let new_beneficiary = ctx.funded_keypair().await;
+
+    let transfer = transfer_ix(
+        &schedule,
+        old_beneficiary.pubkey(),
+        new_beneficiary.pubkey(),
+    );
+    ctx.process(&[transfer], &[&old_beneficiary]).await.unwrap();
+    let accept = accept_ix(&schedule, new_beneficiary.pubkey());
+    ctx.process(&[accept], &[&new_beneficiary]).await.unwrap();
+
+    let vesting: VestingAccount = ctx.fetch(&schedule.vesting_account).await;
+    assert_eq!(vesting.beneficiary, new_beneficiary.pubkey());
+    assert_eq!(vesting.original_beneficiary, old_beneficiary.pubkey());
+    assert_eq!(vesting.pending_beneficiary, None);
+
+    ctx.warp_forward(DURATION).await;
+    let old_claim = schedule.claim_ix(old_beneficiary.pubkey(), None);
+    let result = ctx.process(&[old_claim], &[&old_beneficiary]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+
+    schedule.beneficiary_token_account = ctx
+        .create_token_account(&schedule.mint, &new_beneficiary.pubkey())
+        .await;
+    schedule.beneficiary = new_beneficiary;
+    let claim = schedule.claim_ix(schedule.beneficiary.pubkey(), None);
+    ctx.process(&[claim], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn transfer_by_other_signer_fails() {
+    let (mut ctx, schedule) = setup().await;
+    let intruder = ctx.funded_keypair().await;
+
+    let transfer = transfer_ix(&schedule, intruder.pubkey(), intruder.pubkey());
+    let result = ctx.process(&[transfer], &[&intruder]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn accept_by_other_signer_fails() {
+    let (mut ctx, schedule) = setup().await;
+    let intruder = ctx.funded_keypair().await;
+    let transfer = transfer_ix(
+        &schedule,
+        schedule.beneficiary.pubkey(),
+        Pubkey::new_unique(),
+    );
+    ctx.process(&[transfer], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+
+    let accept = accept_ix(&schedule, intruder.pubkey());
+    let result = ctx.process(&[accept], &[&intruder]).await;
+    assert_error(result, ErrorCode::NotPendingBeneficiary);
+}
+
+#[tokio::test]
+async fn claim_delegate_claims_into_beneficiary_ata() {
+    let (mut ctx, mut schedule) = setup().await;
+    let delegate = ctx.funded_keypair().await;
+    let set_delegate = set_delegate_ix(&schedule, Some(delegate.pubkey()));
+    ctx.process(&[set_delegate], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    ctx.warp_forward(DURATION).await;
+
+    // Another account of the beneficiary is refused
+    let claim = schedule.claim_ix(delegate.pubkey(), None);
+    let result = ctx.process(&[claim], &[&delegate]).await;
+    assert_error(result, ErrorCode::InvalidDestination);
+
+    schedule.beneficiary_token_account = ctx
+        .create_associated_token_account(&schedule.mint, &schedule.beneficiary.pubkey())
+        .await;
+    let claim = schedule.claim_ix(delegate.pubkey(), None);
+    ctx.process(&[claim], &[&delegate]).await.unwrap();
+    assert_eq!(
+        ctx.token_balance(&schedule.beneficiary_token_account).await,
+        TOTAL_AMOUNT
+    );
+}
+
+#[tokio::test]
+async fn cleared_claim_delegate_cannot_claim() {
+    let (mut ctx, schedule) = setup().await;
+    let delegate = ctx.funded_keypair().await;
+    let set_delegate = set_delegate_ix(&schedule, Some(delegate.pubkey()));
+    ctx.process(&[set_delegate], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    let clear_delegate = set_delegate_ix(&schedule, None);
+    ctx.process(&[clear_delegate], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    ctx.warp_forward(DURATION).await;
+
+    let claim = schedule.claim_ix(delegate.pubkey(), None);
+    let result = ctx.process(&[claim], &[&delegate]).await;
+    assert_error(result, ErrorCode::Unauthorized);
+}
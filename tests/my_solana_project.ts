@@ -1,16 +1,74 @@
-import * as anchor from "@project-serum/anchor";
-import { Program } from "@project-serum/anchor";
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert } from "chai";
 import { MySolanaProject } from "../target/types/my_solana_project";
 
 describe("my_solana_project", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
   const program = anchor.workspace.MySolanaProject as Program<MySolanaProject>;
+  const authority = provider.wallet.publicKey;
+  const name = "orders";
+
+  const [registry] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("registry")],
+    program.programId
+  );
+  const [baseAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("counter"), authority.toBuffer(), Buffer.from(name)],
+    program.programId
+  );
 
   it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
+    await program.methods.initializeRegistry().accounts({ registry }).rpc();
+    const tx = await program.methods
+      .initialize(name)
+      .accounts({ baseAccount, registry, authority })
+      .rpc();
     console.log("Your transaction signature", tx);
+
+    const counter = await program.account.baseAccount.fetch(baseAccount);
+    assert.equal(counter.counter.toNumber(), 0);
+    assert.ok(counter.authority.equals(authority));
+    const registryState = await program.account.counterRegistry.fetch(registry);
+    assert.equal(registryState.counterCount.toNumber(), 1);
+  });
+
+  it("Updates the counter", async () => {
+    await program.methods.increment().accounts({ baseAccount, authority }).rpc();
+    await program.methods.incrementBy(new anchor.BN(5)).accounts({ baseAccount, authority }).rpc();
+    await program.methods.decrement().accounts({ baseAccount, authority }).rpc();
+    let counter = await program.account.baseAccount.fetch(baseAccount);
+    assert.equal(counter.counter.toNumber(), 5);
+
+    await program.methods.reset().accounts({ baseAccount, authority }).rpc();
+    counter = await program.account.baseAccount.fetch(baseAccount);
+    assert.equal(counter.counter.toNumber(), 0);
+  });
+
+  it("Issues sequential ids", async () => {
+    const first = await program.methods
+      .nextId()
+      .accounts({ baseAccount, authority })
+      .rpc();
+    const second = await program.methods
+      .nextId()
+      .accounts({ baseAccount, authority })
+      .rpc();
+    assert.notEqual(first, second);
+
+    const counter = await program.account.baseAccount.fetch(baseAccount);
+    assert.equal(counter.counter.toNumber(), 2);
+  });
+
+  it("Closes the counter", async () => {
+    await program.methods
+      .closeCounter()
+      .accounts({ baseAccount, registry, authority })
+      .rpc();
+    const registryState = await program.account.counterRegistry.fetch(registry);
+    assert.equal(registryState.counterCount.toNumber(), 0);
   });
 });